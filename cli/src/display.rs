@@ -0,0 +1,226 @@
+//! Human-readable rendering of program accounts.
+
+use std::fmt::Write;
+
+use anchor_lang::prelude::Pubkey;
+use iterative_dao::{
+    enums::{OptionSelection, ProposalState, VoteMutability, VotingStrategy, WeightSource},
+    state::{Governor, LateVoteExtension, Proposal, ProposalInstruction, VotingSchedule},
+};
+
+pub fn proposal_state(state: &ProposalState) -> &'static str {
+    match state {
+        ProposalState::Draft => "Draft",
+        ProposalState::Active => "Active",
+        ProposalState::Succeeded => "Succeeded",
+        ProposalState::Queued => "Queued",
+        ProposalState::Executed => "Executed",
+        ProposalState::Rejected => "Rejected",
+        ProposalState::Canceled => "Canceled",
+    }
+}
+
+pub fn governor(address: &Pubkey, governor: &Governor) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Governor          {address}");
+    let _ = writeln!(out, "  Smart wallet    {}", governor.smart_wallet);
+    let _ = writeln!(out, "  Electorate      {}", governor.electorate);
+    let _ = writeln!(out, "  Governance mint {}", governor.governance_mint);
+    let _ = writeln!(out, "  Vote threshold  {}%", governor.vote_threshold);
+    let _ = writeln!(out, "  Timelock delay  {}", duration(governor.timelock_delay));
+    let _ = writeln!(out, "  Voting          {}", voting_strategy(&governor.voting_strategy));
+    let _ = writeln!(out, "  Weight source   {}", weight_source(&governor.weight_source));
+    let _ = writeln!(out, "  Vote changes    {}", vote_mutability(&governor.vote_mutability));
+    let _ = writeln!(out, "  Late votes      {}", late_vote_extension(governor.late_vote_extension.as_ref()));
+    let _ = writeln!(out, "  Voting period   {}", voting_schedule(&governor.voting_schedule));
+    if governor.voting_schedule.review_period > 0 {
+        let _ = writeln!(out, "  Review period   {}", duration(governor.voting_schedule.review_period));
+    }
+    let _ = writeln!(out, "  Categories      {}", governor.category_count);
+    let _ = writeln!(out, "  Proposals       {}", governor.proposal_count);
+    let _ = writeln!(out, "  Voters          {}", governor.voters.len());
+    for voter in &governor.voters {
+        let _ = writeln!(out, "    {} weight {}", voter.pubkey, voter.weight);
+    }
+    out
+}
+
+pub fn proposal(address: &Pubkey, proposal: &Proposal) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Proposal #{}       {address}", proposal.proposal_id);
+    let _ = writeln!(out, "  State           {}", proposal_state(&proposal.state));
+    let _ = writeln!(out, "  Proposer        {}", proposal.proposer);
+    if let Some(category) = proposal.category {
+        let emergency = if proposal.emergency { " (emergency)" } else { "" };
+        let _ = writeln!(out, "  Category        {category}{emergency}");
+    }
+    let _ = writeln!(out, "  Vote threshold  {}%", proposal.vote_threshold);
+    if proposal.quorum > 0 {
+        let _ = writeln!(out, "  Quorum          {}", proposal.quorum);
+    }
+    if proposal.activated_at > 0 {
+        let _ = writeln!(out, "  Activated at    {}", proposal.activated_at);
+        if proposal.review_period > 0 {
+            let _ = writeln!(
+                out,
+                "  Voting starts   {} ({} review)",
+                proposal.voting_starts_at().unwrap_or(i64::MAX),
+                duration(proposal.review_period)
+            );
+        }
+        let extended = match proposal.voting_extension {
+            0 => String::new(),
+            seconds => format!(", extended by {}", duration(seconds)),
+        };
+        let _ = writeln!(
+            out,
+            "  Voting ends     {} ({}{extended})",
+            proposal.voting_ends_at().unwrap_or(i64::MAX),
+            duration(proposal.voting_period)
+        );
+        if proposal.is_secret_ballot() {
+            let _ = writeln!(
+                out,
+                "  Reveal ends     {} ({})",
+                proposal.counting_starts_at().unwrap_or(i64::MAX),
+                duration(proposal.reveal_period)
+            );
+        }
+    }
+    if proposal.voting_strategy != VotingStrategy::Linear {
+        let _ = writeln!(out, "  Voting          {}", voting_strategy(&proposal.voting_strategy));
+    }
+    if proposal.queued_at > 0 {
+        let _ = writeln!(out, "  Queued at       {}", proposal.queued_at);
+        let _ = writeln!(out, "  Executable at   {}", proposal.ready_to_execute_at);
+    }
+    if proposal.is_multiple_choice() {
+        let _ = writeln!(out, "  Selection       {}", option_selection(&proposal.selection));
+        let _ = writeln!(out, "  Abstained       {}", proposal.abstain_votes);
+        if proposal.selection == OptionSelection::InstantRunoff {
+            let _ = writeln!(
+                out,
+                "  Tally round     {} ({} of {} ballots counted)",
+                proposal.tally_round, proposal.tallied_ballots, proposal.ranked_ballots
+            );
+        }
+        for (index, option) in proposal.options.iter().enumerate() {
+            let winner = if proposal.winning_option == Some(index as u8) {
+                " (winner)"
+            } else if proposal.eliminated_options & (1 << index) != 0 {
+                " (eliminated)"
+            } else {
+                ""
+            };
+            let _ = writeln!(out, "  Option {index}        {} - {} votes{winner}", option.label, option.votes);
+            instructions(&mut out, &option.instructions);
+        }
+    } else {
+        let _ = writeln!(
+            out,
+            "  Votes           for {} / against {} / abstain {}",
+            proposal.for_votes, proposal.against_votes, proposal.abstain_votes
+        );
+        let _ = writeln!(out, "  Instructions    {}", proposal.instructions.len());
+        instructions(&mut out, &proposal.instructions);
+    }
+    out
+}
+
+fn instructions(out: &mut String, instructions: &[ProposalInstruction]) {
+    for (index, ix) in instructions.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "    {index}: {} ({} accounts, {} bytes)",
+            ix.program_id,
+            ix.accounts.len(),
+            ix.data.len()
+        );
+    }
+}
+
+fn option_selection(selection: &OptionSelection) -> &'static str {
+    match selection {
+        OptionSelection::Plurality => "plurality",
+        OptionSelection::Threshold => "threshold",
+        OptionSelection::InstantRunoff => "instant-runoff",
+    }
+}
+
+fn voting_strategy(strategy: &VotingStrategy) -> &'static str {
+    match strategy {
+        VotingStrategy::Linear => "linear",
+        VotingStrategy::Quadratic => "quadratic",
+    }
+}
+
+fn weight_source(source: &WeightSource) -> String {
+    match source {
+        WeightSource::Declared => "declared".to_string(),
+        WeightSource::Registry => "registry".to_string(),
+        WeightSource::Locker => "locker".to_string(),
+        WeightSource::AddIn { program } => format!("add-in {program}"),
+        WeightSource::Nft => "nft".to_string(),
+    }
+}
+
+fn vote_mutability(mutability: &VoteMutability) -> String {
+    match mutability {
+        VoteMutability::Free => "free".to_string(),
+        VoteMutability::Immutable => "immutable".to_string(),
+        VoteMutability::UntilCutoff { cutoff } => format!("until {} before voting ends", duration(*cutoff)),
+    }
+}
+
+fn late_vote_extension(rule: Option<&LateVoteExtension>) -> String {
+    match rule {
+        None => "never extend voting".to_string(),
+        Some(rule) => format!(
+            "extend by {} if cast in the last {}, up to {}",
+            duration(rule.extension),
+            duration(rule.window),
+            duration(rule.max_extension)
+        ),
+    }
+}
+
+fn voting_schedule(schedule: &VotingSchedule) -> String {
+    let max = match schedule.max_voting_period {
+        i64::MAX => "no limit".to_string(),
+        max => duration(max),
+    };
+    format!(
+        "{} by default, {} to {max}",
+        duration(schedule.default_voting_period),
+        duration(schedule.min_voting_period)
+    )
+}
+
+/// One line per proposal, for listings.
+pub fn proposal_summary(address: &Pubkey, proposal: &Proposal) -> String {
+    if proposal.is_multiple_choice() {
+        let votes: Vec<String> = proposal.options.iter().map(|o| o.votes.to_string()).collect();
+        return format!(
+            "#{:<4} {:<10} options {:<26} {address}",
+            proposal.proposal_id,
+            proposal_state(&proposal.state),
+            votes.join(" / "),
+        );
+    }
+    format!(
+        "#{:<4} {:<10} for {:<8} against {:<8} abstain {:<8} {address}",
+        proposal.proposal_id,
+        proposal_state(&proposal.state),
+        proposal.for_votes,
+        proposal.against_votes,
+        proposal.abstain_votes,
+    )
+}
+
+fn duration(seconds: i64) -> String {
+    match seconds {
+        s if s % 86_400 == 0 && s != 0 => format!("{}d", s / 86_400),
+        s if s % 3_600 == 0 && s != 0 => format!("{}h", s / 3_600),
+        s => format!("{s}s"),
+    }
+}
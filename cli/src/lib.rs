@@ -0,0 +1,9 @@
+//! Library half of the `iterative-dao` command-line tool.
+//!
+//! The binary in `main.rs` only parses arguments and dispatches; signing,
+//! proposal files and output formatting live here so they can be tested.
+
+pub mod display;
+pub mod proposal_file;
+pub mod signer;
+pub mod transaction;
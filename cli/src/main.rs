@@ -0,0 +1,660 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use iterative_dao::{
+    enums::{VoteMutability, VotingStrategy, WeightSource},
+    state::{LateVoteExtension, ProposalCategoryConfig, VotingSchedule},
+};
+use iterative_dao_cli::{
+    display,
+    proposal_file::{self, ProposalSpec},
+    signer::{LoadedSigner, SignerSource},
+    transaction::{self, Mode, Outcome},
+};
+use iterative_dao_client::{accounts, instructions, instructions::LockAccounts, pda};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, hash::Hash, instruction::Instruction, pubkey::Pubkey};
+
+/// Administer iterative-dao governors from the command line.
+#[derive(Parser)]
+#[command(name = "iterative-dao", version)]
+struct Cli {
+    /// RPC endpoint of the cluster.
+    #[arg(short, long, global = true, env = "ITERATIVE_DAO_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Fee payer; also pays rent for new accounts.
+    #[arg(short, long, global = true, default_value = "~/.config/solana/id.json")]
+    keypair: SignerSource,
+    /// Smart wallet controlling the governor, as a keypair file or a pubkey.
+    #[arg(short = 'w', long, global = true)]
+    smart_wallet: Option<SignerSource>,
+    /// Sign with the keypairs given and print the transaction instead of
+    /// sending it; nothing is read from the cluster.
+    #[arg(long, global = true, requires = "blockhash")]
+    sign_only: bool,
+    /// Recent blockhash to sign against; required with --sign-only.
+    #[arg(long, global = true, requires = "sign_only")]
+    blockhash: Option<Hash>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum StrategyArg {
+    /// One vote per unit of voting power.
+    Linear,
+    /// Square root of voting power.
+    Quadratic,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SourceArg {
+    /// Weight given when voting, plus escrowed and locked tokens.
+    Declared,
+    /// Registered voter weight.
+    Registry,
+    /// Escrowed and locked tokens only.
+    Locker,
+    /// A voter-weight add-in program.
+    AddIn,
+    /// NFTs of configured collections.
+    Nft,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MutabilityArg {
+    /// Changeable while the proposal is active.
+    Free,
+    /// Final once cast.
+    Immutable,
+    /// Changeable until a cutoff before voting ends.
+    UntilCutoff,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the governor for the smart wallet.
+    InitGovernor {
+        #[arg(long)]
+        vote_threshold: u8,
+        #[arg(long)]
+        timelock_delay: i64,
+        #[arg(long)]
+        electorate: Pubkey,
+        #[arg(long)]
+        governance_mint: Pubkey,
+    },
+    /// Register a voter with a fixed weight.
+    AddVoter { voter: Pubkey, weight: u64 },
+    /// Choose how votes are counted on proposals created from now on.
+    SetVotingStrategy { strategy: StrategyArg },
+    /// Choose where votes read voting power from.
+    SetWeightSource {
+        source: SourceArg,
+        /// Add-in program that owns voters' `VoterWeightRecord` accounts.
+        #[arg(long, required_if_eq("source", "add-in"))]
+        program: Option<Pubkey>,
+    },
+    /// Choose whether cast votes can be changed or withdrawn.
+    SetVoteMutability {
+        mutability: MutabilityArg,
+        /// Seconds before voting ends after which votes are final.
+        #[arg(long, required_if_eq("mutability", "until-cutoff"))]
+        cutoff: Option<i64>,
+    },
+    /// Extend voting when a late vote changes the outcome, or stop doing so.
+    SetLateVoteExtension {
+        /// Seconds before voting ends in which a vote can extend it.
+        #[arg(long, required_unless_present = "off")]
+        window: Option<i64>,
+        /// Seconds added by each such vote.
+        #[arg(long, required_unless_present = "off")]
+        extension: Option<i64>,
+        /// Most seconds a proposal can be extended by in total.
+        #[arg(long, required_unless_present = "off")]
+        max_extension: Option<i64>,
+        #[arg(long, conflicts_with_all = ["window", "extension", "max_extension"])]
+        off: bool,
+    },
+    /// Bound the voting period of every proposal, set the period used when
+    /// activation names none, and delay voting after activation.
+    SetVotingSchedule {
+        #[arg(long)]
+        min_voting_period: i64,
+        /// Defaults to no upper bound.
+        #[arg(long, default_value_t = i64::MAX)]
+        max_voting_period: i64,
+        #[arg(long)]
+        default_voting_period: i64,
+        /// Seconds from activation until voting opens.
+        #[arg(long, default_value_t = 0)]
+        review_period: i64,
+    },
+    /// Set the voting power of each NFT in a Metaplex collection; zero
+    /// stops the collection from voting.
+    ConfigureNftCollection {
+        collection: Pubkey,
+        #[arg(long)]
+        weight: u64,
+    },
+    /// Create or replace a proposal category with its own voting terms.
+    ConfigureProposalCategory {
+        id: u8,
+        #[arg(long)]
+        name: String,
+        /// Percentage of votes cast needed to pass.
+        #[arg(long)]
+        threshold: u8,
+        /// Votes that must be cast, abstentions included, for a result.
+        #[arg(long, default_value_t = 0)]
+        quorum: u64,
+        /// With the maximum, must lie within the governor's voting schedule.
+        #[arg(long)]
+        min_voting_period: i64,
+        #[arg(long)]
+        max_voting_period: i64,
+        #[arg(long)]
+        timelock_delay: i64,
+        /// Programs its proposals may call; repeat for each.
+        #[arg(long = "program", required = true)]
+        programs: Vec<Pubkey>,
+        /// Let its proposals be fast-tracked once votes for them alone meet
+        /// the quorum and threshold.
+        #[arg(long)]
+        emergency: bool,
+    },
+    /// Create a draft proposal from a JSON or TOML instruction file, which
+    /// may instead list labelled options for a multiple-choice vote.
+    CreateProposal {
+        #[arg(long)]
+        proposer: SignerSource,
+        instructions: PathBuf,
+        /// Category whose terms the proposal is voted under.
+        #[arg(long)]
+        category: Option<u8>,
+        /// The governor's current proposal count; read from the cluster
+        /// unless given, and required with --sign-only.
+        #[arg(long)]
+        proposal_id: Option<u64>,
+    },
+    /// Open a draft proposal for voting.
+    ActivateProposal {
+        proposal_id: u64,
+        /// Defaults to the governor's default voting period, within the proposal's bounds.
+        #[arg(long)]
+        voting_period: Option<i64>,
+        /// Make it a secret ballot, revealed for this long after voting.
+        #[arg(long)]
+        reveal_period: Option<i64>,
+    },
+    /// Cancel a proposal that is still in draft.
+    CancelProposal {
+        proposal_id: u64,
+        #[arg(long)]
+        proposer: SignerSource,
+    },
+    /// Close voting and record the outcome.
+    FinaliseProposal {
+        proposal_id: u64,
+        /// Finalise before voting ends; fails unless the outcome is already decided.
+        #[arg(long)]
+        early: bool,
+        /// Pass an emergency proposal once votes for it meet its quorum and threshold.
+        #[arg(long, conflicts_with = "early")]
+        fast_track: bool,
+    },
+    /// Count ranked ballots of an instant-runoff proposal until it is decided.
+    TallyRankedVotes {
+        proposal_id: u64,
+        /// Vote accounts passed per transaction.
+        #[arg(long, default_value_t = 20)]
+        batch: usize,
+    },
+    /// Start the timelock on a succeeded proposal.
+    QueueProposal { proposal_id: u64 },
+    /// Execute a queued proposal once its timelock has passed.
+    ExecuteProposal { proposal_id: u64 },
+    /// Lock governance tokens, optionally with a vesting schedule.
+    LockTokens {
+        #[command(flatten)]
+        accounts: TokenArgs,
+        #[arg(long)]
+        amount: u64,
+        #[arg(long)]
+        duration: i64,
+        #[arg(long)]
+        lock_id: u64,
+        /// Release linearly after this many seconds instead of all at the end.
+        #[arg(long)]
+        cliff: Option<i64>,
+    },
+    /// Return an expired lock's tokens to the user.
+    WithdrawTokens {
+        #[command(flatten)]
+        accounts: TokenArgs,
+        #[arg(long)]
+        lock_id: u64,
+    },
+    /// Claim the released part of a vesting lock.
+    ClaimVested {
+        #[command(flatten)]
+        accounts: TokenArgs,
+        #[arg(long)]
+        lock_id: u64,
+    },
+    /// Create the locker for a mint.
+    CreateLocker {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        multiplier: u64,
+        #[arg(long)]
+        min_duration: i64,
+        #[arg(long)]
+        max_duration: i64,
+    },
+    /// Escrow tokens in the locker for their mint.
+    CreateEscrow {
+        #[command(flatten)]
+        accounts: TokenArgs,
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        amount: u64,
+        #[arg(long)]
+        duration: i64,
+        #[arg(long)]
+        escrow_id: u64,
+    },
+    /// Return an ended escrow's tokens to the user.
+    WithdrawEscrow {
+        #[command(flatten)]
+        accounts: TokenArgs,
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        escrow_id: u64,
+    },
+    /// Print the governor of the smart wallet.
+    ShowGovernor,
+    /// Print one proposal of the governor.
+    ShowProposal { proposal_id: u64 },
+    /// Print a line per proposal of the governor.
+    ListProposals,
+    /// Add signatures to a `--sign-only` transaction and send it once complete.
+    Submit {
+        transaction: String,
+        #[arg(long = "signer")]
+        signers: Vec<SignerSource>,
+    },
+}
+
+#[derive(Args)]
+struct TokenArgs {
+    /// Token owner, as a keypair file or a pubkey.
+    #[arg(long)]
+    user: SignerSource,
+    #[arg(long)]
+    user_token_account: Pubkey,
+    #[arg(long)]
+    escrow_token_account: Pubkey,
+}
+
+struct Session {
+    rpc: RpcClient,
+    fee_payer: LoadedSigner,
+    smart_wallet: Option<LoadedSigner>,
+    mode: Mode,
+}
+
+impl Session {
+    fn smart_wallet(&self) -> Result<&LoadedSigner> {
+        self.smart_wallet.as_ref().context("--smart-wallet is required for this command")
+    }
+
+    fn governor(&self) -> Result<Pubkey> {
+        Ok(pda::find_governor_address(&self.smart_wallet()?.pubkey).0)
+    }
+
+    fn proposal(&self, proposal_id: u64) -> Result<Pubkey> {
+        Ok(pda::find_proposal_address(&self.governor()?, proposal_id).0)
+    }
+
+    fn send(&self, instructions: &[Instruction], signers: &[&LoadedSigner]) -> Result<()> {
+        report(transaction::process(&self.rpc, instructions, &self.fee_payer, signers, &self.mode)?);
+        Ok(())
+    }
+
+    /// Sends an instruction that the smart wallet must sign.
+    fn send_as_wallet(&self, ix: Instruction, others: &[&LoadedSigner]) -> Result<()> {
+        let mut signers = vec![self.smart_wallet()?];
+        signers.extend_from_slice(others);
+        self.send(&[ix], &signers)
+    }
+
+    fn lock_accounts(&self, args: &TokenArgs, user: &LoadedSigner) -> Result<LockAccounts> {
+        Ok(LockAccounts {
+            smart_wallet: self.smart_wallet()?.pubkey,
+            user: user.pubkey,
+            user_token_account: args.user_token_account,
+            escrow_token_account: args.escrow_token_account,
+        })
+    }
+}
+
+fn report(outcome: Outcome) {
+    match outcome {
+        Outcome::Sent(signature) => println!("Signature: {signature}"),
+        Outcome::Signed(signed) => {
+            println!("Transaction: {}", signed.encoded);
+            for (pubkey, signature) in &signed.present {
+                println!("Signed by {pubkey}: {signature}");
+            }
+            for pubkey in &signed.absent {
+                println!("Missing signature from {pubkey}");
+            }
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let session = Session {
+        rpc: RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed()),
+        fee_payer: cli.keypair.load()?,
+        smart_wallet: cli.smart_wallet.as_ref().map(SignerSource::load).transpose()?,
+        mode: match cli.blockhash {
+            Some(blockhash) if cli.sign_only => Mode::SignOnly { blockhash },
+            _ => Mode::Send,
+        },
+    };
+    run(&session, cli.command)
+}
+
+fn run(session: &Session, command: Command) -> Result<()> {
+    let payer = session.fee_payer.pubkey;
+    match command {
+        Command::InitGovernor { vote_threshold, timelock_delay, electorate, governance_mint } => {
+            let wallet = session.smart_wallet()?.pubkey;
+            let ix = instructions::init_governor(
+                wallet,
+                payer,
+                vote_threshold,
+                timelock_delay,
+                electorate,
+                governance_mint,
+            );
+            session.send_as_wallet(ix, &[])
+        }
+        Command::AddVoter { voter, weight } => {
+            let ix = instructions::add_voter(session.smart_wallet()?.pubkey, voter, weight);
+            session.send_as_wallet(ix, &[])
+        }
+        Command::SetVotingStrategy { strategy } => {
+            let strategy = match strategy {
+                StrategyArg::Linear => VotingStrategy::Linear,
+                StrategyArg::Quadratic => VotingStrategy::Quadratic,
+            };
+            let ix = instructions::set_voting_strategy(session.smart_wallet()?.pubkey, strategy);
+            session.send_as_wallet(ix, &[])
+        }
+        Command::SetWeightSource { source, program } => {
+            if source != SourceArg::AddIn && program.is_some() {
+                bail!("--program only applies to the add-in source");
+            }
+            let source = match source {
+                SourceArg::Declared => WeightSource::Declared,
+                SourceArg::Registry => WeightSource::Registry,
+                SourceArg::Locker => WeightSource::Locker,
+                SourceArg::AddIn => WeightSource::AddIn { program: program.context("--program is required")? },
+                SourceArg::Nft => WeightSource::Nft,
+            };
+            let ix = instructions::set_weight_source(session.smart_wallet()?.pubkey, source);
+            session.send_as_wallet(ix, &[])
+        }
+        Command::SetVoteMutability { mutability, cutoff } => {
+            if mutability != MutabilityArg::UntilCutoff && cutoff.is_some() {
+                bail!("--cutoff only applies to until-cutoff");
+            }
+            let mutability = match mutability {
+                MutabilityArg::Free => VoteMutability::Free,
+                MutabilityArg::Immutable => VoteMutability::Immutable,
+                MutabilityArg::UntilCutoff => {
+                    VoteMutability::UntilCutoff { cutoff: cutoff.context("--cutoff is required")? }
+                }
+            };
+            let ix = instructions::set_vote_mutability(session.smart_wallet()?.pubkey, mutability);
+            session.send_as_wallet(ix, &[])
+        }
+        Command::SetLateVoteExtension { window, extension, max_extension, off } => {
+            let rule = match (window, extension, max_extension) {
+                (Some(window), Some(extension), Some(max_extension)) if !off => {
+                    Some(LateVoteExtension { window, extension, max_extension })
+                }
+                _ => None,
+            };
+            let ix = instructions::set_late_vote_extension(session.smart_wallet()?.pubkey, rule);
+            session.send_as_wallet(ix, &[])
+        }
+        Command::SetVotingSchedule { min_voting_period, max_voting_period, default_voting_period, review_period } => {
+            let voting_schedule =
+                VotingSchedule { min_voting_period, max_voting_period, default_voting_period, review_period };
+            let ix = instructions::set_voting_schedule(session.smart_wallet()?.pubkey, voting_schedule);
+            session.send_as_wallet(ix, &[])
+        }
+        Command::ConfigureNftCollection { collection, weight } => {
+            let ix = instructions::configure_nft_collection(session.smart_wallet()?.pubkey, payer, collection, weight);
+            session.send_as_wallet(ix, &[])
+        }
+        Command::ConfigureProposalCategory {
+            id,
+            name,
+            threshold,
+            quorum,
+            min_voting_period,
+            max_voting_period,
+            timelock_delay,
+            programs,
+            emergency,
+        } => {
+            let config = ProposalCategoryConfig {
+                name,
+                vote_threshold: threshold,
+                quorum,
+                min_voting_period,
+                max_voting_period,
+                timelock_delay,
+                programs,
+                emergency,
+            };
+            let ix = instructions::configure_proposal_category(session.smart_wallet()?.pubkey, payer, id, config);
+            session.send_as_wallet(ix, &[])
+        }
+        Command::CreateProposal { proposer, instructions: path, category, proposal_id } => {
+            let proposer = proposer.load()?;
+            let governor_key = session.governor()?;
+            let proposal_id = match proposal_id {
+                Some(proposal_id) => proposal_id,
+                None if matches!(session.mode, Mode::SignOnly { .. }) => {
+                    bail!("--sign-only needs --proposal-id, the governor's current proposal count")
+                }
+                None => accounts::fetch::<iterative_dao::state::Governor>(&session.rpc, &governor_key)?.proposal_count,
+            };
+            let ix = match proposal_file::load(&path)? {
+                ProposalSpec::Instructions(ixs) => instructions::create_proposal(
+                    governor_key,
+                    proposal_id,
+                    proposer.pubkey,
+                    payer,
+                    category,
+                    ixs,
+                ),
+                ProposalSpec::Options { options, selection } => instructions::create_multiple_choice_proposal(
+                    governor_key,
+                    proposal_id,
+                    proposer.pubkey,
+                    payer,
+                    category,
+                    options,
+                    selection,
+                ),
+            };
+            println!("Proposal #{proposal_id} {}", session.proposal(proposal_id)?);
+            session.send(&[ix], &[&proposer])
+        }
+        Command::ActivateProposal { proposal_id, voting_period, reveal_period } => {
+            let smart_wallet = session.smart_wallet()?.pubkey;
+            let proposal = session.proposal(proposal_id)?;
+            let ix = match reveal_period {
+                Some(reveal_period) => {
+                    instructions::activate_secret_proposal(smart_wallet, proposal, voting_period, reveal_period)
+                }
+                None => instructions::activate_proposal(smart_wallet, proposal, voting_period),
+            };
+            session.send_as_wallet(ix, &[])
+        }
+        Command::CancelProposal { proposal_id, proposer } => {
+            let proposer = proposer.load()?;
+            let ix = instructions::cancel_proposal(
+                session.smart_wallet()?.pubkey,
+                session.proposal(proposal_id)?,
+                proposer.pubkey,
+            );
+            session.send_as_wallet(ix, &[&proposer])
+        }
+        Command::FinaliseProposal { proposal_id, early, fast_track } => {
+            let (smart_wallet, proposal) = (session.smart_wallet()?.pubkey, session.proposal(proposal_id)?);
+            let ix = if early {
+                instructions::finalise_proposal_early(smart_wallet, proposal)
+            } else if fast_track {
+                instructions::fast_track_proposal(smart_wallet, proposal)
+            } else {
+                instructions::finalise_proposal(smart_wallet, proposal)
+            };
+            session.send_as_wallet(ix, &[])
+        }
+        Command::TallyRankedVotes { proposal_id, batch } => {
+            if matches!(session.mode, Mode::SignOnly { .. }) {
+                bail!("tallying takes several dependent transactions and cannot be signed offline");
+            }
+            tally_ranked_votes(session, session.proposal(proposal_id)?, batch.max(1))
+        }
+        Command::QueueProposal { proposal_id } => {
+            let ix = instructions::queue_proposal(session.smart_wallet()?.pubkey, session.proposal(proposal_id)?);
+            session.send_as_wallet(ix, &[])
+        }
+        Command::ExecuteProposal { proposal_id } => {
+            let ix = instructions::execute_proposal(session.smart_wallet()?.pubkey, session.proposal(proposal_id)?);
+            session.send_as_wallet(ix, &[])
+        }
+        Command::LockTokens { accounts: args, amount, duration, lock_id, cliff } => {
+            let user = args.user.load()?;
+            let accs = session.lock_accounts(&args, &user)?;
+            let ix = match cliff {
+                Some(cliff) => instructions::lock_vesting_tokens(accs, payer, amount, cliff, duration, lock_id),
+                None => instructions::lock_tokens(accs, payer, amount, duration, lock_id),
+            };
+            session.send_as_wallet(ix, &[&user])
+        }
+        Command::WithdrawTokens { accounts: args, lock_id } => {
+            let user = args.user.load()?;
+            let ix = instructions::withdraw_tokens(session.lock_accounts(&args, &user)?, lock_id);
+            session.send_as_wallet(ix, &[&user])
+        }
+        Command::ClaimVested { accounts: args, lock_id } => {
+            let user = args.user.load()?;
+            let ix = instructions::claim_vested(session.lock_accounts(&args, &user)?, lock_id);
+            session.send_as_wallet(ix, &[&user])
+        }
+        Command::CreateLocker { mint, multiplier, min_duration, max_duration } => {
+            let ix = instructions::create_locker(
+                session.smart_wallet()?.pubkey,
+                mint,
+                payer,
+                multiplier,
+                min_duration,
+                max_duration,
+            );
+            session.send_as_wallet(ix, &[])
+        }
+        Command::CreateEscrow { accounts: args, mint, amount, duration, escrow_id } => {
+            let user = args.user.load()?;
+            let locker = pda::find_locker_address(&session.governor()?, &mint).0;
+            let ix = instructions::create_escrow(
+                session.lock_accounts(&args, &user)?,
+                locker,
+                payer,
+                amount,
+                duration,
+                escrow_id,
+            );
+            session.send_as_wallet(ix, &[&user])
+        }
+        Command::WithdrawEscrow { accounts: args, mint, escrow_id } => {
+            let user = args.user.load()?;
+            let locker = pda::find_locker_address(&session.governor()?, &mint).0;
+            let ix = instructions::withdraw_escrow(session.lock_accounts(&args, &user)?, locker, escrow_id);
+            session.send_as_wallet(ix, &[&user])
+        }
+        Command::ShowGovernor => {
+            let address = session.governor()?;
+            let governor = accounts::fetch(&session.rpc, &address)?;
+            print!("{}", display::governor(&address, &governor));
+            Ok(())
+        }
+        Command::ShowProposal { proposal_id } => {
+            let address = session.proposal(proposal_id)?;
+            let proposal = accounts::fetch(&session.rpc, &address)?;
+            print!("{}", display::proposal(&address, &proposal));
+            Ok(())
+        }
+        Command::ListProposals => {
+            let mut proposals = accounts::fetch_governor_proposals(&session.rpc, &session.governor()?)?;
+            proposals.sort_by_key(|(_, proposal)| proposal.proposal_id);
+            for (address, proposal) in &proposals {
+                println!("{}", display::proposal_summary(address, proposal));
+            }
+            Ok(())
+        }
+        Command::Submit { transaction, signers } => {
+            let signers = signers.iter().map(SignerSource::load).collect::<Result<Vec<_>>>()?;
+            let refs: Vec<&LoadedSigner> = signers.iter().collect();
+            report(transaction::submit(&session.rpc, &transaction, &refs)?);
+            Ok(())
+        }
+    }
+}
+
+/// Sends `tally_ranked_votes` batches until the proposal leaves `Active`.
+///
+/// Each round needs every ballot again, so uncounted votes are re-read
+/// after every pass rather than computed up front.
+fn tally_ranked_votes(session: &Session, address: Pubkey, batch: usize) -> Result<()> {
+    let mut progress = None;
+    loop {
+        let proposal: iterative_dao::state::Proposal = accounts::fetch(&session.rpc, &address)?;
+        if proposal.state != iterative_dao::enums::ProposalState::Active {
+            print!("{}", display::proposal(&address, &proposal));
+            return Ok(());
+        }
+        let position = (proposal.tally_round, proposal.tallied_ballots);
+        if progress == Some(position) {
+            bail!("tally is stuck in round {} with {} ballots counted", position.0, position.1);
+        }
+        progress = Some(position);
+
+        let pending: Vec<Pubkey> = accounts::fetch_proposal_votes(&session.rpc, &address)?
+            .into_iter()
+            .filter(|(_, vote)| !vote.ranking.is_empty() && vote.tallied_round != proposal.tally_round)
+            .map(|(key, _)| key)
+            .collect();
+        if pending.is_empty() {
+            // A fully counted round only needs closing.
+            session.send(&[instructions::tally_ranked_votes(address, &[])], &[])?;
+        }
+        for chunk in pending.chunks(batch) {
+            session.send(&[instructions::tally_ranked_votes(address, chunk)], &[])?;
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Proposal instruction files for `create-proposal`.
+//!
+//! Either JSON or TOML (chosen by file extension) with the same shape:
+//!
+//! ```toml
+//! [[instructions]]
+//! program_id = "11111111111111111111111111111111"
+//! data = "AgAAAOgDAAAAAAAA"          # base64, may be omitted
+//!
+//! [[instructions.accounts]]
+//! pubkey = "..."
+//! is_writable = true                # is_signer / is_writable default to false
+//! ```
+//!
+//! A multiple-choice proposal lists `options` instead, each with a label and
+//! its own (possibly empty) instruction list:
+//!
+//! ```toml
+//! selection = "threshold"           # "plurality" (default) or "instant-runoff"
+//!
+//! [[options]]
+//! label = "Fund in full"
+//!
+//! [[options.instructions]]
+//! program_id = "..."
+//! ```
+
+use std::{path::Path, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use iterative_dao::{
+    enums::OptionSelection,
+    state::{NewProposalOption, ProposalAccount, ProposalInstruction},
+};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProposalFile {
+    #[serde(default)]
+    instructions: Vec<InstructionSpec>,
+    #[serde(default)]
+    options: Vec<OptionSpec>,
+    selection: Option<SelectionSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OptionSpec {
+    label: String,
+    #[serde(default)]
+    instructions: Vec<InstructionSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum SelectionSpec {
+    Plurality,
+    Threshold,
+    InstantRunoff,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InstructionSpec {
+    program_id: String,
+    #[serde(default)]
+    accounts: Vec<AccountSpec>,
+    #[serde(default)]
+    data: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AccountSpec {
+    pubkey: String,
+    #[serde(default)]
+    is_signer: bool,
+    #[serde(default)]
+    is_writable: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Toml,
+}
+
+/// What a proposal file asks `create-proposal` to submit.
+pub enum ProposalSpec {
+    Instructions(Vec<ProposalInstruction>),
+    Options { options: Vec<NewProposalOption>, selection: OptionSelection },
+}
+
+pub fn load(path: &Path) -> Result<ProposalSpec> {
+    let format = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => Format::Toml,
+        Some("json") => Format::Json,
+        _ => bail!("{} must end in .json or .toml", path.display()),
+    };
+    let contents = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    parse(&contents, format).with_context(|| format!("parsing {}", path.display()))
+}
+
+pub fn parse(contents: &str, format: Format) -> Result<ProposalSpec> {
+    let file: ProposalFile = match format {
+        Format::Json => serde_json::from_str(contents)?,
+        Format::Toml => toml::from_str(contents)?,
+    };
+    if !file.options.is_empty() {
+        if !file.instructions.is_empty() {
+            bail!("list instructions under each option, not at the top level");
+        }
+        let options = file
+            .options
+            .into_iter()
+            .enumerate()
+            .map(|(index, option)| {
+                Ok(NewProposalOption {
+                    label: option.label,
+                    instructions: resolve_all(option.instructions).with_context(|| format!("option {index}"))?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        let selection = match file.selection {
+            Some(SelectionSpec::Threshold) => OptionSelection::Threshold,
+            Some(SelectionSpec::InstantRunoff) => OptionSelection::InstantRunoff,
+            Some(SelectionSpec::Plurality) | None => OptionSelection::Plurality,
+        };
+        return Ok(ProposalSpec::Options { options, selection });
+    }
+    if file.selection.is_some() {
+        bail!("selection only applies to proposals with options");
+    }
+    if file.instructions.is_empty() {
+        bail!("a proposal needs at least one instruction");
+    }
+    resolve_all(file.instructions).map(ProposalSpec::Instructions)
+}
+
+fn resolve_all(specs: Vec<InstructionSpec>) -> Result<Vec<ProposalInstruction>> {
+    specs
+        .into_iter()
+        .enumerate()
+        .map(|(index, spec)| spec.resolve().with_context(|| format!("instruction {index}")))
+        .collect()
+}
+
+impl InstructionSpec {
+    fn resolve(self) -> Result<ProposalInstruction> {
+        let accounts = self
+            .accounts
+            .into_iter()
+            .map(|account| {
+                Ok(ProposalAccount {
+                    pubkey: parse_pubkey(&account.pubkey)?,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(ProposalInstruction {
+            program_id: parse_pubkey(&self.program_id)?,
+            accounts,
+            data: STANDARD.decode(&self.data).context("data is not valid base64")?,
+        })
+    }
+}
+
+fn parse_pubkey(s: &str) -> Result<Pubkey> {
+    Pubkey::from_str(s).with_context(|| format!("invalid pubkey {s:?}"))
+}
@@ -0,0 +1,55 @@
+//! Signers named on the command line.
+
+use std::{path::PathBuf, str::FromStr};
+
+use anyhow::{anyhow, Context, Result};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+};
+
+/// A keypair file, or a bare public key whose signature will be added
+/// elsewhere (see `--sign-only` and the `submit` command).
+#[derive(Clone, Debug)]
+pub enum SignerSource {
+    Keypair(PathBuf),
+    Pubkey(Pubkey),
+}
+
+impl FromStr for SignerSource {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Ok(pubkey) = Pubkey::from_str(s) {
+            return Ok(SignerSource::Pubkey(pubkey));
+        }
+        Ok(SignerSource::Keypair(expand_home(s)))
+    }
+}
+
+impl SignerSource {
+    pub fn load(&self) -> Result<LoadedSigner> {
+        match self {
+            SignerSource::Pubkey(pubkey) => Ok(LoadedSigner { pubkey: *pubkey, keypair: None }),
+            SignerSource::Keypair(path) => {
+                let keypair = read_keypair_file(path)
+                    .map_err(|err| anyhow!("{err}"))
+                    .with_context(|| format!("reading keypair {}", path.display()))?;
+                Ok(LoadedSigner { pubkey: keypair.pubkey(), keypair: Some(keypair) })
+            }
+        }
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+pub struct LoadedSigner {
+    pub pubkey: Pubkey,
+    /// `None` when only the public key was given.
+    pub keypair: Option<Keypair>,
+}
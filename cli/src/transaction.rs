@@ -0,0 +1,111 @@
+//! Sending, offline signing and submitting of transactions.
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+    transaction::Transaction,
+};
+
+use crate::signer::LoadedSigner;
+
+/// How a built transaction leaves the tool.
+pub enum Mode {
+    /// Sign with every signer and send it to the cluster.
+    Send,
+    /// Sign with the keypairs at hand against `blockhash` and print the
+    /// transaction for `submit`, without contacting the cluster.
+    SignOnly { blockhash: Hash },
+}
+
+pub enum Outcome {
+    Sent(Signature),
+    Signed(SignedTransaction),
+}
+
+pub struct SignedTransaction {
+    /// Base64 of the bincode-serialised transaction.
+    pub encoded: String,
+    pub present: Vec<(Pubkey, Signature)>,
+    pub absent: Vec<Pubkey>,
+}
+
+/// Builds a transaction paid by `fee_payer` and handles it according to `mode`.
+pub fn process(
+    rpc: &RpcClient,
+    instructions: &[Instruction],
+    fee_payer: &LoadedSigner,
+    signers: &[&LoadedSigner],
+    mode: &Mode,
+) -> Result<Outcome> {
+    let mut tx = Transaction::new_with_payer(instructions, Some(&fee_payer.pubkey));
+    let keypairs: Vec<&dyn Signer> = std::iter::once(fee_payer)
+        .chain(signers.iter().copied())
+        .filter_map(|signer| signer.keypair.as_ref().map(|k| k as &dyn Signer))
+        .collect();
+
+    match mode {
+        Mode::Send => {
+            let blockhash = rpc.get_latest_blockhash()?;
+            tx.try_partial_sign(&keypairs, blockhash)?;
+            if let Some(missing) = absent_signers(&tx).first() {
+                bail!("no keypair for signer {missing}; use --sign-only and `submit` instead");
+            }
+            Ok(Outcome::Sent(rpc.send_and_confirm_transaction(&tx)?))
+        }
+        Mode::SignOnly { blockhash } => {
+            tx.try_partial_sign(&keypairs, *blockhash)?;
+            Ok(Outcome::Signed(signed(&tx)?))
+        }
+    }
+}
+
+/// Adds signatures to a transaction produced by `--sign-only` and, once
+/// every signer is present, sends it.
+pub fn submit(rpc: &RpcClient, encoded: &str, signers: &[&LoadedSigner]) -> Result<Outcome> {
+    let mut tx = decode(encoded)?;
+    let keypairs: Vec<&dyn Signer> = signers
+        .iter()
+        .filter_map(|signer| signer.keypair.as_ref().map(|k| k as &dyn Signer))
+        .collect();
+    if !keypairs.is_empty() {
+        let blockhash = tx.message.recent_blockhash;
+        tx.try_partial_sign(&keypairs, blockhash)?;
+    }
+    if !absent_signers(&tx).is_empty() {
+        return Ok(Outcome::Signed(signed(&tx)?));
+    }
+    Ok(Outcome::Sent(rpc.send_and_confirm_transaction(&tx)?))
+}
+
+pub fn decode(encoded: &str) -> Result<Transaction> {
+    let bytes = STANDARD.decode(encoded.trim()).context("transaction is not valid base64")?;
+    bincode::deserialize(&bytes).map_err(|err| anyhow!("transaction could not be decoded: {err}"))
+}
+
+fn signed(tx: &Transaction) -> Result<SignedTransaction> {
+    let signer_keys = &tx.message.account_keys[..tx.message.header.num_required_signatures as usize];
+    let (present, absent): (Vec<_>, Vec<_>) = signer_keys
+        .iter()
+        .zip(&tx.signatures)
+        .partition(|(_, signature)| **signature != Signature::default());
+    Ok(SignedTransaction {
+        encoded: STANDARD.encode(bincode::serialize(tx)?),
+        present: present.into_iter().map(|(key, sig)| (*key, *sig)).collect(),
+        absent: absent.into_iter().map(|(key, _)| *key).collect(),
+    })
+}
+
+fn absent_signers(tx: &Transaction) -> Vec<Pubkey> {
+    let signer_keys = &tx.message.account_keys[..tx.message.header.num_required_signatures as usize];
+    signer_keys
+        .iter()
+        .zip(&tx.signatures)
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(key, _)| *key)
+        .collect()
+}
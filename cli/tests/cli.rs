@@ -0,0 +1,226 @@
+use iterative_dao::{
+    enums::{OptionSelection, ProposalState, VotingStrategy},
+    state::{Proposal, ProposalAccount, ProposalInstruction},
+};
+use iterative_dao_cli::{
+    display,
+    proposal_file::{parse, Format, ProposalSpec},
+    signer::LoadedSigner,
+    transaction::{decode, process, submit, Mode, Outcome},
+};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+fn keypair_signer() -> LoadedSigner {
+    let keypair = Keypair::new();
+    LoadedSigner { pubkey: keypair.pubkey(), keypair: Some(keypair) }
+}
+
+/// Never contacted: every call below supplies its own blockhash.
+fn offline_rpc() -> RpcClient {
+    RpcClient::new("http://127.0.0.1:1".to_string())
+}
+
+#[test]
+fn parses_toml_and_json_proposal_files() {
+    let program = Pubkey::new_unique();
+    let account = Pubkey::new_unique();
+    let toml = format!(
+        r#"
+        [[instructions]]
+        program_id = "{program}"
+        data = "AQID"
+
+        [[instructions.accounts]]
+        pubkey = "{account}"
+        is_writable = true
+        "#
+    );
+    let json = format!(
+        r#"{{"instructions": [{{"program_id": "{program}", "data": "AQID",
+            "accounts": [{{"pubkey": "{account}", "is_writable": true}}]}}]}}"#
+    );
+
+    for parsed in [parse(&toml, Format::Toml).unwrap(), parse(&json, Format::Json).unwrap()] {
+        let ProposalSpec::Instructions(parsed) = parsed else { panic!("expected instructions") };
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].program_id, program);
+        assert_eq!(parsed[0].data, vec![1, 2, 3]);
+        assert_eq!(parsed[0].accounts[0].pubkey, account);
+        assert!(parsed[0].accounts[0].is_writable);
+        assert!(!parsed[0].accounts[0].is_signer);
+    }
+}
+
+#[test]
+fn rejects_invalid_proposal_files() {
+    assert!(parse(r#"{"instructions": []}"#, Format::Json).is_err());
+    assert!(parse(r#"{"instructions": [{"program_id": "not-a-key"}]}"#, Format::Json).is_err());
+    let program = Pubkey::new_unique();
+    let bad_data = format!(r#"{{"instructions": [{{"program_id": "{program}", "data": "%%"}}]}}"#);
+    assert!(parse(&bad_data, Format::Json).is_err());
+    assert!(parse(r#"{"instructions": [], "selection": "threshold"}"#, Format::Json).is_err());
+}
+
+#[test]
+fn parses_multiple_choice_proposal_files() {
+    let program = Pubkey::new_unique();
+    let toml = format!(
+        r#"
+selection = "threshold"
+
+[[options]]
+label = "Fund"
+
+[[options.instructions]]
+program_id = "{program}"
+
+[[options]]
+label = "Do nothing"
+"#
+    );
+
+    let ProposalSpec::Options { options, selection } = parse(&toml, Format::Toml).unwrap() else {
+        panic!("expected options");
+    };
+    assert_eq!(selection, OptionSelection::Threshold);
+    assert_eq!((options[0].label.as_str(), options[0].instructions.len()), ("Fund", 1));
+    assert_eq!(options[0].instructions[0].program_id, program);
+    assert!(options[1].instructions.is_empty());
+}
+
+#[test]
+fn sign_only_leaves_pubkey_signers_for_submit() {
+    let rpc = offline_rpc();
+    let fee_payer = keypair_signer();
+    let wallet = Keypair::new();
+    let wallet_pubkey_only = LoadedSigner { pubkey: wallet.pubkey(), keypair: None };
+    let ix = Instruction::new_with_bytes(
+        iterative_dao::ID,
+        &[0],
+        vec![AccountMeta::new_readonly(wallet.pubkey(), true)],
+    );
+    let mode = Mode::SignOnly { blockhash: Hash::new_unique() };
+
+    let Outcome::Signed(signed) = process(&rpc, &[ix], &fee_payer, &[&wallet_pubkey_only], &mode).unwrap() else {
+        panic!("sign-only must not send");
+    };
+    assert_eq!(signed.present.len(), 1);
+    assert_eq!(signed.present[0].0, fee_payer.pubkey);
+    assert_eq!(signed.absent, vec![wallet.pubkey()]);
+
+    // A second party that does not hold the wallet key adds nothing.
+    let Outcome::Signed(resigned) = submit(&rpc, &signed.encoded, &[]).unwrap() else {
+        panic!("incomplete transaction must not be sent");
+    };
+    assert_eq!(resigned.absent, vec![wallet.pubkey()]);
+
+    let mut tx = decode(&signed.encoded).unwrap();
+    let blockhash = tx.message.recent_blockhash;
+    tx.partial_sign(&[&wallet], blockhash);
+    tx.verify().unwrap();
+}
+
+/// Runs the binary against an RPC endpoint that is never listening.
+fn run_offline(args: &[&str]) -> std::process::Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_iterative-dao"))
+        .args(["--url", "http://127.0.0.1:1", "--keypair", &Pubkey::new_unique().to_string()])
+        .args(["--smart-wallet", &Pubkey::new_unique().to_string()])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn create_proposal_signs_offline_without_rpc() {
+    let path = std::env::temp_dir().join(format!("proposal-{}.json", Pubkey::new_unique()));
+    let program = Pubkey::new_unique();
+    std::fs::write(&path, format!(r#"{{"instructions": [{{"program_id": "{program}", "data": "AQ=="}}]}}"#)).unwrap();
+    let blockhash = Hash::new_unique().to_string();
+    let proposer = Pubkey::new_unique().to_string();
+    let create = ["create-proposal", "--proposer", &proposer, path.to_str().unwrap()];
+
+    let offline = ["--sign-only", "--blockhash", &blockhash];
+    let signed = run_offline(&[&offline[..], &create[..], &["--proposal-id", "4"]].concat());
+    let stdout = String::from_utf8(signed.stdout).unwrap();
+    assert!(signed.status.success(), "{}", String::from_utf8_lossy(&signed.stderr));
+    assert!(stdout.starts_with("Proposal #4 "));
+    assert!(stdout.contains("Transaction: "));
+
+    let without_id = run_offline(&[&offline[..], &create[..]].concat());
+    assert!(!without_id.status.success());
+    assert!(String::from_utf8_lossy(&without_id.stderr).contains("--proposal-id"));
+    let without_blockhash = run_offline(&[&["--sign-only"], &create[..], &["--proposal-id", "4"]].concat());
+    assert!(!without_blockhash.status.success());
+    assert!(String::from_utf8_lossy(&without_blockhash.stderr).contains("--blockhash"));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn renders_proposal() {
+    let proposal = Proposal {
+        governor: Pubkey::new_unique(),
+        proposer: Pubkey::new_unique(),
+        instructions: vec![ProposalInstruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![ProposalAccount { pubkey: Pubkey::new_unique(), is_signer: false, is_writable: true }],
+            data: vec![1, 2],
+        }],
+        state: ProposalState::Queued,
+        proposal_id: 7,
+        activated_at: 1_000,
+        voting_period: 86_400,
+        timelock_delay: 3_600,
+        queued_at: 90_000,
+        ready_to_execute_at: 93_600,
+        for_votes: 70,
+        against_votes: 30,
+        abstain_votes: 0,
+        payer: Pubkey::new_unique(),
+        finalised_at: 0,
+        options: vec![],
+        selection: OptionSelection::Plurality,
+        winning_option: None,
+        ranked_ballots: 0,
+        tally_round: 0,
+        tallied_ballots: 0,
+        eliminated_options: 0,
+        voting_strategy: VotingStrategy::Quadratic,
+        reveal_period: 0,
+        voting_extension: 0,
+        category: Some(2),
+        vote_threshold: 60,
+        quorum: 500,
+        min_voting_period: 3_600,
+        max_voting_period: 604_800,
+        emergency: false,
+        review_period: 0,
+        padding: [0; 2],
+    };
+    let address = Pubkey::new_unique();
+    let text = display::proposal(&address, &proposal);
+
+    assert!(text.starts_with("Proposal #7"));
+    assert!(text.contains("State           Queued"));
+    assert!(text.contains("Voting ends     87400 (1d)"));
+    assert!(text.contains("for 70 / against 30 / abstain 0"));
+    assert!(text.contains("(1 accounts, 2 bytes)"));
+    assert!(text.contains("Voting          quadratic"));
+    assert!(text.contains("Category        2"));
+    assert!(text.contains("Vote threshold  60%"));
+    assert!(text.contains("Quorum          500"));
+    assert!(display::proposal_summary(&address, &proposal).starts_with("#7    Queued"));
+
+    let emergency = Proposal { emergency: true, ..proposal.clone() };
+    assert!(display::proposal(&address, &emergency).contains("Category        2 (emergency)"));
+    let extended = Proposal { voting_period: 90_000, voting_extension: 3_600, ..proposal.clone() };
+    assert!(display::proposal(&address, &extended).contains("Voting ends     91000 (25h, extended by 1h)"));
+    let reviewed = display::proposal(&address, &Proposal { review_period: 7_200, ..proposal });
+    assert!(reviewed.contains("Voting starts   8200 (2h review)"));
+    assert!(reviewed.contains("Voting ends     94600 (1d)"));
+}
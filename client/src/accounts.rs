@@ -0,0 +1,92 @@
+//! Fetching and decoding program accounts.
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Discriminator};
+use iterative_dao::state::{Governor, Proposal, Vote};
+use solana_account_decoder::UiAccountEncoding;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::account::Account;
+
+use crate::{
+    error::{ClientError, Result},
+    pda::{find_governor_address, find_proposal_address},
+};
+
+/// Decodes an account owned by the program, checking its discriminator.
+pub fn decode<T: AccountDeserialize>(address: &Pubkey, account: &Account) -> Result<T> {
+    if account.owner != iterative_dao::ID {
+        return Err(ClientError::InvalidOwner(*address));
+    }
+    T::try_deserialize(&mut account.data.as_slice()).map_err(|err| ClientError::Deserialize {
+        address: *address,
+        reason: err.to_string(),
+    })
+}
+
+pub fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    fetch_optional(rpc, address)?.ok_or(ClientError::AccountNotFound(*address))
+}
+
+/// Like [`fetch`], but a missing account is `Ok(None)` rather than an error.
+pub fn fetch_optional<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<Option<T>> {
+    let account = rpc
+        .get_account_with_commitment(address, rpc.commitment())?
+        .value;
+    account.map(|account| decode(address, &account)).transpose()
+}
+
+/// Fetches several accounts in one request, preserving the order of `addresses`.
+pub fn fetch_multiple<T: AccountDeserialize>(rpc: &RpcClient, addresses: &[Pubkey]) -> Result<Vec<Option<T>>> {
+    let accounts = rpc.get_multiple_accounts(addresses)?;
+    addresses
+        .iter()
+        .zip(accounts)
+        .map(|(address, account)| account.map(|account| decode(address, &account)).transpose())
+        .collect()
+}
+
+pub fn fetch_governor(rpc: &RpcClient, smart_wallet: &Pubkey) -> Result<Governor> {
+    fetch(rpc, &find_governor_address(smart_wallet).0)
+}
+
+pub fn fetch_proposal(rpc: &RpcClient, governor: &Pubkey, proposal_id: u64) -> Result<Proposal> {
+    fetch(rpc, &find_proposal_address(governor, proposal_id).0)
+}
+
+/// All proposals of `governor`, in no particular order.
+pub fn fetch_governor_proposals(rpc: &RpcClient, governor: &Pubkey) -> Result<Vec<(Pubkey, Proposal)>> {
+    // `Proposal::governor` is the first field after the discriminator.
+    fetch_by_key_at::<Proposal>(rpc, 8, governor)
+}
+
+/// All votes recorded against `proposal`.
+pub fn fetch_proposal_votes(rpc: &RpcClient, proposal: &Pubkey) -> Result<Vec<(Pubkey, Vote)>> {
+    // `Vote::proposal` is the first field after the discriminator.
+    fetch_by_key_at::<Vote>(rpc, 8, proposal)
+}
+
+fn fetch_by_key_at<T: AccountDeserialize + Discriminator>(
+    rpc: &RpcClient,
+    offset: usize,
+    key: &Pubkey,
+) -> Result<Vec<(Pubkey, T)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &T::DISCRIMINATOR)),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, key.as_ref())),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(rpc.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        with_context: None,
+    };
+    rpc.get_program_accounts_with_config(&iterative_dao::ID, config)?
+        .into_iter()
+        .map(|(address, account)| Ok((address, decode(&address, &account)?)))
+        .collect()
+}
@@ -0,0 +1,56 @@
+//! Client errors and decoding of the program's custom error codes.
+
+use anchor_lang::{error::ERROR_CODE_OFFSET, prelude::Pubkey};
+use iterative_dao::errors::ErrorCode;
+use solana_rpc_client_api::client_error::Error as RpcError;
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("rpc request failed: {0}")]
+    Rpc(Box<RpcError>),
+    #[error("account {0} does not exist")]
+    AccountNotFound(Pubkey),
+    #[error("account {0} is not owned by the iterative-dao program")]
+    InvalidOwner(Pubkey),
+    #[error("account {address} could not be deserialized: {reason}")]
+    Deserialize { address: Pubkey, reason: String },
+}
+
+impl From<RpcError> for ClientError {
+    fn from(err: RpcError) -> Self {
+        ClientError::Rpc(Box::new(err))
+    }
+}
+
+impl ClientError {
+    /// The program error behind a failed transaction, if there is one.
+    pub fn program_error(&self) -> Option<ErrorCode> {
+        match self {
+            ClientError::Rpc(err) => err.get_transaction_error().as_ref().and_then(program_error),
+            _ => None,
+        }
+    }
+}
+
+/// Maps a custom error code to the program error it stands for.
+///
+/// Codes below the offset belong to Anchor itself and return `None`.
+pub fn decode_error(code: u32) -> Option<ErrorCode> {
+    let index = code.checked_sub(ERROR_CODE_OFFSET)?;
+    ErrorCode::try_from(index).ok()
+}
+
+/// Extracts the custom error code from a failed transaction.
+pub fn custom_error_code(err: &TransactionError) -> Option<u32> {
+    match err {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(*code),
+        _ => None,
+    }
+}
+
+pub fn program_error(err: &TransactionError) -> Option<ErrorCode> {
+    custom_error_code(err).and_then(decode_error)
+}
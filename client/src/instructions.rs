@@ -0,0 +1,793 @@
+//! One builder per instruction in the program's `lib.rs`.
+//!
+//! Builders derive every PDA they need, so callers only pass the keys that
+//! cannot be derived (wallets, signers, token accounts).
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{ed25519_program, instruction::Instruction, system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use iterative_dao::{
+    accounts,
+    enums::{OptionSelection, VoteMutability, VoteSide, VotingStrategy, WeightSource},
+    instruction,
+    metadata::find_metadata_address,
+    state::{
+        BatchedVote, LateVoteExtension, NewProposalOption, ProposalCategoryConfig, ProposalInstruction, SignedVote,
+        VoteRationale, VotingSchedule,
+    },
+};
+
+use crate::pda::{
+    find_escrow_address, find_governor_address, find_lock_address, find_locker_address,
+    find_nft_collection_address, find_nft_vote_address, find_proposal_address, find_proposal_category_address,
+    find_vote_address,
+};
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: iterative_dao::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Appends `(locker, escrow)` pairs whose escrowed tokens count towards a vote.
+///
+/// Read by `cast_vote`, `create_and_cast_vote`, `set_vote`, `cast_ranked_vote`
+/// and `commit_vote`; `cast_votes` reads them after its `(proposal, vote)` pairs.
+pub fn with_escrows(mut ix: Instruction, pairs: &[(Pubkey, Pubkey)]) -> Instruction {
+    for (locker, escrow) in pairs {
+        ix.accounts.push(AccountMeta::new_readonly(*locker, false));
+        ix.accounts.push(AccountMeta::new_readonly(*escrow, false));
+    }
+    ix
+}
+
+/// Appends lock accounts whose still-locked tokens count towards a vote,
+/// read alongside any escrow pairs.
+pub fn with_locks(mut ix: Instruction, locks: &[Pubkey]) -> Instruction {
+    for lock in locks {
+        ix.accounts.push(AccountMeta::new_readonly(*lock, false));
+    }
+    ix
+}
+
+/// Appends the voter's `VoterWeightRecord` for a governor whose weight
+/// source is an add-in; it replaces any escrow pairs.
+pub fn with_voter_weight_record(mut ix: Instruction, record: Pubkey) -> Instruction {
+    ix.accounts.push(AccountMeta::new_readonly(record, false));
+    ix
+}
+
+//  Governor Management
+
+pub fn init_governor(
+    smart_wallet: Pubkey,
+    payer: Pubkey,
+    vote_threshold: u8,
+    timelock_delay: i64,
+    electorate: Pubkey,
+    governance_mint: Pubkey,
+) -> Instruction {
+    build(
+        accounts::InitGovernor {
+            governor: find_governor_address(&smart_wallet).0,
+            payer,
+            smart_wallet,
+            system_program: system_program::ID,
+        },
+        instruction::InitGovernor { vote_threshold, timelock_delay, electorate, governance_mint },
+    )
+}
+
+pub fn add_voter(smart_wallet: Pubkey, new_voter: Pubkey, weight: u64) -> Instruction {
+    build(
+        accounts::AddVoter {
+            governor: find_governor_address(&smart_wallet).0,
+            smart_wallet,
+            system_program: system_program::ID,
+        },
+        instruction::AddVoter { new_voter, weight },
+    )
+}
+
+pub fn set_voting_strategy(smart_wallet: Pubkey, voting_strategy: VotingStrategy) -> Instruction {
+    build(
+        accounts::SetVotingStrategy { governor: find_governor_address(&smart_wallet).0, smart_wallet },
+        instruction::SetVotingStrategy { voting_strategy },
+    )
+}
+
+pub fn set_weight_source(smart_wallet: Pubkey, weight_source: WeightSource) -> Instruction {
+    build(
+        accounts::SetWeightSource { governor: find_governor_address(&smart_wallet).0, smart_wallet },
+        instruction::SetWeightSource { weight_source },
+    )
+}
+
+pub fn set_vote_mutability(smart_wallet: Pubkey, vote_mutability: VoteMutability) -> Instruction {
+    build(
+        accounts::SetVoteMutability { governor: find_governor_address(&smart_wallet).0, smart_wallet },
+        instruction::SetVoteMutability { vote_mutability },
+    )
+}
+
+pub fn set_late_vote_extension(smart_wallet: Pubkey, late_vote_extension: Option<LateVoteExtension>) -> Instruction {
+    build(
+        accounts::SetLateVoteExtension { governor: find_governor_address(&smart_wallet).0, smart_wallet },
+        instruction::SetLateVoteExtension { late_vote_extension },
+    )
+}
+
+pub fn set_voting_schedule(smart_wallet: Pubkey, voting_schedule: VotingSchedule) -> Instruction {
+    build(
+        accounts::SetVotingSchedule { governor: find_governor_address(&smart_wallet).0, smart_wallet },
+        instruction::SetVotingSchedule { voting_schedule },
+    )
+}
+
+pub fn configure_nft_collection(smart_wallet: Pubkey, payer: Pubkey, collection: Pubkey, weight: u64) -> Instruction {
+    let governor = find_governor_address(&smart_wallet).0;
+    build(
+        accounts::ConfigureNftCollection {
+            governor,
+            nft_collection: find_nft_collection_address(&governor, &collection).0,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::ConfigureNftCollection { collection, weight },
+    )
+}
+
+//  Proposal Management
+
+pub fn configure_proposal_category(
+    smart_wallet: Pubkey,
+    payer: Pubkey,
+    id: u8,
+    config: ProposalCategoryConfig,
+) -> Instruction {
+    let governor = find_governor_address(&smart_wallet).0;
+    build(
+        accounts::ConfigureProposalCategory {
+            governor,
+            category: find_proposal_category_address(&governor, id).0,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::ConfigureProposalCategory { id, config },
+    )
+}
+
+pub fn create_proposal(
+    governor: Pubkey,
+    proposal_id: u64,
+    proposer: Pubkey,
+    payer: Pubkey,
+    category: Option<u8>,
+    instructions: Vec<ProposalInstruction>,
+) -> Instruction {
+    build(
+        accounts::CreateProposal {
+            governor,
+            proposal: find_proposal_address(&governor, proposal_id).0,
+            payer,
+            proposer,
+            category: category.map(|id| find_proposal_category_address(&governor, id).0),
+            system_program: system_program::ID,
+        },
+        instruction::CreateProposal { instructions },
+    )
+}
+
+pub fn create_multiple_choice_proposal(
+    governor: Pubkey,
+    proposal_id: u64,
+    proposer: Pubkey,
+    payer: Pubkey,
+    category: Option<u8>,
+    options: Vec<NewProposalOption>,
+    selection: OptionSelection,
+) -> Instruction {
+    build(
+        accounts::CreateProposal {
+            governor,
+            proposal: find_proposal_address(&governor, proposal_id).0,
+            payer,
+            proposer,
+            category: category.map(|id| find_proposal_category_address(&governor, id).0),
+            system_program: system_program::ID,
+        },
+        instruction::CreateMultipleChoiceProposal { options, selection },
+    )
+}
+
+pub fn activate_proposal(smart_wallet: Pubkey, proposal: Pubkey, voting_period: Option<i64>) -> Instruction {
+    build(
+        accounts::ActivateProposal {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            smart_wallet,
+            system_program: system_program::ID,
+        },
+        instruction::ActivateProposal { voting_period },
+    )
+}
+
+pub fn activate_secret_proposal(
+    smart_wallet: Pubkey,
+    proposal: Pubkey,
+    voting_period: Option<i64>,
+    reveal_period: i64,
+) -> Instruction {
+    build(
+        accounts::ActivateProposal {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            smart_wallet,
+            system_program: system_program::ID,
+        },
+        instruction::ActivateSecretProposal { voting_period, reveal_period },
+    )
+}
+
+pub fn cancel_proposal(smart_wallet: Pubkey, proposal: Pubkey, proposer: Pubkey) -> Instruction {
+    build(
+        accounts::CancelProposal {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            smart_wallet,
+            proposer,
+            system_program: system_program::ID,
+        },
+        instruction::CancelProposal {},
+    )
+}
+
+pub fn queue_proposal(smart_wallet: Pubkey, proposal: Pubkey) -> Instruction {
+    build(
+        accounts::QueueProposal {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            smart_wallet,
+            system_program: system_program::ID,
+        },
+        instruction::QueueProposal {},
+    )
+}
+
+pub fn finalise_proposal(smart_wallet: Pubkey, proposal: Pubkey) -> Instruction {
+    build(
+        accounts::FinaliseProposal { governor: find_governor_address(&smart_wallet).0, proposal, smart_wallet },
+        instruction::FinaliseProposal {},
+    )
+}
+
+pub fn finalise_proposal_early(smart_wallet: Pubkey, proposal: Pubkey) -> Instruction {
+    build(
+        accounts::FinaliseProposal { governor: find_governor_address(&smart_wallet).0, proposal, smart_wallet },
+        instruction::FinaliseProposalEarly {},
+    )
+}
+
+pub fn fast_track_proposal(smart_wallet: Pubkey, proposal: Pubkey) -> Instruction {
+    build(
+        accounts::FinaliseProposal { governor: find_governor_address(&smart_wallet).0, proposal, smart_wallet },
+        instruction::FastTrackProposal {},
+    )
+}
+
+pub fn execute_proposal(smart_wallet: Pubkey, proposal: Pubkey) -> Instruction {
+    build(
+        accounts::ExecuteProposal { governor: find_governor_address(&smart_wallet).0, proposal, smart_wallet },
+        instruction::ExecuteProposal {},
+    )
+}
+
+//  Voting
+
+pub fn create_vote(smart_wallet: Pubkey, proposal: Pubkey, voter: Pubkey, payer: Pubkey) -> Instruction {
+    build(
+        accounts::CreateVote {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            vote: find_vote_address(&proposal, &voter).0,
+            voter,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::CreateVote {},
+    )
+}
+
+pub fn cast_vote(
+    smart_wallet: Pubkey,
+    proposal: Pubkey,
+    voter: Pubkey,
+    payer: Pubkey,
+    side: VoteSide,
+    weight: u64,
+    rationale: Option<VoteRationale>,
+) -> Instruction {
+    build(
+        accounts::CastVote {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            vote: find_vote_address(&proposal, &voter).0,
+            voter,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::CastVote { side, weight, rationale },
+    )
+}
+
+pub fn create_and_cast_vote(
+    smart_wallet: Pubkey,
+    proposal: Pubkey,
+    voter: Pubkey,
+    payer: Pubkey,
+    side: VoteSide,
+    weight: u64,
+    rationale: Option<VoteRationale>,
+) -> Instruction {
+    build(
+        accounts::CreateVote {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            vote: find_vote_address(&proposal, &voter).0,
+            voter,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::CreateAndCastVote { side, weight, rationale },
+    )
+}
+
+/// Casts `voter`'s vote on each proposal in `votes`, creating vote accounts
+/// as needed. Voting-power accounts can be appended as for `cast_vote`.
+pub fn cast_votes(
+    smart_wallet: Pubkey,
+    voter: Pubkey,
+    payer: Pubkey,
+    votes: Vec<(Pubkey, BatchedVote)>,
+) -> Instruction {
+    let pairs: Vec<AccountMeta> = votes
+        .iter()
+        .flat_map(|(proposal, _)| {
+            [AccountMeta::new(*proposal, false), AccountMeta::new(find_vote_address(proposal, &voter).0, false)]
+        })
+        .collect();
+    let mut ix = build(
+        accounts::CastVotes {
+            governor: find_governor_address(&smart_wallet).0,
+            voter,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::CastVotes { votes: votes.into_iter().map(|(_, vote)| vote).collect() },
+    );
+    ix.accounts.extend(pairs);
+    ix
+}
+
+pub fn set_vote(
+    smart_wallet: Pubkey,
+    proposal: Pubkey,
+    voter: Pubkey,
+    payer: Pubkey,
+    new_side: VoteSide,
+    rationale: Option<VoteRationale>,
+) -> Instruction {
+    build(
+        accounts::SetVote {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            vote: find_vote_address(&proposal, &voter).0,
+            voter,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::SetVote { new_side, rationale },
+    )
+}
+
+pub fn withdraw_vote(smart_wallet: Pubkey, proposal: Pubkey, voter: Pubkey, payer: Pubkey) -> Instruction {
+    build(
+        accounts::SetVote {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            vote: find_vote_address(&proposal, &voter).0,
+            voter,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::WithdrawVote {},
+    )
+}
+
+/// Commits to a secret ballot; the commitment comes from
+/// `iterative_dao::secret::ballot_commitment`.
+pub fn commit_vote(
+    smart_wallet: Pubkey,
+    proposal: Pubkey,
+    voter: Pubkey,
+    payer: Pubkey,
+    commitment: [u8; 32],
+    weight: u64,
+) -> Instruction {
+    build(
+        accounts::SetVote {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            vote: find_vote_address(&proposal, &voter).0,
+            voter,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::CommitVote { commitment, weight },
+    )
+}
+
+pub fn reveal_vote(
+    smart_wallet: Pubkey,
+    proposal: Pubkey,
+    voter: Pubkey,
+    payer: Pubkey,
+    side: VoteSide,
+    salt: [u8; 32],
+) -> Instruction {
+    build(
+        accounts::SetVote {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            vote: find_vote_address(&proposal, &voter).0,
+            voter,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::RevealVote { side, salt },
+    )
+}
+
+pub fn cast_ranked_vote(
+    smart_wallet: Pubkey,
+    proposal: Pubkey,
+    voter: Pubkey,
+    payer: Pubkey,
+    ranking: Vec<u8>,
+    weight: u64,
+) -> Instruction {
+    build(
+        accounts::CastVote {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            vote: find_vote_address(&proposal, &voter).0,
+            voter,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::CastRankedVote { ranking, weight },
+    )
+}
+
+/// The NFT a `cast_nft_vote` adds to a vote.
+#[derive(Clone, Copy, Debug)]
+pub struct NftAccounts {
+    pub mint: Pubkey,
+    /// The voter's token account holding the NFT.
+    pub token_account: Pubkey,
+    /// Collection the NFT is verified into.
+    pub collection: Pubkey,
+}
+
+pub fn cast_nft_vote(
+    smart_wallet: Pubkey,
+    proposal: Pubkey,
+    voter: Pubkey,
+    payer: Pubkey,
+    nft: NftAccounts,
+    side: VoteSide,
+) -> Instruction {
+    let governor = find_governor_address(&smart_wallet).0;
+    build(
+        accounts::CastNftVote {
+            governor,
+            proposal,
+            vote: find_vote_address(&proposal, &voter).0,
+            nft_collection: find_nft_collection_address(&governor, &nft.collection).0,
+            nft_token_account: nft.token_account,
+            nft_metadata: find_metadata_address(&nft.mint),
+            nft_vote_record: find_nft_vote_address(&proposal, &nft.mint).0,
+            voter,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::CastNftVote { side },
+    )
+}
+
+/// Records `votes` signed off-chain. An earlier instruction in the same
+/// transaction must verify each voter's signature over
+/// `relay::relayed_vote_message`; see `verify_ed25519_signatures`.
+pub fn cast_relayed_votes(
+    smart_wallet: Pubkey,
+    proposal: Pubkey,
+    payer: Pubkey,
+    votes: Vec<SignedVote>,
+) -> Instruction {
+    let vote_accounts: Vec<AccountMeta> =
+        votes.iter().map(|vote| AccountMeta::new(find_vote_address(&proposal, &vote.voter).0, false)).collect();
+    let mut ix = build(
+        accounts::CastRelayedVotes {
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            smart_wallet,
+            payer,
+            instructions: sysvar::instructions::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CastRelayedVotes { votes },
+    );
+    ix.accounts.extend(vote_accounts);
+    ix
+}
+
+/// An ed25519 program instruction verifying each `(signer, signature, message)`.
+///
+/// Keys and messages are carried in the instruction's own data, which is the
+/// only layout `cast_relayed_votes` accepts.
+pub fn verify_ed25519_signatures(signatures: &[(Pubkey, [u8; 64], Vec<u8>)]) -> Instruction {
+    const OFFSETS_START: usize = 2;
+    const OFFSETS_LEN: usize = 14;
+    const THIS_INSTRUCTION: usize = u16::MAX as usize;
+
+    let body_start = OFFSETS_START + signatures.len() * OFFSETS_LEN;
+    let mut data = vec![signatures.len() as u8, 0];
+    let mut body = Vec::new();
+    for (signer, signature, message) in signatures {
+        let key_offset = body_start + body.len();
+        body.extend_from_slice(signer.as_ref());
+        let signature_offset = body_start + body.len();
+        body.extend_from_slice(signature);
+        let message_offset = body_start + body.len();
+        body.extend_from_slice(message);
+        for field in [
+            signature_offset,
+            THIS_INSTRUCTION,
+            key_offset,
+            THIS_INSTRUCTION,
+            message_offset,
+            message.len(),
+            THIS_INSTRUCTION,
+        ] {
+            data.extend_from_slice(&(field as u16).to_le_bytes());
+        }
+    }
+    data.extend(body);
+    Instruction { program_id: ed25519_program::ID, accounts: Vec::new(), data }
+}
+
+/// Counts the given vote accounts towards the current instant-runoff round.
+pub fn tally_ranked_votes(proposal: Pubkey, votes: &[Pubkey]) -> Instruction {
+    let mut ix = build(accounts::TallyRankedVotes { proposal }, instruction::TallyRankedVotes {});
+    ix.accounts.extend(votes.iter().map(|vote| AccountMeta::new(*vote, false)));
+    ix
+}
+
+//  Token Locking
+
+/// Accounts shared by the lock, vesting, withdraw and escrow builders.
+#[derive(Clone, Copy, Debug)]
+pub struct LockAccounts {
+    pub smart_wallet: Pubkey,
+    pub user: Pubkey,
+    pub user_token_account: Pubkey,
+    pub escrow_token_account: Pubkey,
+}
+
+pub fn lock_tokens(
+    accs: LockAccounts,
+    payer: Pubkey,
+    amount: u64,
+    duration: i64,
+    lock_id: u64,
+) -> Instruction {
+    let governor = find_governor_address(&accs.smart_wallet).0;
+    build(
+        accounts::LockTokens {
+            governor,
+            smart_wallet: accs.smart_wallet,
+            user: accs.user,
+            user_token_account: accs.user_token_account,
+            escrow_token_account: accs.escrow_token_account,
+            lock_account: find_lock_address(&governor, &accs.user, lock_id).0,
+            payer,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::LockTokens { amount, duration, lock_id },
+    )
+}
+
+pub fn withdraw_tokens(accs: LockAccounts, lock_id: u64) -> Instruction {
+    let governor = find_governor_address(&accs.smart_wallet).0;
+    build(
+        accounts::WithdrawTokens {
+            lock_account: find_lock_address(&governor, &accs.user, lock_id).0,
+            user: accs.user,
+            user_token_account: accs.user_token_account,
+            escrow_token_account: accs.escrow_token_account,
+            smart_wallet: accs.smart_wallet,
+            governor,
+            token_program: spl_token::ID,
+        },
+        instruction::WithdrawTokens { lock_id },
+    )
+}
+
+pub fn lock_vesting_tokens(
+    accs: LockAccounts,
+    payer: Pubkey,
+    amount: u64,
+    cliff_duration: i64,
+    duration: i64,
+    lock_id: u64,
+) -> Instruction {
+    let governor = find_governor_address(&accs.smart_wallet).0;
+    build(
+        accounts::LockVestingTokens {
+            governor,
+            smart_wallet: accs.smart_wallet,
+            user: accs.user,
+            user_token_account: accs.user_token_account,
+            escrow_token_account: accs.escrow_token_account,
+            lock_account: find_lock_address(&governor, &accs.user, lock_id).0,
+            payer,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::LockVestingTokens { amount, cliff_duration, duration, lock_id },
+    )
+}
+
+pub fn claim_vested(accs: LockAccounts, lock_id: u64) -> Instruction {
+    let governor = find_governor_address(&accs.smart_wallet).0;
+    build(
+        accounts::ClaimVested {
+            lock_account: find_lock_address(&governor, &accs.user, lock_id).0,
+            user: accs.user,
+            user_token_account: accs.user_token_account,
+            escrow_token_account: accs.escrow_token_account,
+            smart_wallet: accs.smart_wallet,
+            governor,
+            token_program: spl_token::ID,
+        },
+        instruction::ClaimVested { lock_id },
+    )
+}
+
+//  Locker
+
+pub fn create_locker(
+    smart_wallet: Pubkey,
+    mint: Pubkey,
+    payer: Pubkey,
+    voting_power_multiplier: u64,
+    min_lock_duration: i64,
+    max_lock_duration: i64,
+) -> Instruction {
+    let governor = find_governor_address(&smart_wallet).0;
+    build(
+        accounts::CreateLocker {
+            governor,
+            locker: find_locker_address(&governor, &mint).0,
+            mint,
+            smart_wallet,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::CreateLocker { voting_power_multiplier, min_lock_duration, max_lock_duration },
+    )
+}
+
+pub fn set_locker_params(
+    smart_wallet: Pubkey,
+    locker: Pubkey,
+    proposal: Pubkey,
+    new_voting_power_multiplier: u64,
+    new_min_lock_duration: i64,
+    new_max_lock_duration: i64,
+) -> Instruction {
+    build(
+        accounts::SetLockerParams {
+            locker,
+            governor: find_governor_address(&smart_wallet).0,
+            proposal,
+            authority: smart_wallet,
+            system_program: system_program::ID,
+        },
+        instruction::SetLockerParams {
+            new_voting_power_multiplier,
+            new_min_lock_duration,
+            new_max_lock_duration,
+        },
+    )
+}
+
+//  Escrow
+
+pub fn create_escrow(
+    accs: LockAccounts,
+    locker: Pubkey,
+    payer: Pubkey,
+    amount: u64,
+    duration: i64,
+    escrow_id: u64,
+) -> Instruction {
+    build(
+        accounts::CreateEscrow {
+            governor: find_governor_address(&accs.smart_wallet).0,
+            locker,
+            smart_wallet: accs.smart_wallet,
+            user: accs.user,
+            user_token_account: accs.user_token_account,
+            escrow_token_account: accs.escrow_token_account,
+            escrow: find_escrow_address(&locker, &accs.user, escrow_id).0,
+            payer,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateEscrow { amount, duration, escrow_id },
+    )
+}
+
+pub fn withdraw_escrow(accs: LockAccounts, locker: Pubkey, escrow_id: u64) -> Instruction {
+    build(
+        accounts::WithdrawEscrow {
+            escrow: find_escrow_address(&locker, &accs.user, escrow_id).0,
+            locker,
+            user: accs.user,
+            user_token_account: accs.user_token_account,
+            escrow_token_account: accs.escrow_token_account,
+            smart_wallet: accs.smart_wallet,
+            governor: find_governor_address(&accs.smart_wallet).0,
+            token_program: spl_token::ID,
+        },
+        instruction::WithdrawEscrow { escrow_id },
+    )
+}
+
+//  Account Closing
+
+pub fn close_proposal(proposal: Pubkey, payer: Pubkey) -> Instruction {
+    build(accounts::CloseProposal { proposal, payer }, instruction::CloseProposal {})
+}
+
+pub fn close_vote(vote: Pubkey, proposal: Pubkey, payer: Pubkey) -> Instruction {
+    build(accounts::CloseVote { vote, proposal, payer }, instruction::CloseVote {})
+}
+
+pub fn close_nft_vote_record(nft_vote_record: Pubkey, proposal: Pubkey, payer: Pubkey) -> Instruction {
+    build(accounts::CloseNftVoteRecord { nft_vote_record, proposal, payer }, instruction::CloseNftVoteRecord {})
+}
+
+pub fn close_lock_account(lock_account: Pubkey, payer: Pubkey) -> Instruction {
+    build(accounts::CloseLockAccount { lock_account, payer }, instruction::CloseLockAccount {})
+}
+
+pub fn close_escrow(escrow: Pubkey, locker: Pubkey, payer: Pubkey) -> Instruction {
+    build(accounts::CloseEscrow { escrow, locker, payer }, instruction::CloseEscrow {})
+}
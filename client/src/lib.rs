@@ -0,0 +1,14 @@
+//! Rust client for the iterative-dao program.
+//!
+//! - [`pda`] derives every program address from the same seeds as `contexts.rs`.
+//! - [`instructions`] builds each instruction in the program's `lib.rs`.
+//! - [`accounts`] fetches and decodes governors, proposals, votes and locks.
+//! - [`error`] maps custom error codes back to `iterative_dao::errors::ErrorCode`.
+
+pub mod accounts;
+pub mod error;
+pub mod instructions;
+pub mod pda;
+
+pub use error::{ClientError, Result};
+pub use iterative_dao::{self as program, ID};
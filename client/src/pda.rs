@@ -0,0 +1,64 @@
+//! Program-derived addresses, mirroring the `seeds` constraints in `contexts.rs`.
+
+use anchor_lang::prelude::Pubkey;
+
+pub const GOVERNOR_SEED: &[u8] = b"governor";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const VOTE_SEED: &[u8] = b"vote";
+pub const LOCK_SEED: &[u8] = b"lock";
+pub const LOCKER_SEED: &[u8] = b"locker";
+pub const ESCROW_SEED: &[u8] = b"escrow";
+pub const NFT_COLLECTION_SEED: &[u8] = b"nft_collection";
+pub const NFT_VOTE_SEED: &[u8] = b"nft_vote";
+pub const PROPOSAL_CATEGORY_SEED: &[u8] = b"proposal_category";
+
+/// The governor controlled by `smart_wallet`.
+pub fn find_governor_address(smart_wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GOVERNOR_SEED, smart_wallet.as_ref()], &iterative_dao::ID)
+}
+
+/// Proposal number `proposal_id` of `governor`; ids follow `Governor::proposal_count`.
+pub fn find_proposal_address(governor: &Pubkey, proposal_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PROPOSAL_SEED, governor.as_ref(), &proposal_id.to_le_bytes()],
+        &iterative_dao::ID,
+    )
+}
+
+pub fn find_vote_address(proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTE_SEED, proposal.as_ref(), voter.as_ref()], &iterative_dao::ID)
+}
+
+pub fn find_lock_address(governor: &Pubkey, user: &Pubkey, lock_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[LOCK_SEED, governor.as_ref(), user.as_ref(), &lock_id.to_le_bytes()],
+        &iterative_dao::ID,
+    )
+}
+
+/// The single locker `governor` may hold for `mint`.
+pub fn find_locker_address(governor: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LOCKER_SEED, governor.as_ref(), mint.as_ref()], &iterative_dao::ID)
+}
+
+pub fn find_escrow_address(locker: &Pubkey, user: &Pubkey, escrow_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ESCROW_SEED, locker.as_ref(), user.as_ref(), &escrow_id.to_le_bytes()],
+        &iterative_dao::ID,
+    )
+}
+
+/// Voting configuration of `collection` under `governor`.
+pub fn find_nft_collection_address(governor: &Pubkey, collection: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[NFT_COLLECTION_SEED, governor.as_ref(), collection.as_ref()], &iterative_dao::ID)
+}
+
+/// Proposal category `id` of `governor`.
+pub fn find_proposal_category_address(governor: &Pubkey, id: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROPOSAL_CATEGORY_SEED, governor.as_ref(), &[id]], &iterative_dao::ID)
+}
+
+/// Record of the NFT `mint` having voted on `proposal`.
+pub fn find_nft_vote_address(proposal: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[NFT_VOTE_SEED, proposal.as_ref(), mint.as_ref()], &iterative_dao::ID)
+}
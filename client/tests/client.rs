@@ -0,0 +1,57 @@
+use anchor_lang::{prelude::Pubkey, Discriminator};
+use iterative_dao::{enums::VoteSide, errors::ErrorCode};
+use iterative_dao_client::{
+    error::{decode_error, program_error},
+    instructions, pda,
+};
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+#[test]
+fn decode_error_matches_anchor_numbering() {
+    for code in [ErrorCode::GovernorNotInitialised, ErrorCode::TimelockNotExpired, ErrorCode::SecretBallotUnsupported] {
+        assert_eq!(decode_error(code.into()).map(|c| c.name()), Some(code.name()));
+    }
+    assert!(decode_error(7000).is_none());
+    assert!(decode_error(3012).is_none());
+}
+
+#[test]
+fn decodes_program_error_from_transaction() {
+    let err = TransactionError::InstructionError(0, InstructionError::Custom(ErrorCode::TimelockNotExpired.into()));
+    assert!(matches!(program_error(&err), Some(ErrorCode::TimelockNotExpired)));
+
+    let err = TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature);
+    assert!(program_error(&err).is_none());
+}
+
+#[test]
+fn pdas_are_distinct_per_seed_input() {
+    let wallet = Pubkey::new_unique();
+    let (governor, _) = pda::find_governor_address(&wallet);
+    assert_ne!(pda::find_proposal_address(&governor, 0), pda::find_proposal_address(&governor, 1));
+
+    let user = Pubkey::new_unique();
+    let (locker, _) = pda::find_locker_address(&governor, &Pubkey::new_unique());
+    assert_ne!(pda::find_lock_address(&governor, &user, 1).0, pda::find_escrow_address(&locker, &user, 1).0);
+}
+
+#[test]
+fn cast_vote_derives_accounts() {
+    let wallet = Pubkey::new_unique();
+    let proposal = Pubkey::new_unique();
+    let voter = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let ix = instructions::cast_vote(wallet, proposal, voter, payer, VoteSide::For {}, 10, None);
+
+    assert_eq!(ix.program_id, iterative_dao::ID);
+    assert_eq!(ix.accounts[0].pubkey, pda::find_governor_address(&wallet).0);
+    assert_eq!(ix.accounts[2].pubkey, pda::find_vote_address(&proposal, &voter).0);
+    assert!(ix.accounts[3].is_signer);
+    assert_eq!(&ix.data[..8], &iterative_dao::instruction::CastVote::DISCRIMINATOR);
+
+    let locker = Pubkey::new_unique();
+    let escrow = Pubkey::new_unique();
+    let ix = instructions::with_escrows(ix, &[(locker, escrow)]);
+    let tail: Vec<_> = ix.accounts[ix.accounts.len() - 2..].iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(tail, [locker, escrow]);
+}
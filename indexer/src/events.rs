@@ -0,0 +1,105 @@
+//! Decoding of the program's events.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use iterative_dao::events::*;
+
+/// Every event the program emits.
+pub enum DaoEvent {
+    GovernorCreated(GovernorCreated),
+    VoterAdded(VoterAdded),
+    VotingStrategySet(VotingStrategySet),
+    WeightSourceSet(WeightSourceSet),
+    VoteMutabilitySet(VoteMutabilitySet),
+    LateVoteExtensionSet(LateVoteExtensionSet),
+    VotingScheduleSet(VotingScheduleSet),
+    NftCollectionConfigured(NftCollectionConfigured),
+    ProposalCategoryConfigured(ProposalCategoryConfigured),
+    ProposalCreated(ProposalCreated),
+    ProposalActivated(ProposalActivated),
+    VotingExtended(VotingExtended),
+    ProposalCanceled(ProposalCanceled),
+    ProposalQueued(ProposalQueued),
+    ProposalFinalised(ProposalFinalised),
+    ProposalExecuted(ProposalExecuted),
+    VoteCreated(VoteCreateEvent),
+    VoteSet(VoteSetEvent),
+    VoteWithdrawn(VoteWithdrawn),
+    VoteCommitted(VoteCommitted),
+    RankedVoteCast(RankedVoteCast),
+    RankedRoundTallied(RankedRoundTallied),
+    NftVoteRecorded(NftVoteRecorded),
+    Lock(LockEvent),
+    VestingLock(VestingLockEvent),
+    ClaimVested(ClaimVestedEvent),
+    Withdraw(WithdrawEvent),
+    NewLocker(NewLockerEvent),
+    LockerSetParams(LockerSetParamsEvent),
+    NewEscrow(NewEscrowEvent),
+    EscrowWithdrawn(EscrowWithdrawn),
+    ProposalClosed(ProposalClosed),
+    VoteClosed(VoteClosed),
+    NftVoteRecordClosed(NftVoteRecordClosed),
+    LockClosed(LockClosed),
+    EscrowClosed(EscrowClosed),
+}
+
+fn parse<T: AnchorDeserialize>(body: &[u8]) -> Option<T> {
+    T::deserialize(&mut &body[..]).ok()
+}
+
+impl DaoEvent {
+    /// Decodes one `Program data:` payload; `None` for anything that is not
+    /// a known event of this program or was written by a newer program than
+    /// [`EVENT_VERSION`].
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < 9 {
+            return None;
+        }
+        let (discriminator, body) = data.split_at(8);
+        if body[0] == 0 || body[0] > EVENT_VERSION {
+            return None;
+        }
+        let event = match discriminator {
+            d if d == GovernorCreated::DISCRIMINATOR => DaoEvent::GovernorCreated(parse(body)?),
+            d if d == VoterAdded::DISCRIMINATOR => DaoEvent::VoterAdded(parse(body)?),
+            d if d == VotingStrategySet::DISCRIMINATOR => DaoEvent::VotingStrategySet(parse(body)?),
+            d if d == WeightSourceSet::DISCRIMINATOR => DaoEvent::WeightSourceSet(parse(body)?),
+            d if d == VoteMutabilitySet::DISCRIMINATOR => DaoEvent::VoteMutabilitySet(parse(body)?),
+            d if d == LateVoteExtensionSet::DISCRIMINATOR => DaoEvent::LateVoteExtensionSet(parse(body)?),
+            d if d == VotingScheduleSet::DISCRIMINATOR => DaoEvent::VotingScheduleSet(parse(body)?),
+            d if d == NftCollectionConfigured::DISCRIMINATOR => DaoEvent::NftCollectionConfigured(parse(body)?),
+            d if d == ProposalCategoryConfigured::DISCRIMINATOR => {
+                DaoEvent::ProposalCategoryConfigured(parse(body)?)
+            }
+            d if d == ProposalCreated::DISCRIMINATOR => DaoEvent::ProposalCreated(parse(body)?),
+            d if d == ProposalActivated::DISCRIMINATOR => DaoEvent::ProposalActivated(parse(body)?),
+            d if d == VotingExtended::DISCRIMINATOR => DaoEvent::VotingExtended(parse(body)?),
+            d if d == ProposalCanceled::DISCRIMINATOR => DaoEvent::ProposalCanceled(parse(body)?),
+            d if d == ProposalQueued::DISCRIMINATOR => DaoEvent::ProposalQueued(parse(body)?),
+            d if d == ProposalFinalised::DISCRIMINATOR => DaoEvent::ProposalFinalised(parse(body)?),
+            d if d == ProposalExecuted::DISCRIMINATOR => DaoEvent::ProposalExecuted(parse(body)?),
+            d if d == VoteCreateEvent::DISCRIMINATOR => DaoEvent::VoteCreated(parse(body)?),
+            d if d == VoteSetEvent::DISCRIMINATOR => DaoEvent::VoteSet(parse(body)?),
+            d if d == VoteWithdrawn::DISCRIMINATOR => DaoEvent::VoteWithdrawn(parse(body)?),
+            d if d == VoteCommitted::DISCRIMINATOR => DaoEvent::VoteCommitted(parse(body)?),
+            d if d == RankedVoteCast::DISCRIMINATOR => DaoEvent::RankedVoteCast(parse(body)?),
+            d if d == RankedRoundTallied::DISCRIMINATOR => DaoEvent::RankedRoundTallied(parse(body)?),
+            d if d == NftVoteRecorded::DISCRIMINATOR => DaoEvent::NftVoteRecorded(parse(body)?),
+            d if d == LockEvent::DISCRIMINATOR => DaoEvent::Lock(parse(body)?),
+            d if d == VestingLockEvent::DISCRIMINATOR => DaoEvent::VestingLock(parse(body)?),
+            d if d == ClaimVestedEvent::DISCRIMINATOR => DaoEvent::ClaimVested(parse(body)?),
+            d if d == WithdrawEvent::DISCRIMINATOR => DaoEvent::Withdraw(parse(body)?),
+            d if d == NewLockerEvent::DISCRIMINATOR => DaoEvent::NewLocker(parse(body)?),
+            d if d == LockerSetParamsEvent::DISCRIMINATOR => DaoEvent::LockerSetParams(parse(body)?),
+            d if d == NewEscrowEvent::DISCRIMINATOR => DaoEvent::NewEscrow(parse(body)?),
+            d if d == EscrowWithdrawn::DISCRIMINATOR => DaoEvent::EscrowWithdrawn(parse(body)?),
+            d if d == ProposalClosed::DISCRIMINATOR => DaoEvent::ProposalClosed(parse(body)?),
+            d if d == VoteClosed::DISCRIMINATOR => DaoEvent::VoteClosed(parse(body)?),
+            d if d == NftVoteRecordClosed::DISCRIMINATOR => DaoEvent::NftVoteRecordClosed(parse(body)?),
+            d if d == LockClosed::DISCRIMINATOR => DaoEvent::LockClosed(parse(body)?),
+            d if d == EscrowClosed::DISCRIMINATOR => DaoEvent::EscrowClosed(parse(body)?),
+            _ => return None,
+        };
+        Some(event)
+    }
+}
@@ -0,0 +1,730 @@
+//! In-memory state rebuilt from events.
+
+use std::collections::BTreeMap;
+
+use anchor_lang::prelude::Pubkey;
+use iterative_dao::{
+    enums::{ProposalState, VoteMutability, VoteSide, VotingStrategy, WeightSource},
+    events::VoteSetEvent,
+    state::VotingSchedule,
+};
+use serde::Serialize;
+
+use crate::events::DaoEvent;
+
+/// Where an event came from, recorded on the entities it touches.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct EventContext {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GovernorView {
+    #[serde(with = "pubkey")]
+    pub address: Pubkey,
+    #[serde(with = "pubkey")]
+    pub smart_wallet: Pubkey,
+    #[serde(with = "pubkey")]
+    pub electorate: Pubkey,
+    #[serde(with = "pubkey")]
+    pub governance_mint: Pubkey,
+    pub vote_threshold: u8,
+    pub timelock_delay: i64,
+    pub created_at: i64,
+    /// Registered voter weights, keyed by voter.
+    pub voters: BTreeMap<String, u64>,
+    pub proposal_count: u64,
+    pub voting_strategy: &'static str,
+    /// `declared`, `registry`, `locker`, `add-in:<program>` or `nft`.
+    pub weight_source: String,
+    /// `free`, `immutable` or `until-cutoff:<seconds>`.
+    pub vote_mutability: String,
+    /// `off`, or `window:<seconds>,extension:<seconds>,max:<seconds>`.
+    pub late_vote_extension: String,
+    /// `min:<seconds>,max:<seconds>,default:<seconds>,review:<seconds>`.
+    pub voting_schedule: String,
+    /// Per-NFT weight of each collection allowed to vote, keyed by collection.
+    pub nft_collections: BTreeMap<String, u64>,
+    /// Proposal categories, keyed by id.
+    pub categories: BTreeMap<u8, CategoryView>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CategoryView {
+    #[serde(with = "pubkey")]
+    pub address: Pubkey,
+    pub name: String,
+    pub vote_threshold: u8,
+    pub quorum: u64,
+    pub min_voting_period: i64,
+    pub max_voting_period: i64,
+    pub timelock_delay: i64,
+    /// Programs the category's proposals may call.
+    pub programs: Vec<String>,
+    pub emergency: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ProposalView {
+    #[serde(with = "pubkey")]
+    pub address: Pubkey,
+    #[serde(with = "pubkey")]
+    pub governor: Pubkey,
+    pub proposal_id: u64,
+    /// Unknown when indexing started after the proposal was created.
+    #[serde(with = "pubkey::option")]
+    pub proposer: Option<Pubkey>,
+    /// Id of the category the proposal was created under.
+    pub category: Option<u8>,
+    pub state: &'static str,
+    pub created_at: Option<i64>,
+    pub activated_at: Option<i64>,
+    /// Later than `activated_at` when the proposal had a review period.
+    pub voting_starts_at: Option<i64>,
+    pub voting_ends_at: Option<i64>,
+    /// End of the reveal window of a secret ballot.
+    pub reveal_ends_at: Option<i64>,
+    pub timelock_delay: Option<i64>,
+    pub queued_at: Option<i64>,
+    pub ready_to_execute_at: Option<i64>,
+    pub canceled_at: Option<i64>,
+    pub finalised_at: Option<i64>,
+    pub executed_at: Option<i64>,
+    pub for_votes: u64,
+    pub against_votes: u64,
+    pub abstain_votes: u64,
+    /// Per-option tallies of a multiple-choice proposal; empty for yes/no.
+    /// First preferences only for instant-runoff proposals.
+    pub option_votes: Vec<u64>,
+    /// Options knocked out by instant-runoff rounds, in order.
+    pub eliminated_options: Vec<u8>,
+    pub winning_option: Option<u8>,
+    pub closed: bool,
+    pub last_signature: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct VoteView {
+    #[serde(with = "pubkey")]
+    pub address: Pubkey,
+    #[serde(with = "pubkey")]
+    pub proposal: Pubkey,
+    #[serde(with = "pubkey")]
+    pub voter: Pubkey,
+    /// `None` until the vote is cast.
+    pub side: Option<&'static str>,
+    /// Selected option when `side` is `Choice`.
+    pub option: Option<u8>,
+    /// Full preference order of a ranked ballot.
+    pub ranking: Vec<u8>,
+    pub weight: u64,
+    /// Mints of the NFTs backing the vote, in the order they were added.
+    pub nft_mints: Vec<String>,
+    /// Committed on a secret ballot; `side` stays `None` until revealed.
+    pub committed: bool,
+    /// Reasons given with the current side, if any.
+    pub rationale: Option<RationaleView>,
+    pub closed: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RationaleView {
+    pub reason: String,
+    pub uri: String,
+    /// Hex-encoded hash of the document at `uri`.
+    pub uri_hash: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LockView {
+    #[serde(with = "pubkey")]
+    pub address: Pubkey,
+    #[serde(with = "pubkey")]
+    pub user: Pubkey,
+    pub amount: u64,
+    pub start_time: i64,
+    pub cliff_time: Option<i64>,
+    pub end_time: i64,
+    pub claimed_amount: u64,
+    pub withdrawn: bool,
+    pub closed: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LockerView {
+    #[serde(with = "pubkey")]
+    pub address: Pubkey,
+    #[serde(with = "pubkey")]
+    pub governor: Pubkey,
+    #[serde(with = "pubkey")]
+    pub mint: Pubkey,
+    pub voting_power_multiplier: u64,
+    pub min_lock_duration: i64,
+    pub max_lock_duration: i64,
+    pub total_escrowed: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EscrowView {
+    #[serde(with = "pubkey")]
+    pub address: Pubkey,
+    #[serde(with = "pubkey")]
+    pub locker: Pubkey,
+    #[serde(with = "pubkey")]
+    pub user: Pubkey,
+    pub amount: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Locker multiplier when the escrow was created, as the program snapshots it.
+    pub voting_power_multiplier: Option<u64>,
+    pub withdrawn: bool,
+    pub closed: bool,
+}
+
+#[derive(Default, Serialize)]
+pub struct Index {
+    pub governors: BTreeMap<String, GovernorView>,
+    pub proposals: BTreeMap<String, ProposalView>,
+    pub votes: BTreeMap<String, VoteView>,
+    pub locks: BTreeMap<String, LockView>,
+    pub lockers: BTreeMap<String, LockerView>,
+    pub escrows: BTreeMap<String, EscrowView>,
+    /// Number of events applied.
+    pub events: u64,
+    pub last_slot: u64,
+}
+
+fn state_name(state: &ProposalState) -> &'static str {
+    match state {
+        ProposalState::Draft => "Draft",
+        ProposalState::Active => "Active",
+        ProposalState::Succeeded => "Succeeded",
+        ProposalState::Queued => "Queued",
+        ProposalState::Executed => "Executed",
+        ProposalState::Rejected => "Rejected",
+        ProposalState::Canceled => "Canceled",
+    }
+}
+
+fn schedule_name(schedule: &VotingSchedule) -> String {
+    format!(
+        "min:{},max:{},default:{},review:{}",
+        schedule.min_voting_period,
+        schedule.max_voting_period,
+        schedule.default_voting_period,
+        schedule.review_period
+    )
+}
+
+fn side_name(side: &VoteSide) -> &'static str {
+    match side {
+        VoteSide::For {} => "For",
+        VoteSide::Against {} => "Against",
+        VoteSide::Abstain {} => "Abstain",
+        VoteSide::Choice { .. } => "Choice",
+    }
+}
+
+impl VoteView {
+    fn new(address: Pubkey, proposal: Pubkey, voter: Pubkey) -> Self {
+        Self {
+            address,
+            proposal,
+            voter,
+            side: None,
+            option: None,
+            ranking: Vec::new(),
+            weight: 0,
+            nft_mints: Vec::new(),
+            committed: false,
+            rationale: None,
+            closed: false,
+        }
+    }
+}
+
+impl ProposalView {
+    fn new(address: Pubkey, governor: Pubkey, proposal_id: u64) -> Self {
+        Self {
+            address,
+            governor,
+            proposal_id,
+            proposer: None,
+            category: None,
+            state: state_name(&ProposalState::Draft),
+            created_at: None,
+            activated_at: None,
+            voting_starts_at: None,
+            voting_ends_at: None,
+            reveal_ends_at: None,
+            timelock_delay: None,
+            queued_at: None,
+            ready_to_execute_at: None,
+            canceled_at: None,
+            finalised_at: None,
+            executed_at: None,
+            for_votes: 0,
+            against_votes: 0,
+            abstain_votes: 0,
+            option_votes: Vec::new(),
+            eliminated_options: Vec::new(),
+            winning_option: None,
+            closed: false,
+            last_signature: String::new(),
+        }
+    }
+
+    fn tally(&mut self, side: &str, option: Option<u8>) -> &mut u64 {
+        match (side, option) {
+            ("For", _) => &mut self.for_votes,
+            ("Against", _) => &mut self.against_votes,
+            ("Choice", Some(index)) => {
+                let index = index as usize;
+                if self.option_votes.len() <= index {
+                    self.option_votes.resize(index + 1, 0);
+                }
+                &mut self.option_votes[index]
+            }
+            _ => &mut self.abstain_votes,
+        }
+    }
+}
+
+impl Index {
+    pub fn governor(&self, address: &Pubkey) -> Option<&GovernorView> {
+        self.governors.get(&address.to_string())
+    }
+
+    pub fn proposal(&self, address: &Pubkey) -> Option<&ProposalView> {
+        self.proposals.get(&address.to_string())
+    }
+
+    /// Proposals of `governor`, by proposal id.
+    pub fn proposals_of(&self, governor: &Pubkey) -> Vec<&ProposalView> {
+        let mut proposals: Vec<_> = self.proposals.values().filter(|p| &p.governor == governor).collect();
+        proposals.sort_by_key(|p| p.proposal_id);
+        proposals
+    }
+
+    pub fn votes_on(&self, proposal: &Pubkey) -> Vec<&VoteView> {
+        self.votes.values().filter(|v| &v.proposal == proposal).collect()
+    }
+
+    /// Open lock positions and escrows of `user`.
+    pub fn positions_of(&self, user: &Pubkey) -> (Vec<&LockView>, Vec<&EscrowView>) {
+        let locks = self.locks.values().filter(|l| &l.user == user && !l.withdrawn).collect();
+        let escrows = self.escrows.values().filter(|e| &e.user == user && !e.withdrawn && !e.closed).collect();
+        (locks, escrows)
+    }
+
+    fn proposal_mut(
+        &mut self,
+        address: Pubkey,
+        governor: Pubkey,
+        proposal_id: u64,
+        ctx: &EventContext,
+    ) -> &mut ProposalView {
+        let proposal = self
+            .proposals
+            .entry(address.to_string())
+            .or_insert_with(|| ProposalView::new(address, governor, proposal_id));
+        proposal.last_signature.clone_from(&ctx.signature);
+        proposal
+    }
+
+    fn record_vote(&mut self, e: &VoteSetEvent, ctx: &EventContext) {
+        // `set_vote` re-emits with the new side and weight, so the
+        // previous contribution is taken back out first.
+        let previous = self
+            .votes
+            .get(&e.vote.to_string())
+            .and_then(|v| v.side.map(|side| (side, v.option, v.weight)));
+        let side = side_name(&e.side);
+        let option = match e.side {
+            VoteSide::Choice { index } => Some(index),
+            _ => None,
+        };
+        let proposal_id = self.proposals.get(&e.proposal.to_string()).map_or(0, |p| p.proposal_id);
+        let proposal = self.proposal_mut(e.proposal, e.governor, proposal_id, ctx);
+        if let Some((old_side, old_option, old_weight)) = previous {
+            let tally = proposal.tally(old_side, old_option);
+            *tally = tally.saturating_sub(old_weight);
+        }
+        let tally = proposal.tally(side, option);
+        *tally = tally.saturating_add(e.weight);
+
+        let vote = self.votes.entry(e.vote.to_string()).or_insert_with(|| VoteView::new(e.vote, e.proposal, e.voter));
+        vote.side = Some(side);
+        vote.option = option;
+        vote.weight = e.weight;
+        vote.rationale = e.rationale.as_ref().map(|r| RationaleView {
+            reason: r.reason.clone(),
+            uri: r.uri.clone(),
+            uri_hash: r.uri_hash.iter().map(|b| format!("{b:02x}")).collect(),
+        });
+    }
+
+    pub fn apply(&mut self, event: &DaoEvent, ctx: &EventContext) {
+        self.events += 1;
+        self.last_slot = self.last_slot.max(ctx.slot);
+
+        match event {
+            DaoEvent::GovernorCreated(e) => {
+                self.governors.insert(
+                    e.governor.to_string(),
+                    GovernorView {
+                        address: e.governor,
+                        smart_wallet: e.smart_wallet,
+                        electorate: e.electorate,
+                        governance_mint: e.governance_mint,
+                        vote_threshold: e.vote_threshold,
+                        timelock_delay: e.timelock_delay,
+                        created_at: e.created_at,
+                        voters: BTreeMap::new(),
+                        proposal_count: 0,
+                        voting_strategy: "linear",
+                        weight_source: "declared".to_string(),
+                        vote_mutability: "free".to_string(),
+                        late_vote_extension: "off".to_string(),
+                        voting_schedule: schedule_name(&VotingSchedule::default()),
+                        nft_collections: BTreeMap::new(),
+                        categories: BTreeMap::new(),
+                    },
+                );
+            }
+            DaoEvent::VoterAdded(e) => {
+                if let Some(governor) = self.governors.get_mut(&e.governor.to_string()) {
+                    governor.voters.insert(e.voter.to_string(), e.weight);
+                }
+            }
+            DaoEvent::VotingStrategySet(e) => {
+                if let Some(governor) = self.governors.get_mut(&e.governor.to_string()) {
+                    governor.voting_strategy = match e.voting_strategy {
+                        VotingStrategy::Linear => "linear",
+                        VotingStrategy::Quadratic => "quadratic",
+                    };
+                }
+            }
+            DaoEvent::WeightSourceSet(e) => {
+                if let Some(governor) = self.governors.get_mut(&e.governor.to_string()) {
+                    governor.weight_source = match e.weight_source {
+                        WeightSource::Declared => "declared".to_string(),
+                        WeightSource::Registry => "registry".to_string(),
+                        WeightSource::Locker => "locker".to_string(),
+                        WeightSource::AddIn { program } => format!("add-in:{program}"),
+                        WeightSource::Nft => "nft".to_string(),
+                    };
+                }
+            }
+            DaoEvent::VoteMutabilitySet(e) => {
+                if let Some(governor) = self.governors.get_mut(&e.governor.to_string()) {
+                    governor.vote_mutability = match e.vote_mutability {
+                        VoteMutability::Free => "free".to_string(),
+                        VoteMutability::Immutable => "immutable".to_string(),
+                        VoteMutability::UntilCutoff { cutoff } => format!("until-cutoff:{cutoff}"),
+                    };
+                }
+            }
+            DaoEvent::LateVoteExtensionSet(e) => {
+                if let Some(governor) = self.governors.get_mut(&e.governor.to_string()) {
+                    governor.late_vote_extension = match e.late_vote_extension {
+                        None => "off".to_string(),
+                        Some(rule) => {
+                            format!("window:{},extension:{},max:{}", rule.window, rule.extension, rule.max_extension)
+                        }
+                    };
+                }
+            }
+            DaoEvent::VotingScheduleSet(e) => {
+                if let Some(governor) = self.governors.get_mut(&e.governor.to_string()) {
+                    governor.voting_schedule = schedule_name(&e.voting_schedule);
+                }
+            }
+            DaoEvent::NftCollectionConfigured(e) => {
+                if let Some(governor) = self.governors.get_mut(&e.governor.to_string()) {
+                    if e.weight == 0 {
+                        governor.nft_collections.remove(&e.collection.to_string());
+                    } else {
+                        governor.nft_collections.insert(e.collection.to_string(), e.weight);
+                    }
+                }
+            }
+            DaoEvent::ProposalCategoryConfigured(e) => {
+                if let Some(governor) = self.governors.get_mut(&e.governor.to_string()) {
+                    governor.categories.insert(
+                        e.id,
+                        CategoryView {
+                            address: e.category,
+                            name: e.name.clone(),
+                            vote_threshold: e.vote_threshold,
+                            quorum: e.quorum,
+                            min_voting_period: e.min_voting_period,
+                            max_voting_period: e.max_voting_period,
+                            timelock_delay: e.timelock_delay,
+                            programs: e.programs.iter().map(Pubkey::to_string).collect(),
+                            emergency: e.emergency,
+                        },
+                    );
+                }
+            }
+            DaoEvent::ProposalCreated(e) => {
+                if let Some(governor) = self.governors.get_mut(&e.governor.to_string()) {
+                    governor.proposal_count = governor.proposal_count.max(e.proposal_id + 1);
+                }
+                let proposal = self.proposal_mut(e.proposal, e.governor, e.proposal_id, ctx);
+                proposal.proposer = Some(e.proposer);
+                proposal.category = e.category;
+                proposal.created_at = Some(e.created_at);
+                proposal.option_votes.resize(e.option_count as usize, 0);
+            }
+            DaoEvent::ProposalActivated(e) => {
+                let proposal = self.proposal_mut(e.proposal, e.governor, e.proposal_id, ctx);
+                proposal.state = state_name(&ProposalState::Active);
+                proposal.activated_at = Some(e.activated_at);
+                let voting_starts_at = e.activated_at.saturating_add(e.review_period);
+                let voting_ends_at = voting_starts_at.saturating_add(e.voting_period);
+                proposal.voting_starts_at = Some(voting_starts_at);
+                proposal.voting_ends_at = Some(voting_ends_at);
+                proposal.reveal_ends_at = (e.reveal_period > 0).then(|| voting_ends_at.saturating_add(e.reveal_period));
+                proposal.timelock_delay = Some(e.timelock_delay);
+            }
+            DaoEvent::VotingExtended(e) => {
+                let proposal = self.proposal_mut(e.proposal, e.governor, e.proposal_id, ctx);
+                proposal.voting_ends_at = Some(e.voting_ends_at);
+            }
+            DaoEvent::ProposalCanceled(e) => {
+                let proposal = self.proposal_mut(e.proposal, e.governor, e.proposal_id, ctx);
+                proposal.state = state_name(&ProposalState::Canceled);
+                proposal.canceled_at = Some(e.canceled_at);
+            }
+            DaoEvent::ProposalQueued(e) => {
+                let proposal = self.proposal_mut(e.proposal, e.governor, e.proposal_id, ctx);
+                proposal.state = state_name(&ProposalState::Queued);
+                proposal.queued_at = Some(e.queued_at);
+                proposal.ready_to_execute_at = Some(e.ready_to_execute_at);
+            }
+            DaoEvent::ProposalFinalised(e) => {
+                // The program's tallies are authoritative over the replayed ones.
+                let proposal = self.proposal_mut(e.proposal, e.governor, e.proposal_id, ctx);
+                proposal.state = state_name(&e.state);
+                proposal.finalised_at = Some(e.finalised_at);
+                proposal.for_votes = e.for_votes;
+                proposal.against_votes = e.against_votes;
+                proposal.abstain_votes = e.abstain_votes;
+                proposal.winning_option = e.winning_option;
+            }
+            DaoEvent::ProposalExecuted(e) => {
+                let proposal = self.proposal_mut(e.proposal, e.governor, e.proposal_id, ctx);
+                proposal.state = state_name(&ProposalState::Executed);
+                proposal.executed_at = Some(e.executed_at);
+            }
+            DaoEvent::VoteCreated(e) => {
+                self.votes.insert(e.vote.to_string(), VoteView::new(e.vote, e.proposal, e.voter));
+            }
+            DaoEvent::VoteSet(e) => {
+                self.record_vote(e, ctx);
+            }
+            DaoEvent::RankedVoteCast(e) => {
+                let Some(&first) = e.ranking.first() else { return };
+                // Counted like a single choice of the first preference.
+                let first_choice = VoteSetEvent {
+                    version: e.version,
+                    governor: e.governor,
+                    vote: e.vote,
+                    proposal: e.proposal,
+                    voter: e.voter,
+                    side: VoteSide::Choice { index: first },
+                    weight: e.weight,
+                    voted_at: e.voted_at,
+                    rationale: None,
+                };
+                self.record_vote(&first_choice, ctx);
+                if let Some(vote) = self.votes.get_mut(&e.vote.to_string()) {
+                    vote.ranking = e.ranking.clone();
+                }
+            }
+            DaoEvent::VoteWithdrawn(e) => {
+                let counted = self.votes.get_mut(&e.vote.to_string()).and_then(|vote| {
+                    let counted = vote.side.map(|side| (side, vote.option, vote.weight));
+                    vote.side = None;
+                    vote.option = None;
+                    vote.weight = 0;
+                    vote.committed = false;
+                    vote.rationale = None;
+                    counted
+                });
+                if let Some((side, option, weight)) = counted {
+                    let proposal_id = self.proposals.get(&e.proposal.to_string()).map_or(0, |p| p.proposal_id);
+                    let proposal = self.proposal_mut(e.proposal, e.governor, proposal_id, ctx);
+                    let tally = proposal.tally(side, option);
+                    *tally = tally.saturating_sub(weight);
+                }
+            }
+            DaoEvent::VoteCommitted(e) => {
+                let vote = self
+                    .votes
+                    .entry(e.vote.to_string())
+                    .or_insert_with(|| VoteView::new(e.vote, e.proposal, e.voter));
+                vote.committed = true;
+            }
+            DaoEvent::NftVoteRecorded(e) => {
+                // The tally moves with the `VoteSetEvent` that follows.
+                let vote = self
+                    .votes
+                    .entry(e.vote.to_string())
+                    .or_insert_with(|| VoteView::new(e.vote, e.proposal, e.voter));
+                vote.nft_mints.push(e.mint.to_string());
+            }
+            DaoEvent::RankedRoundTallied(e) => {
+                let proposal = self.proposal_mut(e.proposal, e.governor, e.proposal_id, ctx);
+                proposal.eliminated_options.push(e.eliminated_option);
+            }
+            DaoEvent::Lock(e) => {
+                self.locks.insert(
+                    e.lock_account.to_string(),
+                    LockView {
+                        address: e.lock_account,
+                        user: e.user,
+                        amount: e.amount,
+                        start_time: e.start_time,
+                        cliff_time: None,
+                        end_time: e.end_time,
+                        claimed_amount: 0,
+                        withdrawn: false,
+                        closed: false,
+                    },
+                );
+            }
+            DaoEvent::VestingLock(e) => {
+                self.locks.insert(
+                    e.lock_account.to_string(),
+                    LockView {
+                        address: e.lock_account,
+                        user: e.user,
+                        amount: e.amount,
+                        start_time: e.start_time,
+                        cliff_time: Some(e.cliff_time),
+                        end_time: e.end_time,
+                        claimed_amount: 0,
+                        withdrawn: false,
+                        closed: false,
+                    },
+                );
+            }
+            DaoEvent::ClaimVested(e) => {
+                if let Some(lock) = self.locks.get_mut(&e.lock_account.to_string()) {
+                    lock.claimed_amount = e.claimed_amount;
+                    lock.withdrawn = e.remaining_locked == 0;
+                }
+            }
+            DaoEvent::Withdraw(e) => {
+                if let Some(lock) = self.locks.get_mut(&e.lock_account.to_string()) {
+                    lock.claimed_amount = lock.claimed_amount.saturating_add(e.amount);
+                    lock.withdrawn = true;
+                }
+            }
+            DaoEvent::NewLocker(e) => {
+                self.lockers.insert(
+                    e.locker.to_string(),
+                    LockerView {
+                        address: e.locker,
+                        governor: e.governor,
+                        mint: e.mint,
+                        voting_power_multiplier: e.voting_power_multiplier,
+                        min_lock_duration: e.min_lock_duration,
+                        max_lock_duration: e.max_lock_duration,
+                        total_escrowed: 0,
+                    },
+                );
+            }
+            DaoEvent::LockerSetParams(e) => {
+                if let Some(locker) = self.lockers.get_mut(&e.locker.to_string()) {
+                    locker.voting_power_multiplier = e.new_voting_power_multiplier;
+                    locker.min_lock_duration = e.new_min_lock_duration;
+                    locker.max_lock_duration = e.new_max_lock_duration;
+                }
+            }
+            DaoEvent::NewEscrow(e) => {
+                let locker = self.lockers.get_mut(&e.locker.to_string());
+                let multiplier = locker.map(|locker| {
+                    locker.total_escrowed = locker.total_escrowed.saturating_add(e.amount);
+                    locker.voting_power_multiplier
+                });
+                self.escrows.insert(
+                    e.escrow.to_string(),
+                    EscrowView {
+                        address: e.escrow,
+                        locker: e.locker,
+                        user: e.user,
+                        amount: e.amount,
+                        start_time: e.start_time,
+                        end_time: e.end_time,
+                        voting_power_multiplier: multiplier,
+                        withdrawn: false,
+                        closed: false,
+                    },
+                );
+            }
+            DaoEvent::EscrowWithdrawn(e) => {
+                if let Some(escrow) = self.escrows.get_mut(&e.escrow.to_string()) {
+                    escrow.withdrawn = true;
+                }
+                if let Some(locker) = self.lockers.get_mut(&e.locker.to_string()) {
+                    locker.total_escrowed = locker.total_escrowed.saturating_sub(e.amount);
+                }
+            }
+            DaoEvent::ProposalClosed(e) => {
+                let proposal = self.proposal_mut(e.proposal, e.governor, e.proposal_id, ctx);
+                proposal.proposer = Some(e.proposer);
+                proposal.state = state_name(&e.state);
+                proposal.for_votes = e.for_votes;
+                proposal.against_votes = e.against_votes;
+                proposal.abstain_votes = e.abstain_votes;
+                proposal.option_votes = e.option_votes.clone();
+                proposal.winning_option = e.winning_option;
+                proposal.closed = true;
+            }
+            DaoEvent::VoteClosed(e) => {
+                if let Some(vote) = self.votes.get_mut(&e.vote.to_string()) {
+                    vote.closed = true;
+                }
+            }
+            // Records only stop an NFT voting twice; closing one changes no view.
+            DaoEvent::NftVoteRecordClosed(_) => {}
+            DaoEvent::LockClosed(e) => {
+                if let Some(lock) = self.locks.get_mut(&e.lock_account.to_string()) {
+                    lock.withdrawn = true;
+                    lock.closed = true;
+                }
+            }
+            DaoEvent::EscrowClosed(e) => {
+                if let Some(escrow) = self.escrows.get_mut(&e.escrow.to_string()) {
+                    escrow.closed = true;
+                }
+            }
+        }
+    }
+}
+
+/// Serialises keys as base58 strings rather than byte arrays.
+mod pubkey {
+    use anchor_lang::prelude::Pubkey;
+    use serde::Serializer;
+
+    pub fn serialize<S: Serializer>(key: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(key)
+    }
+
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(key: &Option<Pubkey>, serializer: S) -> Result<S::Ok, S::Error> {
+            match key {
+                Some(key) => serializer.collect_str(key),
+                None => serializer.serialize_none(),
+            }
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! Event-sourced index of iterative-dao state.
+//!
+//! Transactions come from JSON dumps or an RPC node ([`source`]), their logs
+//! are reduced to the program's own events ([`logs`], [`events`]) and folded
+//! into an in-memory [`index::Index`] that can be queried or dumped as JSON.
+
+pub mod events;
+pub mod index;
+pub mod logs;
+pub mod source;
+
+pub use index::Index;
+pub use source::TransactionRecord;
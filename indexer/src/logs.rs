@@ -0,0 +1,39 @@
+//! Extraction of `emit!` payloads from transaction logs.
+
+use anchor_lang::prelude::Pubkey;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Decoded `Program data:` payloads logged while `program_id` was the
+/// executing program, in log order.
+///
+/// Other programs can log the same prefix, so the invocation stack is
+/// tracked from the runtime's `invoke` / `success` / `failed` lines.
+pub fn program_data(logs: &[String], program_id: &Pubkey) -> Vec<Vec<u8>> {
+    let invoke = format!("Program {program_id} invoke");
+    let mut stack: Vec<bool> = Vec::new();
+    let mut payloads = Vec::new();
+
+    for line in logs {
+        if let Some(rest) = line.strip_prefix("Program ") {
+            if rest.contains(" invoke [") {
+                stack.push(line.starts_with(&invoke));
+                continue;
+            }
+            if rest.ends_with(" success") || rest.contains(" failed: ") {
+                stack.pop();
+                continue;
+            }
+        }
+        if stack.last() != Some(&true) {
+            continue;
+        }
+        if let Some(data) = line.strip_prefix(PROGRAM_DATA) {
+            if let Ok(bytes) = STANDARD.decode(data) {
+                payloads.push(bytes);
+            }
+        }
+    }
+    payloads
+}
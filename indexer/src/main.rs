@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
+use iterative_dao_indexer::{source, Index};
+use serde::Serialize;
+use solana_rpc_client::rpc_client::RpcClient;
+
+/// Rebuild iterative-dao state from program events and query it.
+#[derive(Parser)]
+#[command(name = "iterative-dao-indexer", version)]
+struct Cli {
+    /// Transaction dumps: JSON arrays or JSON lines of `getTransaction` results.
+    #[arg(long = "json", global = true)]
+    dumps: Vec<PathBuf>,
+    /// Also backfill from this RPC endpoint.
+    #[arg(short, long, global = true, env = "ITERATIVE_DAO_URL")]
+    url: Option<String>,
+    /// Most recent transactions to fetch from `--url`.
+    #[arg(long, global = true, default_value_t = 1000)]
+    limit: usize,
+    /// Program to index, if not the default deployment.
+    #[arg(long, global = true, default_value_t = iterative_dao::ID)]
+    program_id: Pubkey,
+    #[command(subcommand)]
+    query: Query,
+}
+
+#[derive(Subcommand)]
+enum Query {
+    /// The whole index.
+    Snapshot,
+    Governor { address: Pubkey },
+    /// Proposals of a governor.
+    Proposals { governor: Pubkey },
+    /// One proposal with its votes.
+    Proposal { address: Pubkey },
+    /// Open locks and escrows of a user.
+    Positions { user: Pubkey },
+}
+
+fn print(value: &impl Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut records = Vec::new();
+    for path in &cli.dumps {
+        records.extend(source::load_json(path)?);
+    }
+    if let Some(url) = &cli.url {
+        records.extend(source::fetch_rpc(&RpcClient::new(url.clone()), &cli.program_id, cli.limit)?);
+    }
+    if records.is_empty() {
+        bail!("nothing to index; pass --json and/or --url");
+    }
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &cli.program_id);
+
+    match cli.query {
+        Query::Snapshot => print(&index),
+        Query::Governor { address } => print(&index.governor(&address)),
+        Query::Proposals { governor } => print(&index.proposals_of(&governor)),
+        Query::Proposal { address } => print(&serde_json::json!({
+            "proposal": index.proposal(&address),
+            "votes": index.votes_on(&address),
+        })),
+        Query::Positions { user } => {
+            let (locks, escrows) = index.positions_of(&user);
+            print(&serde_json::json!({ "locks": locks, "escrows": escrows }))
+        }
+    }
+}
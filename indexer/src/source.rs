@@ -0,0 +1,141 @@
+//! Transaction sources: JSON dumps and an RPC node.
+
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::request::RpcRequest;
+
+use crate::{
+    events::DaoEvent,
+    index::{EventContext, Index},
+    logs::program_data,
+};
+
+/// The parts of a confirmed transaction the indexer needs.
+#[derive(Clone, Debug, Default)]
+pub struct TransactionRecord {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// Failed transactions are skipped: their events never took effect.
+    pub failed: bool,
+    pub logs: Vec<String>,
+}
+
+impl TransactionRecord {
+    /// Reads either a `getTransaction` result (JSON encoding) or a flat
+    /// `{signature, slot, blockTime, err, logs}` object.
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let slot = value["slot"].as_u64().context("missing slot")?;
+        let block_time = value["blockTime"].as_i64();
+        let (signature, err, logs) = match value.get("meta") {
+            Some(meta) => (&value["transaction"]["signatures"][0], &meta["err"], &meta["logMessages"]),
+            None => (&value["signature"], &value["err"], &value["logs"]),
+        };
+        let logs = logs
+            .as_array()
+            .context("missing log messages")?
+            .iter()
+            .filter_map(|line| line.as_str().map(str::to_owned))
+            .collect();
+        Ok(Self {
+            signature: signature.as_str().context("missing signature")?.to_owned(),
+            slot,
+            block_time,
+            failed: !err.is_null(),
+            logs,
+        })
+    }
+
+    pub fn events(&self, program_id: &Pubkey) -> Vec<DaoEvent> {
+        if self.failed {
+            return Vec::new();
+        }
+        program_data(&self.logs, program_id)
+            .iter()
+            .filter_map(|data| DaoEvent::decode(data))
+            .collect()
+    }
+}
+
+/// Loads records from a file holding a JSON array or one object per line.
+pub fn load_json(path: &Path) -> Result<Vec<TransactionRecord>> {
+    let contents = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let values: Vec<Value> = match serde_json::from_str(&contents) {
+        Ok(Value::Array(values)) => values,
+        Ok(value) => vec![value],
+        Err(_) => contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()
+            .with_context(|| format!("{} is neither JSON nor JSON lines", path.display()))?,
+    };
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| TransactionRecord::from_json(value).with_context(|| format!("record {i}")))
+        .collect()
+}
+
+/// Fetches up to `limit` of the program's most recent transactions, oldest first.
+pub fn fetch_rpc(rpc: &RpcClient, program_id: &Pubkey, limit: usize) -> Result<Vec<TransactionRecord>> {
+    let mut signatures: Vec<String> = Vec::new();
+    let mut before: Option<String> = None;
+    while signatures.len() < limit {
+        let page: Value = rpc.send(
+            RpcRequest::GetSignaturesForAddress,
+            json!([program_id.to_string(), {
+                "limit": (limit - signatures.len()).min(1000),
+                "before": before,
+                "commitment": "confirmed",
+            }]),
+        )?;
+        let page = page.as_array().context("unexpected getSignaturesForAddress response")?;
+        if page.is_empty() {
+            break;
+        }
+        for entry in page {
+            signatures.push(entry["signature"].as_str().context("missing signature")?.to_owned());
+        }
+        before = signatures.last().cloned();
+    }
+
+    let mut records = Vec::with_capacity(signatures.len());
+    for signature in signatures.iter().rev() {
+        let value: Value = rpc.send(
+            RpcRequest::GetTransaction,
+            json!([signature, {
+                "encoding": "json",
+                "commitment": "confirmed",
+                "maxSupportedTransactionVersion": 0,
+            }]),
+        )?;
+        if value.is_null() {
+            bail!("transaction {signature} is no longer available");
+        }
+        records.push(TransactionRecord::from_json(&value)?);
+    }
+    Ok(records)
+}
+
+impl Index {
+    /// Applies every event of `records`, ordered by slot. Records within a
+    /// slot keep their given order.
+    pub fn ingest(&mut self, records: &mut [TransactionRecord], program_id: &Pubkey) {
+        records.sort_by_key(|record| record.slot);
+        for record in records.iter() {
+            let ctx = EventContext {
+                signature: record.signature.clone(),
+                slot: record.slot,
+                block_time: record.block_time,
+            };
+            for event in record.events(program_id) {
+                self.apply(&event, &ctx);
+            }
+        }
+    }
+}
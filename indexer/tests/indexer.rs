@@ -0,0 +1,736 @@
+use anchor_lang::{prelude::Pubkey, Event};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use iterative_dao::{
+    enums::{ProposalState, VoteMutability, VoteSide, VotingStrategy, WeightSource},
+    events::*,
+    state::{LateVoteExtension, VoteRationale, VotingSchedule},
+};
+use iterative_dao_client::pda::{find_escrow_address, find_governor_address, find_lock_address, find_locker_address};
+use iterative_dao_indexer::{logs::program_data, Index, TransactionRecord};
+use serde_json::json;
+
+fn program_logs(events: &[&dyn Fn() -> Vec<u8>]) -> Vec<String> {
+    let mut logs = vec![format!("Program {} invoke [1]", iterative_dao::ID)];
+    for event in events {
+        logs.push(format!("Program data: {}", STANDARD.encode(event())));
+    }
+    logs.push(format!("Program {} success", iterative_dao::ID));
+    logs
+}
+
+fn record(slot: u64, logs: Vec<String>) -> TransactionRecord {
+    TransactionRecord { signature: format!("sig{slot}"), slot, logs, ..TransactionRecord::default() }
+}
+
+fn vote_set(vote: Pubkey, proposal: Pubkey, side: VoteSide, weight: u64) -> Vec<u8> {
+    VoteSetEvent {
+        version: EVENT_VERSION,
+        governor: Pubkey::default(),
+        vote,
+        proposal,
+        voter: Pubkey::new_unique(),
+        side,
+        weight,
+        voted_at: 0,
+        rationale: None,
+    }
+    .data()
+}
+
+fn canceled(proposal: Pubkey) -> ProposalCanceled {
+    ProposalCanceled {
+        version: EVENT_VERSION,
+        governor: Pubkey::default(),
+        proposal,
+        proposal_id: 0,
+        canceled_by: Pubkey::new_unique(),
+        canceled_at: 1,
+    }
+}
+
+#[test]
+fn ignores_data_logged_by_other_programs() {
+    let other = Pubkey::new_unique();
+    let event = canceled(Pubkey::new_unique()).data();
+    let encoded = STANDARD.encode(&event);
+    let logs = vec![
+        format!("Program {} invoke [1]", iterative_dao::ID),
+        format!("Program {other} invoke [2]"),
+        format!("Program data: {encoded}"),
+        format!("Program {other} success"),
+        format!("Program data: {encoded}"),
+        format!("Program {} success", iterative_dao::ID),
+        format!("Program {other} invoke [1]"),
+        format!("Program data: {encoded}"),
+        format!("Program {other} failed: custom program error: 0x1"),
+    ];
+
+    assert_eq!(program_data(&logs, &iterative_dao::ID), vec![event]);
+}
+
+#[test]
+fn rebuilds_tallies_across_vote_changes() {
+    let proposal = Pubkey::new_unique();
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let activated = ProposalActivated {
+        version: EVENT_VERSION,
+        governor: Pubkey::default(),
+        proposal,
+        proposal_id: 0,
+        activated_at: 100,
+        voting_period: 50,
+        timelock_delay: 10,
+        review_period: 0,
+        reveal_period: 0,
+    };
+    let mut records = vec![
+        // Deliberately out of order: ingestion sorts by slot.
+        record(3, program_logs(&[&|| vote_set(a, proposal, VoteSide::Against {}, 40)])),
+        record(1, program_logs(&[&|| activated.data()])),
+        record(
+            2,
+            program_logs(&[
+                &|| vote_set(a, proposal, VoteSide::For {}, 30),
+                &|| vote_set(b, proposal, VoteSide::Abstain {}, 5),
+            ]),
+        ),
+    ];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+
+    let view = index.proposal(&proposal).unwrap();
+    assert_eq!(view.state, "Active");
+    assert_eq!(view.voting_ends_at, Some(150));
+    assert_eq!((view.for_votes, view.against_votes, view.abstain_votes), (0, 40, 5));
+    assert_eq!(index.votes_on(&proposal).len(), 2);
+    assert_eq!(view.last_signature, "sig3");
+}
+
+#[test]
+fn failed_transactions_are_skipped() {
+    let proposal = Pubkey::new_unique();
+    let canceled = canceled(proposal);
+    let mut failed = record(1, program_logs(&[&|| canceled.data()]));
+    failed.failed = true;
+
+    let mut index = Index::default();
+    index.ingest(&mut [failed], &iterative_dao::ID);
+    assert!(index.proposal(&proposal).is_none());
+    assert_eq!(index.events, 0);
+}
+
+fn governor_created(governor: Pubkey, smart_wallet: Pubkey) -> GovernorCreated {
+    GovernorCreated {
+        version: EVENT_VERSION,
+        governor,
+        smart_wallet,
+        electorate: Pubkey::new_unique(),
+        vote_threshold: 60,
+        timelock_delay: 3600,
+        governance_mint: Pubkey::new_unique(),
+        created_at: 0,
+    }
+}
+
+#[test]
+fn tracks_lock_positions_through_withdrawal() {
+    let wallet = Pubkey::new_unique();
+    let governor = find_governor_address(&wallet).0;
+    let user = Pubkey::new_unique();
+    let lock_account = find_lock_address(&governor, &user, 7).0;
+    let created = governor_created(governor, wallet);
+    let lock = LockEvent {
+        version: EVENT_VERSION,
+        governor,
+        lock_account,
+        user,
+        amount: 100,
+        start_time: 0,
+        end_time: 10,
+        lock_id: 7,
+    };
+    let withdraw = WithdrawEvent {
+        version: EVENT_VERSION,
+        governor,
+        lock_account,
+        user,
+        amount: 100,
+        lock_id: 7,
+        withdrawn_at: 10,
+    };
+
+    let mut index = Index::default();
+    index.ingest(&mut [record(1, program_logs(&[&|| created.data(), &|| lock.data()]))], &iterative_dao::ID);
+    assert_eq!(index.positions_of(&user).0.len(), 1);
+
+    index.ingest(&mut [record(2, program_logs(&[&|| withdraw.data()]))], &iterative_dao::ID);
+    assert!(index.positions_of(&user).0.is_empty());
+    let view = &index.locks[&lock_account.to_string()];
+    assert!(view.withdrawn);
+    assert_eq!(view.claimed_amount, 100);
+}
+
+#[test]
+fn tracks_escrows_through_withdrawal() {
+    let governor = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let locker = find_locker_address(&governor, &Pubkey::new_unique()).0;
+    let escrow = find_escrow_address(&locker, &user, 1).0;
+    let new_locker = NewLockerEvent {
+        version: EVENT_VERSION,
+        locker,
+        governor,
+        mint: Pubkey::new_unique(),
+        voting_power_multiplier: 2,
+        min_lock_duration: 0,
+        max_lock_duration: 100,
+    };
+    let new_escrow = NewEscrowEvent {
+        version: EVENT_VERSION,
+        governor,
+        escrow,
+        locker,
+        user,
+        amount: 40,
+        start_time: 0,
+        end_time: 100,
+        escrow_id: 1,
+    };
+    let withdrawn = EscrowWithdrawn {
+        version: EVENT_VERSION,
+        governor,
+        escrow,
+        locker,
+        user,
+        amount: 40,
+        escrow_id: 1,
+        withdrawn_at: 100,
+    };
+
+    let mut index = Index::default();
+    index.ingest(&mut [record(1, program_logs(&[&|| new_locker.data(), &|| new_escrow.data()]))], &iterative_dao::ID);
+    assert_eq!(index.positions_of(&user).1.len(), 1);
+    assert_eq!(index.lockers[&locker.to_string()].total_escrowed, 40);
+
+    index.ingest(&mut [record(2, program_logs(&[&|| withdrawn.data()]))], &iterative_dao::ID);
+    assert!(index.positions_of(&user).1.is_empty());
+    assert!(index.escrows[&escrow.to_string()].withdrawn);
+    assert_eq!(index.lockers[&locker.to_string()].total_escrowed, 0);
+}
+
+#[test]
+fn reads_get_transaction_json() {
+    let proposal = Pubkey::new_unique();
+    let queued = ProposalQueued {
+        version: EVENT_VERSION,
+        governor: Pubkey::default(),
+        proposal,
+        proposal_id: 0,
+        queued_at: 5,
+        ready_to_execute_at: 15,
+    };
+    let value = json!({
+        "slot": 42,
+        "blockTime": 1_700_000_000,
+        "transaction": { "signatures": ["abc"], "message": {} },
+        "meta": { "err": null, "logMessages": program_logs(&[&|| queued.data()]) },
+    });
+
+    let record = TransactionRecord::from_json(&value).unwrap();
+    assert_eq!((record.signature.as_str(), record.slot, record.block_time), ("abc", 42, Some(1_700_000_000)));
+
+    let mut index = Index::default();
+    index.ingest(&mut [record], &iterative_dao::ID);
+    let view = index.proposal(&proposal).unwrap();
+    assert_eq!((view.state, view.ready_to_execute_at), ("Queued", Some(15)));
+
+    let snapshot = serde_json::to_value(&index).unwrap();
+    assert_eq!(snapshot["proposals"][proposal.to_string()]["address"], proposal.to_string());
+}
+
+#[test]
+fn follows_proposal_lifecycle_events() {
+    let wallet = Pubkey::new_unique();
+    let governor = find_governor_address(&wallet).0;
+    let (proposal, proposer) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let created = governor_created(governor, wallet);
+    let voter_added = VoterAdded {
+        version: EVENT_VERSION,
+        governor,
+        voter: proposer,
+        weight: 100,
+        voter_count: 1,
+        added_at: 0,
+    };
+    let proposal_created = ProposalCreated {
+        version: EVENT_VERSION,
+        governor,
+        proposal,
+        proposal_id: 0,
+        proposer,
+        instruction_count: 1,
+        option_count: 0,
+        category: None,
+        created_at: 1,
+    };
+    let finalised = ProposalFinalised {
+        version: EVENT_VERSION,
+        governor,
+        proposal,
+        proposal_id: 0,
+        state: ProposalState::Succeeded,
+        for_votes: 70,
+        against_votes: 30,
+        abstain_votes: 0,
+        winning_option: None,
+        finalised_at: 60,
+    };
+    let executed = ProposalExecuted { version: EVENT_VERSION, governor, proposal, proposal_id: 0, executed_at: 3700 };
+    let mut records = vec![
+        record(1, program_logs(&[&|| created.data(), &|| voter_added.data(), &|| proposal_created.data()])),
+        record(2, program_logs(&[&|| finalised.data()])),
+        record(3, program_logs(&[&|| executed.data()])),
+    ];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+
+    let governor_view = index.governor(&governor).unwrap();
+    assert_eq!(governor_view.voters[&proposer.to_string()], 100);
+    assert_eq!(governor_view.proposal_count, 1);
+    let view = index.proposal(&proposal).unwrap();
+    assert_eq!((view.state, view.proposer), ("Executed", Some(proposer)));
+    assert_eq!((view.for_votes, view.against_votes), (70, 30));
+    assert_eq!((view.finalised_at, view.executed_at), (Some(60), Some(3700)));
+    assert_eq!(index.proposals_of(&governor).len(), 1);
+}
+
+#[test]
+fn skips_events_with_unknown_version() {
+    let proposal = Pubkey::new_unique();
+    let future = ProposalCanceled { version: EVENT_VERSION + 1, ..canceled(proposal) };
+
+    let mut index = Index::default();
+    index.ingest(&mut [record(1, program_logs(&[&|| future.data()]))], &iterative_dao::ID);
+    assert!(index.proposal(&proposal).is_none());
+}
+
+#[test]
+fn tallies_multiple_choice_options() {
+    let (governor, proposal) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let created = ProposalCreated {
+        version: EVENT_VERSION,
+        governor,
+        proposal,
+        proposal_id: 0,
+        proposer: Pubkey::new_unique(),
+        instruction_count: 3,
+        option_count: 3,
+        category: None,
+        created_at: 1,
+    };
+    let mut records = vec![
+        record(1, program_logs(&[&|| created.data()])),
+        record(
+            2,
+            program_logs(&[
+                &|| vote_set(a, proposal, VoteSide::Choice { index: 0 }, 30),
+                &|| vote_set(b, proposal, VoteSide::Choice { index: 2 }, 20),
+            ]),
+        ),
+        record(3, program_logs(&[&|| vote_set(a, proposal, VoteSide::Choice { index: 1 }, 30)])),
+    ];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+
+    let view = index.proposal(&proposal).unwrap();
+    assert_eq!(view.option_votes, vec![0, 30, 20]);
+    assert_eq!(index.votes[&a.to_string()].option, Some(1));
+}
+
+#[test]
+fn close_keeps_final_option_tallies() {
+    let proposal = Pubkey::new_unique();
+    let closed = ProposalClosed {
+        version: EVENT_VERSION,
+        proposal,
+        governor: Pubkey::new_unique(),
+        proposal_id: 4,
+        proposer: Pubkey::new_unique(),
+        state: ProposalState::Succeeded,
+        for_votes: 0,
+        against_votes: 0,
+        abstain_votes: 5,
+        option_votes: vec![10, 40, 25],
+        winning_option: Some(1),
+        finalised_at: 10,
+        closed_at: 20,
+    };
+    let mut records = vec![record(1, program_logs(&[&|| closed.data()]))];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+
+    let view = index.proposal(&proposal).unwrap();
+    assert_eq!((view.option_votes.clone(), view.winning_option), (vec![10, 40, 25], Some(1)));
+    assert!(view.closed);
+}
+
+#[test]
+fn follows_ranked_ballots_and_eliminations() {
+    let (proposal, vote) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let ranked = RankedVoteCast {
+        version: EVENT_VERSION,
+        governor: Pubkey::default(),
+        vote,
+        proposal,
+        voter: Pubkey::new_unique(),
+        ranking: vec![2, 0],
+        weight: 25,
+        voted_at: 1,
+    };
+    let round = RankedRoundTallied {
+        version: EVENT_VERSION,
+        governor: Pubkey::default(),
+        proposal,
+        proposal_id: 0,
+        round: 0,
+        option_votes: vec![40, 35, 25],
+        eliminated_option: 2,
+    };
+    let mut records = vec![record(1, program_logs(&[&|| ranked.data()])), record(2, program_logs(&[&|| round.data()]))];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+
+    let view = index.proposal(&proposal).unwrap();
+    assert_eq!(view.option_votes, vec![0, 0, 25]);
+    assert_eq!(view.eliminated_options, vec![2]);
+    assert_eq!(index.votes[&vote.to_string()].ranking, vec![2, 0]);
+    assert_eq!(index.votes[&vote.to_string()].option, Some(2));
+}
+
+#[test]
+fn tracks_governor_voting_settings() {
+    let wallet = Pubkey::new_unique();
+    let governor = find_governor_address(&wallet).0;
+    let created = governor_created(governor, wallet);
+    let set = VotingStrategySet {
+        version: EVENT_VERSION,
+        governor,
+        voting_strategy: VotingStrategy::Quadratic,
+        set_at: 2,
+    };
+    let add_in = Pubkey::new_unique();
+    let source = WeightSourceSet {
+        version: EVENT_VERSION,
+        governor,
+        weight_source: WeightSource::AddIn { program: add_in },
+        set_at: 2,
+    };
+    let mutability = VoteMutabilitySet {
+        version: EVENT_VERSION,
+        governor,
+        vote_mutability: VoteMutability::UntilCutoff { cutoff: 600 },
+        set_at: 2,
+    };
+    let extension = LateVoteExtensionSet {
+        version: EVENT_VERSION,
+        governor,
+        late_vote_extension: Some(LateVoteExtension { window: 600, extension: 300, max_extension: 900 }),
+        set_at: 2,
+    };
+    let mut records = vec![
+        record(1, program_logs(&[&|| created.data()])),
+        record(2, program_logs(&[&|| set.data(), &|| source.data(), &|| mutability.data(), &|| extension.data()])),
+    ];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+
+    let view = index.governor(&governor).unwrap();
+    assert_eq!(view.voting_strategy, "quadratic");
+    assert_eq!(view.weight_source, format!("add-in:{add_in}"));
+    assert_eq!(view.vote_mutability, "until-cutoff:600");
+    assert_eq!(view.late_vote_extension, "window:600,extension:300,max:900");
+}
+
+#[test]
+fn follows_voting_extensions() {
+    let proposal = Pubkey::new_unique();
+    let activated = ProposalActivated {
+        version: EVENT_VERSION,
+        governor: Pubkey::default(),
+        proposal,
+        proposal_id: 0,
+        activated_at: 100,
+        voting_period: 50,
+        timelock_delay: 10,
+        review_period: 0,
+        reveal_period: 0,
+    };
+    let extended = VotingExtended {
+        version: EVENT_VERSION,
+        governor: Pubkey::default(),
+        proposal,
+        proposal_id: 0,
+        voting_period: 80,
+        voting_ends_at: 180,
+        extended_at: 140,
+    };
+    let mut records = vec![
+        record(1, program_logs(&[&|| activated.data()])),
+        record(
+            2,
+            program_logs(&[&|| vote_set(Pubkey::new_unique(), proposal, VoteSide::For {}, 5), &|| extended.data()]),
+        ),
+    ];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+
+    assert_eq!(index.proposal(&proposal).unwrap().voting_ends_at, Some(180));
+}
+
+#[test]
+fn withdrawn_votes_leave_the_tally() {
+    let proposal = Pubkey::new_unique();
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let withdrawn = VoteWithdrawn {
+        version: EVENT_VERSION,
+        governor: Pubkey::default(),
+        vote: a,
+        proposal,
+        voter: Pubkey::new_unique(),
+        side: VoteSide::For {},
+        weight: 30,
+        withdrawn_at: 3,
+    };
+    let mut records = vec![
+        record(
+            1,
+            program_logs(&[
+                &|| vote_set(a, proposal, VoteSide::For {}, 30),
+                &|| vote_set(b, proposal, VoteSide::For {}, 5),
+            ]),
+        ),
+        record(2, program_logs(&[&|| withdrawn.data()])),
+    ];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+
+    assert_eq!(index.proposal(&proposal).unwrap().for_votes, 5);
+    let vote = &index.votes[&a.to_string()];
+    assert_eq!((vote.side, vote.weight), (None, 0));
+}
+
+#[test]
+fn keeps_rationale_until_the_vote_moves() {
+    let (proposal, vote) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let cast = VoteSetEvent {
+        version: EVENT_VERSION,
+        governor: Pubkey::default(),
+        vote,
+        proposal,
+        voter: Pubkey::new_unique(),
+        side: VoteSide::For {},
+        weight: 30,
+        voted_at: 2,
+        rationale: Some(VoteRationale {
+            reason: "Funds the audit".to_string(),
+            uri: "https://forum/1".to_string(),
+            uri_hash: [0xab; 32],
+        }),
+    };
+    let mut records = vec![record(1, program_logs(&[&|| cast.data()]))];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+    let rationale = index.votes[&vote.to_string()].rationale.clone().unwrap();
+    assert_eq!((rationale.reason.as_str(), rationale.uri.as_str()), ("Funds the audit", "https://forum/1"));
+    assert_eq!(rationale.uri_hash, "ab".repeat(32));
+
+    let mut records = vec![record(2, program_logs(&[&|| vote_set(vote, proposal, VoteSide::Against {}, 30)]))];
+    index.ingest(&mut records, &iterative_dao::ID);
+    assert!(index.votes[&vote.to_string()].rationale.is_none());
+}
+
+#[test]
+fn tracks_nft_collections_and_vote_mints() {
+    let wallet = Pubkey::new_unique();
+    let governor = find_governor_address(&wallet).0;
+    let created = governor_created(governor, wallet);
+    let (common, rare) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let configured = |collection, weight| NftCollectionConfigured {
+        version: EVENT_VERSION,
+        governor,
+        collection,
+        weight,
+        configured_at: 1,
+    };
+    let (proposal, vote, mint) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let recorded = NftVoteRecorded {
+        version: EVENT_VERSION,
+        governor,
+        proposal,
+        vote,
+        voter: Pubkey::new_unique(),
+        mint,
+        collection: rare,
+        weight: 10,
+        voted_at: 2,
+    };
+    let mut records = vec![
+        record(
+            1,
+            program_logs(&[&|| created.data(), &|| configured(common, 1).data(), &|| configured(rare, 10).data()]),
+        ),
+        record(2, program_logs(&[&|| configured(common, 0).data()])),
+        record(3, program_logs(&[&|| recorded.data(), &|| vote_set(vote, proposal, VoteSide::For {}, 10)])),
+    ];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+
+    let view = index.governor(&governor).unwrap();
+    assert_eq!(view.nft_collections.get(&rare.to_string()), Some(&10));
+    assert!(!view.nft_collections.contains_key(&common.to_string()));
+    assert_eq!(index.votes[&vote.to_string()].nft_mints, vec![mint.to_string()]);
+    assert_eq!(index.proposal(&proposal).unwrap().for_votes, 10);
+}
+
+#[test]
+fn follows_secret_ballot_commitments() {
+    let (proposal, vote) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let activated = ProposalActivated {
+        version: EVENT_VERSION,
+        governor: Pubkey::default(),
+        proposal,
+        proposal_id: 0,
+        activated_at: 100,
+        voting_period: 50,
+        timelock_delay: 10,
+        review_period: 0,
+        reveal_period: 20,
+    };
+    let committed = VoteCommitted {
+        version: EVENT_VERSION,
+        governor: Pubkey::default(),
+        vote,
+        proposal,
+        voter: Pubkey::new_unique(),
+        commitment: [1; 32],
+        committed_at: 120,
+    };
+    let mut records = vec![
+        record(1, program_logs(&[&|| activated.data(), &|| committed.data()])),
+        record(2, program_logs(&[&|| vote_set(vote, proposal, VoteSide::Against {}, 40)])),
+    ];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+
+    let view = index.proposal(&proposal).unwrap();
+    assert_eq!((view.voting_ends_at, view.reveal_ends_at), (Some(150), Some(170)));
+    assert_eq!(view.against_votes, 40);
+    let vote = &index.votes[&vote.to_string()];
+    assert!(vote.committed);
+    assert_eq!(vote.side, Some("Against"));
+}
+
+#[test]
+fn records_proposal_categories() {
+    let wallet = Pubkey::new_unique();
+    let governor = find_governor_address(&wallet).0;
+    let created = governor_created(governor, wallet);
+    let (category, program) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let configured = |quorum| ProposalCategoryConfigured {
+        version: EVENT_VERSION,
+        governor,
+        category,
+        id: 1,
+        name: "treasury".to_string(),
+        vote_threshold: 66,
+        quorum,
+        min_voting_period: 3_600,
+        max_voting_period: 86_400,
+        timelock_delay: 7_200,
+        programs: vec![program],
+        emergency: true,
+        configured_at: 1,
+    };
+    let proposal = Pubkey::new_unique();
+    let proposal_created = ProposalCreated {
+        version: EVENT_VERSION,
+        governor,
+        proposal,
+        proposal_id: 0,
+        proposer: Pubkey::new_unique(),
+        instruction_count: 1,
+        option_count: 0,
+        category: Some(1),
+        created_at: 2,
+    };
+    let mut records = vec![
+        record(1, program_logs(&[&|| created.data(), &|| configured(100).data()])),
+        record(2, program_logs(&[&|| configured(500).data(), &|| proposal_created.data()])),
+    ];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+
+    let view = &index.governor(&governor).unwrap().categories[&1];
+    assert_eq!((view.address, view.name.as_str()), (category, "treasury"));
+    assert_eq!((view.vote_threshold, view.quorum, view.timelock_delay), (66, 500, 7_200));
+    assert_eq!(view.programs, vec![program.to_string()]);
+    assert!(view.emergency);
+    assert_eq!(index.proposal(&proposal).unwrap().category, Some(1));
+}
+
+#[test]
+fn follows_voting_schedule_and_review_period() {
+    let wallet = Pubkey::new_unique();
+    let governor = find_governor_address(&wallet).0;
+    let created = governor_created(governor, wallet);
+    let schedule = VotingScheduleSet {
+        version: EVENT_VERSION,
+        governor,
+        voting_schedule: VotingSchedule {
+            min_voting_period: 3600,
+            max_voting_period: 86_400,
+            default_voting_period: 7200,
+            review_period: 600,
+        },
+        set_at: 2,
+    };
+    let proposal = Pubkey::new_unique();
+    let activated = ProposalActivated {
+        version: EVENT_VERSION,
+        governor,
+        proposal,
+        proposal_id: 0,
+        activated_at: 100,
+        voting_period: 7200,
+        timelock_delay: 10,
+        review_period: 600,
+        reveal_period: 300,
+    };
+    let mut records = vec![
+        record(1, program_logs(&[&|| created.data()])),
+        record(2, program_logs(&[&|| schedule.data()])),
+        record(3, program_logs(&[&|| activated.data()])),
+    ];
+
+    let mut index = Index::default();
+    index.ingest(&mut records, &iterative_dao::ID);
+
+    let view = index.governor(&governor).unwrap();
+    assert_eq!(view.voting_schedule, "min:3600,max:86400,default:7200,review:600");
+    let view = index.proposal(&proposal).unwrap();
+    assert_eq!((view.activated_at, view.voting_starts_at), (Some(100), Some(700)));
+    assert_eq!((view.voting_ends_at, view.reveal_ends_at), (Some(7900), Some(8200)));
+}
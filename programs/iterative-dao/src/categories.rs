@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::clock::Clock;
+use crate::{
+    contexts::ConfigureProposalCategory,
+    errors::ErrorCode,
+    events::{ProposalCategoryConfigured, EVENT_VERSION},
+    state::{ProposalCategory, ProposalCategoryConfig, ProposalInstruction},
+};
+
+/// Creates category `id` or replaces its terms.
+///
+/// Proposals already created keep the terms they were created with.
+pub fn configure_proposal_category(
+    ctx: Context<ConfigureProposalCategory>,
+    id: u8,
+    config: ProposalCategoryConfig,
+) -> Result<()> {
+    let governor = &mut ctx.accounts.governor;
+    require!(config.is_valid(&governor.voting_schedule), ErrorCode::InvalidProposalCategory);
+    let category = &mut ctx.accounts.category;
+    if category.governor == Pubkey::default() {
+        governor.category_count = governor.category_count.checked_add(1).ok_or(ErrorCode::NumericalOverflow)?;
+    }
+
+    category.governor = governor.key();
+    category.id = id;
+    category.name = config.name;
+    category.vote_threshold = config.vote_threshold;
+    category.quorum = config.quorum;
+    category.min_voting_period = config.min_voting_period;
+    category.max_voting_period = config.max_voting_period;
+    category.timelock_delay = config.timelock_delay;
+    category.programs = config.programs;
+    category.emergency = config.emergency;
+    category.bump = ctx.bumps.category;
+
+    emit!(ProposalCategoryConfigured {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        category: category.key(),
+        id,
+        name: category.name.clone(),
+        vote_threshold: category.vote_threshold,
+        quorum: category.quorum,
+        min_voting_period: category.min_voting_period,
+        max_voting_period: category.max_voting_period,
+        timelock_delay: category.timelock_delay,
+        programs: category.programs.clone(),
+        emergency: category.emergency,
+        configured_at: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Checks `instructions` only call programs `category` allows.
+pub(crate) fn require_allowed_programs<'a>(
+    category: &ProposalCategory,
+    mut instructions: impl Iterator<Item = &'a ProposalInstruction>,
+) -> Result<()> {
+    require!(
+        instructions.all(|ix| category.programs.contains(&ix.program_id)),
+        ErrorCode::InstructionOutsideCategory
+    );
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::clock::Clock;
+use crate::{
+    contexts::{CloseProposal, CloseVote, CloseNftVoteRecord, CloseLockAccount, CloseEscrow},
+    errors::ErrorCode,
+    events::{ProposalClosed, VoteClosed, NftVoteRecordClosed, LockClosed, EscrowClosed, EVENT_VERSION},
+    state::Proposal,
+};
+
+/// How long terminal accounts are kept on-chain before their rent can be reclaimed.
+pub const RETENTION_PERIOD: i64 = 30 * 24 * 60 * 60;
+
+fn require_retention_elapsed(terminal_at: i64, now: i64) -> Result<()> {
+    let closable_at = terminal_at
+        .checked_add(RETENTION_PERIOD)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    require!(now >= closable_at, ErrorCode::RetentionPeriodActive);
+    Ok(())
+}
+
+/// Checks a vote-level account on `proposal_info` may be closed.
+fn require_proposal_closable(proposal_info: &AccountInfo, now: i64) -> Result<()> {
+    // A closed proposal has already passed its own terminal and retention checks.
+    if !(proposal_info.data_is_empty() && proposal_info.owner == &System::id()) {
+        require!(proposal_info.owner == &crate::ID, ErrorCode::AccountNotTerminal);
+        let data = proposal_info.try_borrow_data()?;
+        let proposal = Proposal::try_deserialize(&mut &data[..])?;
+        require!(proposal.state.is_terminal(), ErrorCode::AccountNotTerminal);
+        require_retention_elapsed(proposal.finalised_at, now)?;
+    }
+    Ok(())
+}
+
+pub fn close_proposal(ctx: Context<CloseProposal>) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+    let now = Clock::get()?.unix_timestamp;
+    require_retention_elapsed(proposal.finalised_at, now)?;
+
+    emit!(ProposalClosed {
+        version: EVENT_VERSION,
+        proposal: proposal.key(),
+        governor: proposal.governor,
+        proposal_id: proposal.proposal_id,
+        proposer: proposal.proposer,
+        state: proposal.state.clone(),
+        for_votes: proposal.for_votes,
+        against_votes: proposal.against_votes,
+        abstain_votes: proposal.abstain_votes,
+        option_votes: proposal.options.iter().map(|o| o.votes).collect(),
+        winning_option: proposal.winning_option,
+        finalised_at: proposal.finalised_at,
+        closed_at: now,
+    });
+    Ok(())
+}
+
+pub fn close_vote(ctx: Context<CloseVote>) -> Result<()> {
+    let vote = &ctx.accounts.vote;
+    let now = Clock::get()?.unix_timestamp;
+    require_proposal_closable(&ctx.accounts.proposal, now)?;
+
+    emit!(VoteClosed {
+        version: EVENT_VERSION,
+        governor: vote.governor,
+        vote: vote.key(),
+        proposal: vote.proposal,
+        voter: vote.voter,
+        side: vote.side.clone(),
+        weight: vote.weight,
+        closed_at: now,
+    });
+    Ok(())
+}
+
+pub fn close_nft_vote_record(ctx: Context<CloseNftVoteRecord>) -> Result<()> {
+    let record = &ctx.accounts.nft_vote_record;
+    let now = Clock::get()?.unix_timestamp;
+    require_proposal_closable(&ctx.accounts.proposal, now)?;
+
+    emit!(NftVoteRecordClosed {
+        version: EVENT_VERSION,
+        governor: record.governor,
+        record: record.key(),
+        proposal: record.proposal,
+        mint: record.mint,
+        closed_at: now,
+    });
+    Ok(())
+}
+
+pub fn close_lock_account(ctx: Context<CloseLockAccount>) -> Result<()> {
+    let lock_account = &ctx.accounts.lock_account;
+    let now = Clock::get()?.unix_timestamp;
+    require_retention_elapsed(lock_account.withdrawn_at, now)?;
+
+    emit!(LockClosed {
+        version: EVENT_VERSION,
+        governor: lock_account.governor,
+        lock_account: lock_account.key(),
+        user: lock_account.user,
+        amount: lock_account.amount,
+        start_time: lock_account.start_time,
+        end_time: lock_account.end_time,
+        withdrawn_at: lock_account.withdrawn_at,
+        closed_at: now,
+    });
+    Ok(())
+}
+
+pub fn close_escrow(ctx: Context<CloseEscrow>) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+    let now = Clock::get()?.unix_timestamp;
+    require_retention_elapsed(escrow.withdrawn_at, now)?;
+
+    emit!(EscrowClosed {
+        version: EVENT_VERSION,
+        governor: ctx.accounts.locker.governor,
+        escrow: escrow.key(),
+        locker: escrow.locker,
+        user: escrow.user,
+        amount: escrow.amount,
+        start_time: escrow.start_time,
+        end_time: escrow.end_time,
+        withdrawn_at: escrow.withdrawn_at,
+        closed_at: now,
+    });
+    Ok(())
+}
@@ -1,9 +1,13 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::TokenAccount;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token::{Mint, TokenAccount};
 use anchor_spl::token::Token;
 use {
-    crate::state::{Governor, Proposal, Vote, LockAccount, Locker, Escrow},
+    crate::state::{
+        Governor, Proposal, ProposalCategory, Vote, LockAccount, Locker, Escrow, NftCollection, NftVoteRecord,
+    },
     crate::enums::{ProposalState, VoteState},
+    crate::errors::ErrorCode,
 };
 
 
@@ -33,6 +37,79 @@ pub struct AddVoter<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct SetVotingStrategy<'info> {
+    #[account(mut, has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    pub smart_wallet: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetWeightSource<'info> {
+    #[account(mut, has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    pub smart_wallet: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetVoteMutability<'info> {
+    #[account(mut, has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    pub smart_wallet: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetLateVoteExtension<'info> {
+    #[account(mut, has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    pub smart_wallet: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetVotingSchedule<'info> {
+    #[account(mut, has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    pub smart_wallet: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(collection: Pubkey)]
+pub struct ConfigureNftCollection<'info> {
+    #[account(has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = NftCollection::LEN,
+        seeds = [b"nft_collection", governor.key().as_ref(), collection.as_ref()],
+        bump
+    )]
+    pub nft_collection: Account<'info, NftCollection>,
+    pub smart_wallet: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u8)]
+pub struct ConfigureProposalCategory<'info> {
+    #[account(mut, has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ProposalCategory::LEN,
+        seeds = [b"proposal_category", governor.key().as_ref(), &[id]],
+        bump
+    )]
+    pub category: Account<'info, ProposalCategory>,
+    pub smart_wallet: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct CreateProposal<'info> {
     #[account(mut)]
@@ -48,6 +125,9 @@ pub struct CreateProposal<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     pub proposer: Signer<'info>,
+    /// Required once the governor has categories.
+    #[account(has_one = governor)]
+    pub category: Option<Account<'info, ProposalCategory>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -131,7 +211,7 @@ pub struct CreateVote<'info> {
 pub struct CastVote<'info> {
     #[account(mut, has_one = smart_wallet)]
     pub governor: Account<'info, Governor>,
-    #[account(mut)]
+    #[account(mut, constraint = proposal.governor == governor.key())]
     pub proposal: Account<'info, Proposal>,
     #[account(
         mut,
@@ -150,6 +230,20 @@ pub struct CastVote<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct CastVotes<'info> {
+    #[account(has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    #[account(signer)]
+    /// CHECK: Verified in logic.
+    pub voter: AccountInfo<'info>,
+    #[account(signer)]
+    pub smart_wallet: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct SetVote<'info> {
     #[account(mut, has_one = smart_wallet)]
@@ -177,21 +271,95 @@ pub struct SetVote<'info> {
 }
 
 #[derive(Accounts)]
-pub struct FinaliseProposal<'info> {
+pub struct CastRelayedVotes<'info> {
     #[account(has_one = smart_wallet)]
     pub governor: Account<'info, Governor>,
+    #[account(
+        mut,
+        constraint = proposal.governor == governor.key(),
+        constraint = proposal.state == ProposalState::Active
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(signer)]
+    pub smart_wallet: Signer<'info>,
+    /// The relayer; pays rent for the vote accounts.
     #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: The instructions sysvar, checked by address.
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastNftVote<'info> {
+    #[account(has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    #[account(
+        mut,
+        constraint = proposal.governor == governor.key(),
+        constraint = proposal.state == ProposalState::Active
+    )]
     pub proposal: Account<'info, Proposal>,
+    #[account(
+        mut,
+        constraint = vote.proposal == proposal.key(),
+        constraint = vote.voter == voter.key()
+    )]
+    pub vote: Account<'info, Vote>,
+    #[account(
+        has_one = governor,
+        seeds = [b"nft_collection", governor.key().as_ref(), nft_collection.collection.as_ref()],
+        bump = nft_collection.bump
+    )]
+    pub nft_collection: Account<'info, NftCollection>,
+    #[account(
+        constraint = nft_token_account.owner == voter.key() @ ErrorCode::InvalidNft,
+        constraint = nft_token_account.amount == 1 @ ErrorCode::InvalidNft
+    )]
+    pub nft_token_account: Account<'info, TokenAccount>,
+    /// CHECK: Token Metadata account of the NFT's mint; verified in logic.
+    pub nft_metadata: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = NftVoteRecord::LEN,
+        seeds = [b"nft_vote", proposal.key().as_ref(), nft_token_account.mint.as_ref()],
+        bump
+    )]
+    pub nft_vote_record: Account<'info, NftVoteRecord>,
+    #[account(signer)]
+    /// CHECK: Verified in logic.
+    pub voter: AccountInfo<'info>,
     #[account(signer)]
     pub smart_wallet: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct ExecuteProposal<'info> {
+pub struct FinaliseProposal<'info> {
     #[account(has_one = smart_wallet)]
     pub governor: Account<'info, Governor>,
+    #[account(mut, constraint = proposal.governor == governor.key())]
+    pub proposal: Account<'info, Proposal>,
+    #[account(signer)]
+    pub smart_wallet: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TallyRankedVotes<'info> {
     #[account(mut)]
     pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    #[account(mut, constraint = proposal.governor == governor.key())]
+    pub proposal: Account<'info, Proposal>,
     #[account(signer)]
     pub smart_wallet: Signer<'info>,
 }
@@ -205,9 +373,16 @@ pub struct LockTokens<'info> {
     pub smart_wallet: Signer<'info>,
     #[account(signer)]
     pub user: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = user_token_account.mint == governor.governance_mint @ ErrorCode::InvalidGovernanceMint
+    )]
     pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = escrow_token_account.mint == governor.governance_mint @ ErrorCode::InvalidGovernanceMint,
+        constraint = escrow_token_account.owner == smart_wallet.key() @ ErrorCode::InvalidEscrowOwner
+    )]
     pub escrow_token_account: Account<'info, TokenAccount>,
     #[account(
         init,
@@ -225,17 +400,82 @@ pub struct LockTokens<'info> {
 
 #[derive(Accounts)]
 pub struct WithdrawTokens<'info> {
-    #[account(mut, has_one = user)]
+    #[account(mut, has_one = user, has_one = governor)]
     pub lock_account: Account<'info, LockAccount>,
     #[account(signer)]
     pub user: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = user_token_account.mint == governor.governance_mint @ ErrorCode::InvalidGovernanceMint
+    )]
     pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = escrow_token_account.key() == lock_account.escrow_token_account,
+        constraint = escrow_token_account.mint == governor.governance_mint @ ErrorCode::InvalidGovernanceMint
+    )]
     pub escrow_token_account: Account<'info, TokenAccount>,
     #[account(signer)]
     pub smart_wallet: Signer<'info>,
+    #[account(mut, has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, cliff_duration: i64, duration: i64, lock_id: u64)]
+pub struct LockVestingTokens<'info> {
+    #[account(mut, has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    #[account(signer)]
+    pub smart_wallet: Signer<'info>,
+    #[account(signer)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        constraint = user_token_account.mint == governor.governance_mint @ ErrorCode::InvalidGovernanceMint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = escrow_token_account.mint == governor.governance_mint @ ErrorCode::InvalidGovernanceMint,
+        constraint = escrow_token_account.owner == smart_wallet.key() @ ErrorCode::InvalidEscrowOwner
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = payer,
+        space = LockAccount::LEN,
+        seeds = [b"lock", governor.key().as_ref(), user.key().as_ref(), &lock_id.to_le_bytes()],
+        bump
+    )]
+    pub lock_account: Account<'info, LockAccount>,
     #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(mut, has_one = user, has_one = governor)]
+    pub lock_account: Account<'info, LockAccount>,
+    #[account(signer)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        constraint = user_token_account.mint == governor.governance_mint @ ErrorCode::InvalidGovernanceMint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = escrow_token_account.key() == lock_account.escrow_token_account,
+        constraint = escrow_token_account.mint == governor.governance_mint @ ErrorCode::InvalidGovernanceMint
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(signer)]
+    pub smart_wallet: Signer<'info>,
+    #[account(mut, has_one = smart_wallet)]
     pub governor: Account<'info, Governor>,
     pub token_program: Program<'info, anchor_spl::token::Token>,
 }
@@ -249,10 +489,11 @@ pub struct CreateLocker<'info> {
         init,
         payer = payer,
         space = Locker::LEN,
-        seeds = [b"locker", governor.key().as_ref()],
+        seeds = [b"locker", governor.key().as_ref(), mint.key().as_ref()],
         bump
     )]
     pub locker: Account<'info, Locker>,
+    pub mint: Account<'info, Mint>,
     #[account(signer)]
     pub smart_wallet: Signer<'info>,
     #[account(mut)]
@@ -265,6 +506,11 @@ pub struct SetLockerParams<'info> {
     #[account(mut, has_one = governor, has_one = authority)]
     pub locker: Account<'info, Locker>,
     pub governor: Account<'info, Governor>,
+    #[account(
+        has_one = governor,
+        constraint = proposal.state == ProposalState::Executed @ ErrorCode::LockerParamsNotApproved
+    )]
+    pub proposal: Account<'info, Proposal>,
     #[account(signer)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -280,9 +526,16 @@ pub struct CreateEscrow<'info> {
     pub smart_wallet: Signer<'info>,
     #[account(signer)]
     pub user: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = user_token_account.mint == locker.mint @ ErrorCode::InvalidLockerMint
+    )]
     pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = escrow_token_account.mint == locker.mint @ ErrorCode::InvalidLockerMint,
+        constraint = escrow_token_account.owner == smart_wallet.key() @ ErrorCode::InvalidEscrowOwner
+    )]
     pub escrow_token_account: Account<'info, TokenAccount>,
     #[account(
         init,
@@ -298,3 +551,94 @@ pub struct CreateEscrow<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct WithdrawEscrow<'info> {
+    #[account(mut, has_one = user, has_one = locker)]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut, has_one = governor)]
+    pub locker: Account<'info, Locker>,
+    #[account(signer)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        constraint = user_token_account.mint == locker.mint @ ErrorCode::InvalidLockerMint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = escrow_token_account.key() == escrow.escrow_token_account
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(signer)]
+    pub smart_wallet: Signer<'info>,
+    #[account(has_one = smart_wallet)]
+    pub governor: Account<'info, Governor>,
+    pub token_program: Program<'info, Token>,
+}
+
+
+#[derive(Accounts)]
+pub struct CloseProposal<'info> {
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        constraint = proposal.state.is_terminal() @ ErrorCode::AccountNotTerminal
+    )]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: Verified against proposal.payer; receives the reclaimed rent.
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVote<'info> {
+    #[account(mut, close = payer, has_one = payer, has_one = proposal)]
+    pub vote: Account<'info, Vote>,
+    /// CHECK: May already be closed; verified in logic.
+    pub proposal: AccountInfo<'info>,
+    /// CHECK: Verified against vote.payer; receives the reclaimed rent.
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseNftVoteRecord<'info> {
+    #[account(mut, close = payer, has_one = payer, has_one = proposal)]
+    pub nft_vote_record: Account<'info, NftVoteRecord>,
+    /// CHECK: May already be closed; verified in logic.
+    pub proposal: AccountInfo<'info>,
+    /// CHECK: Verified against nft_vote_record.payer; receives the reclaimed rent.
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseLockAccount<'info> {
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        constraint = lock_account.withdrawn @ ErrorCode::AccountNotTerminal
+    )]
+    pub lock_account: Account<'info, LockAccount>,
+    /// CHECK: Verified against lock_account.payer; receives the reclaimed rent.
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEscrow<'info> {
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        has_one = locker,
+        constraint = escrow.withdrawn @ ErrorCode::AccountNotTerminal
+    )]
+    pub escrow: Account<'info, Escrow>,
+    pub locker: Account<'info, Locker>,
+    /// CHECK: Verified against escrow.payer; receives the reclaimed rent.
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+}
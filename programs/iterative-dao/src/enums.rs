@@ -11,15 +11,121 @@ pub enum ProposalState {
     Canceled,
 }
 
+impl ProposalState {
+    /// States from which a proposal can never transition again.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, ProposalState::Executed | ProposalState::Rejected | ProposalState::Canceled)
+    }
+}
+
 #[derive(Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug)]
 pub enum VoteSide {
     For {},
     Against {},
     Abstain {},
+    /// Selects `options[index]` of a multiple-choice proposal.
+    Choice { index: u8 },
+}
+
+/// How finalisation picks the winner of a multiple-choice proposal.
+#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug)]
+pub enum OptionSelection {
+    /// The option with the most votes wins; a tie for first place rejects.
+    Plurality,
+    /// As `Plurality`, but the leader also needs the governor's
+    /// `vote_threshold` share of all votes cast, abstentions included.
+    Threshold,
+    /// Voters rank options and `tally_ranked_votes` runs instant-runoff
+    /// rounds until one option holds a majority of the live ballots.
+    InstantRunoff,
+}
+
+/// How a voter's power is turned into votes on a proposal.
+#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug)]
+pub enum VotingStrategy {
+    /// One vote per unit of voting power.
+    Linear,
+    /// The integer square root of voting power, which damps large holders.
+    Quadratic,
+}
+
+impl VotingStrategy {
+    /// Votes counted for `power` under this strategy.
+    pub fn votes(&self, power: u64) -> u64 {
+        match self {
+            VotingStrategy::Linear => power,
+            VotingStrategy::Quadratic => integer_sqrt(power),
+        }
+    }
+}
+
+/// Where votes read a voter's power from.
+#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug)]
+pub enum WeightSource {
+    /// The weight passed to `cast_vote` (the registered weight on
+    /// `set_vote`) plus escrowed locker power.
+    Declared,
+    /// Only the voter's weight in `Governor.voters`.
+    Registry,
+    /// Only the power of the voter's escrowed locker positions.
+    Locker,
+    /// A `VoterWeightRecord` owned and kept current by an add-in program.
+    AddIn { program: Pubkey },
+    /// NFTs of configured collections, cast one at a time with
+    /// `cast_nft_vote`; any holder may vote.
+    Nft,
+}
+
+/// Whether a cast vote may be changed with `set_vote` or taken back with
+/// `withdraw_vote`.
+#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug)]
+pub enum VoteMutability {
+    /// Changeable for as long as the proposal is active.
+    Free,
+    /// Final once cast.
+    Immutable,
+    /// Changeable until `cutoff` seconds before voting ends.
+    UntilCutoff { cutoff: i64 },
+}
+
+/// The action a `VoterWeightRecord` was prepared for, as in SPL Governance.
+#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug)]
+pub enum VoterWeightAction {
+    CastVote,
+    CommentProposal,
+    CreateGovernance,
+    CreateProposal,
+    SignOffProposal,
+}
+
+/// Largest `x` with `x * x <= n`.
+fn integer_sqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method descends monotonically from any overestimate.
+    let mut x = n / 2 + 1;
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
 }
 
 #[derive(Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
 pub enum VoteState {
     Pending,
     Cast,
+    /// Committed on a secret ballot and not yet revealed or counted.
+    Committed,
+}
+
+#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug)]
+pub enum LockKind {
+    /// Full amount released at `end_time`.
+    Standard,
+    /// Nothing released before `cliff_time`, then linear release until `end_time`.
+    Vesting,
 }
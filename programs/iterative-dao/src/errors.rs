@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
 
+/// `TryFrom<u32>` takes the code minus `ERROR_CODE_OFFSET`, which is how clients decode failures.
 #[error_code]
+#[derive(num_enum::TryFromPrimitive)]
 pub enum ErrorCode {
     #[msg("Governor not initialisd yet.")]
     GovernorNotInitialised,
@@ -18,7 +20,7 @@ pub enum ErrorCode {
     UnauthorisedCancellation,
     #[msg("Invalid state transition.")]
     InvalidStateTransition,
-    #[msg("Invalid voting period - must be positive.")]
+    #[msg("Invalid voting period - must be positive and within the proposal's bounds.")]
     InvalidVotingPeriod,
     #[msg("Invalid activation time.")]
     InvalidActivationTime,
@@ -38,4 +40,96 @@ pub enum ErrorCode {
     AlreadyWithdrawn,
     #[msg("Lock period has not expired.")]
     LockNotExpired,
+    #[msg("Token account mint does not match the governance mint.")]
+    InvalidGovernanceMint,
+    #[msg("Token account mint does not match the locker mint.")]
+    InvalidLockerMint,
+    #[msg("Invalid locker or escrow account supplied for voting power.")]
+    InvalidEscrowAccount,
+    #[msg("Locker parameter change was not approved by an executed proposal.")]
+    LockerParamsNotApproved,
+    #[msg("Locker parameters are outside the permitted bounds.")]
+    LockerParamsOutOfBounds,
+    #[msg("No vested tokens are available to claim.")]
+    NothingToClaim,
+    #[msg("Operation is only valid for vesting locks.")]
+    NotVestingLock,
+    #[msg("Account has not reached a terminal state.")]
+    AccountNotTerminal,
+    #[msg("Retention period has not elapsed.")]
+    RetentionPeriodActive,
+    #[msg("Multiple-choice proposals need 2 to 8 options with labels of 1 to 32 bytes.")]
+    InvalidProposalOptions,
+    #[msg("Vote side is not valid for this proposal.")]
+    InvalidVoteOption,
+    #[msg("Ranking must list distinct options of an instant-runoff proposal.")]
+    InvalidRanking,
+    #[msg("Account is not a ranked vote on this proposal.")]
+    InvalidVoteAccount,
+    #[msg("Voter weight record does not belong to this voter, governor or add-in.")]
+    InvalidVoterWeightRecord,
+    #[msg("Voter weight record has expired or was prepared for another action.")]
+    StaleVoterWeightRecord,
+    #[msg("Governor does not weigh votes by NFT.")]
+    NftVotingDisabled,
+    #[msg("Votes under NFT weighting are cast per NFT with cast_nft_vote.")]
+    NftVoteRequired,
+    #[msg("NFT is not held by the voter or not verified in a configured collection.")]
+    InvalidNft,
+    #[msg("Votes on this proposal are committed and revealed.")]
+    SecretBallot,
+    #[msg("Proposal does not use a secret ballot.")]
+    NotSecretBallot,
+    #[msg("Voting period has ended.")]
+    VotingPeriodEnded,
+    #[msg("Reveal period has ended.")]
+    RevealPeriodEnded,
+    #[msg("Revealed side and salt do not match the commitment.")]
+    CommitmentMismatch,
+    #[msg("Relayed votes need the declared or registry weight source.")]
+    RelayedVotesUnsupported,
+    #[msg("Ed25519 instruction is malformed or refers to data in another instruction.")]
+    InvalidSignatureInstruction,
+    #[msg("No verified signature from the voter covers this vote.")]
+    MissingVoteSignature,
+    #[msg("Voter has already voted on this proposal.")]
+    AlreadyVoted,
+    #[msg("Batched vote accounts are missing, out of order or for another governor.")]
+    InvalidBatchAccounts,
+    #[msg("Vote change cutoff must not be negative.")]
+    InvalidVoteMutability,
+    #[msg("Votes cannot be changed or withdrawn once cast.")]
+    VoteImmutable,
+    #[msg("Votes on this proposal can no longer be changed or withdrawn.")]
+    VoteChangeCutoffPassed,
+    #[msg("Vote rationale needs a reason of up to 256 bytes or a URI of up to 200 bytes.")]
+    InvalidVoteRationale,
+    #[msg("Early finalisation needs a binary open-ballot proposal under registry weighting.")]
+    EarlyFinalisationUnsupported,
+    #[msg("Remaining or changeable votes could still change the outcome.")]
+    OutcomeNotDecided,
+    #[msg("Late-vote extension needs a positive window and extension within the maximum.")]
+    InvalidLateVoteExtension,
+    #[msg("Category needs a name, voting periods within the governor's schedule and 1-8 programs; emergencies a 67% threshold and quorum.")]
+    InvalidProposalCategory,
+    #[msg("Governor has proposal categories; the proposal must name one.")]
+    ProposalCategoryRequired,
+    #[msg("Proposal calls a program its category does not allow.")]
+    InstructionOutsideCategory,
+    #[msg("Only open yes/no proposals in an emergency category can be fast-tracked.")]
+    NotEmergencyProposal,
+    #[msg("Votes for the proposal do not yet meet its quorum and threshold.")]
+    EmergencyThresholdNotMet,
+    #[msg("Voting schedule needs a positive minimum, a default within the bounds and a non-negative review period.")]
+    InvalidVotingSchedule,
+    #[msg("Proposal is still in its review period.")]
+    VotingNotStarted,
+    #[msg("Proposal has already been applied to this locker, or is older than one that was.")]
+    LockerParamsProposalUsed,
+    #[msg("Escrow token account must be owned by the governor's smart wallet.")]
+    InvalidEscrowOwner,
+    #[msg("Instant-runoff proposals cannot use a secret ballot.")]
+    SecretBallotUnsupported,
+    #[msg("Ranked ballots cannot be withdrawn.")]
+    RankedVoteNotWithdrawable,
 }
@@ -1,10 +1,12 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 use anchor_lang::solana_program::sysvar::clock::Clock;
 use anchor_spl::token::{self, Transfer};
 use crate::{
-    contexts::CreateEscrow,
+    contexts::{CreateEscrow, WithdrawEscrow},
     errors::ErrorCode,
-    events::NewEscrowEvent
+    events::{EscrowWithdrawn, NewEscrowEvent, EVENT_VERSION},
+    state::{Escrow, LockAccount, Locker},
 };
 
 pub fn create_escrow(ctx: Context<CreateEscrow>, amount: u64, duration: i64, escrow_id: u64) -> Result<()> {
@@ -44,15 +46,116 @@ pub fn create_escrow(ctx: Context<CreateEscrow>, amount: u64, duration: i64, esc
     escrow_acc.end_time = now.checked_add(duration).ok_or(ErrorCode::NumericalOverflow)?;
     escrow_acc.withdrawn = false;
     escrow_acc.escrow_token_account = ctx.accounts.escrow_token_account.key();
+    escrow_acc.voting_power_multiplier = locker.voting_power_multiplier;
+    escrow_acc.payer = ctx.accounts.payer.key();
+    escrow_acc.withdrawn_at = 0;
     escrow_acc.bump = ctx.bumps.escrow;
 
     emit!(NewEscrowEvent {
+        version: EVENT_VERSION,
+        governor: gov.key(),
         escrow: escrow_acc.key(),
         locker: locker.key(),
         user: user_key,
         amount,
         start_time: escrow_acc.start_time,
         end_time: escrow_acc.end_time,
+        escrow_id,
     });
     Ok(())
 }
+
+/// Returns an ended escrow's tokens to its user, after which it can be closed.
+pub fn withdraw_escrow(ctx: Context<WithdrawEscrow>, escrow_id: u64) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= escrow.end_time, ErrorCode::LockNotExpired);
+    require!(!escrow.withdrawn, ErrorCode::AlreadyWithdrawn);
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.user_token_account.to_account_info(),
+            authority: ctx.accounts.smart_wallet.to_account_info(),
+        },
+    );
+    token::transfer(cpi_ctx, escrow.amount).map_err(|_| ErrorCode::InsufficientBalance)?;
+
+    let locker = &mut ctx.accounts.locker;
+    locker.total_locked = locker
+        .total_locked
+        .checked_sub(escrow.amount)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    escrow.withdrawn = true;
+    escrow.withdrawn_at = now;
+
+    emit!(EscrowWithdrawn {
+        version: EVENT_VERSION,
+        governor: ctx.accounts.governor.key(),
+        escrow: escrow.key(),
+        locker: locker.key(),
+        user: escrow.user,
+        amount: escrow.amount,
+        escrow_id,
+        withdrawn_at: now,
+    });
+    Ok(())
+}
+
+/// Sums the voting power of `voter`'s live escrows and locks under the governor.
+///
+/// `accounts` holds `(locker, escrow)` pairs and single lock accounts, in any
+/// order. Each escrow is converted into voting power at the multiplier its
+/// locker had when the escrow was created; a lock counts one vote per token
+/// it still holds back, so a vesting grant loses power as it releases.
+pub fn escrowed_voting_power(
+    governor: Pubkey,
+    voter: Pubkey,
+    accounts: &[AccountInfo],
+    now: i64,
+) -> Result<u64> {
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(accounts.len());
+    let mut total: u64 = 0;
+    let mut accounts = accounts.iter();
+    while let Some(info) = accounts.next() {
+        let power = if is_lock_account(info) {
+            let lock = load_program_account::<LockAccount>(info)?;
+            require!(lock.governor == governor && lock.user == voter, ErrorCode::InvalidEscrowAccount);
+            require!(!seen.contains(info.key), ErrorCode::InvalidEscrowAccount);
+            seen.push(info.key());
+            lock.remaining_locked(now)
+        } else {
+            let escrow_info = accounts.next().ok_or(ErrorCode::InvalidEscrowAccount)?;
+            let locker = load_program_account::<Locker>(info)?;
+            let escrow = load_program_account::<Escrow>(escrow_info)?;
+
+            require!(locker.governor == governor, ErrorCode::InvalidEscrowAccount);
+            require!(escrow.locker == info.key(), ErrorCode::InvalidEscrowAccount);
+            require!(escrow.user == voter, ErrorCode::InvalidEscrowAccount);
+            require!(!seen.contains(escrow_info.key), ErrorCode::InvalidEscrowAccount);
+            seen.push(escrow_info.key());
+
+            if escrow.withdrawn || now >= escrow.end_time {
+                continue;
+            }
+            escrow
+                .amount
+                .checked_mul(escrow.voting_power_multiplier)
+                .ok_or(ErrorCode::NumericalOverflow)?
+        };
+        total = total.checked_add(power).ok_or(ErrorCode::NumericalOverflow)?;
+    }
+    Ok(total)
+}
+
+fn is_lock_account(info: &AccountInfo) -> bool {
+    info.owner == &crate::ID
+        && info.try_borrow_data().is_ok_and(|data| data.starts_with(&LockAccount::DISCRIMINATOR))
+}
+
+fn load_program_account<T: AccountDeserialize>(info: &AccountInfo) -> Result<T> {
+    require!(info.owner == &crate::ID, ErrorCode::InvalidEscrowAccount);
+    let data = info.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..])
+}
@@ -1,75 +1,334 @@
 use anchor_lang::prelude::*;
 
+/// Layout version carried as the first field of every event.
+///
+/// Bumped when a released program changes the fields of any event, so
+/// indexers can select a decoder before reading the rest of the payload.
+pub const EVENT_VERSION: u8 = 1;
+
 #[event]
 pub struct GovernorCreated {
+    pub version: u8,
     pub governor: Pubkey,
     pub smart_wallet: Pubkey,
     pub electorate: Pubkey,
     pub vote_threshold: u8,
     pub timelock_delay: i64,
+    pub governance_mint: Pubkey,
+    pub created_at: i64,
+}
+
+#[event]
+pub struct VoterAdded {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub voter_count: u32,
+    pub added_at: i64,
+}
+
+#[event]
+pub struct VotingStrategySet {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub voting_strategy: crate::enums::VotingStrategy,
+    pub set_at: i64,
+}
+
+#[event]
+pub struct WeightSourceSet {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub weight_source: crate::enums::WeightSource,
+    pub set_at: i64,
+}
+
+#[event]
+pub struct VoteMutabilitySet {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub vote_mutability: crate::enums::VoteMutability,
+    pub set_at: i64,
+}
+
+#[event]
+pub struct LateVoteExtensionSet {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub late_vote_extension: Option<crate::state::LateVoteExtension>,
+    pub set_at: i64,
+}
+
+#[event]
+pub struct VotingScheduleSet {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub voting_schedule: crate::state::VotingSchedule,
+    pub set_at: i64,
+}
+
+#[event]
+pub struct ProposalCategoryConfigured {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub category: Pubkey,
+    pub id: u8,
+    pub name: String,
+    pub vote_threshold: u8,
+    pub quorum: u64,
+    pub min_voting_period: i64,
+    pub max_voting_period: i64,
+    pub timelock_delay: i64,
+    pub programs: Vec<Pubkey>,
+    pub emergency: bool,
+    pub configured_at: i64,
+}
+
+#[event]
+pub struct NftCollectionConfigured {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub collection: Pubkey,
+    pub weight: u64,
+    pub configured_at: i64,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub instruction_count: u32,
+    /// Zero for a yes/no proposal.
+    pub option_count: u8,
+    pub category: Option<u8>,
+    pub created_at: i64,
 }
 
 #[event]
 pub struct ProposalActivated {
+    pub version: u8,
+    pub governor: Pubkey,
     pub proposal: Pubkey,
+    pub proposal_id: u64,
     pub activated_at: i64,
     pub voting_period: i64,
     pub timelock_delay: i64,
+    /// Seconds from activation until voting opens.
+    pub review_period: i64,
+    /// Zero unless the proposal is a secret ballot.
+    pub reveal_period: i64,
+}
+
+/// A late vote moved the outcome, so voting runs longer.
+#[event]
+pub struct VotingExtended {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    /// The voting period after the extension.
+    pub voting_period: i64,
+    pub voting_ends_at: i64,
+    pub extended_at: i64,
 }
 
 #[event]
 pub struct ProposalCanceled {
+    pub version: u8,
+    pub governor: Pubkey,
     pub proposal: Pubkey,
+    pub proposal_id: u64,
     pub canceled_by: Pubkey,
     pub canceled_at: i64,
 }
 
 #[event]
 pub struct ProposalQueued {
+    pub version: u8,
+    pub governor: Pubkey,
     pub proposal: Pubkey,
+    pub proposal_id: u64,
     pub queued_at: i64,
     pub ready_to_execute_at: i64,
 }
 
+#[event]
+pub struct ProposalFinalised {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    /// `Succeeded` or `Rejected`.
+    pub state: crate::enums::ProposalState,
+    pub for_votes: u64,
+    pub against_votes: u64,
+    pub abstain_votes: u64,
+    /// Winning option of a multiple-choice proposal.
+    pub winning_option: Option<u8>,
+    pub finalised_at: i64,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    pub executed_at: i64,
+}
+
 #[event]
 pub struct VoteCreateEvent {
+    pub version: u8,
+    pub governor: Pubkey,
     pub vote: Pubkey,
     pub proposal: Pubkey,
     /// CHECK: verify the voter is authorised
     pub voter: Pubkey,
     pub state: crate::enums::VoteState,
+    pub created_at: i64,
 }
 
 #[event]
 pub struct VoteSetEvent {
+    pub version: u8,
+    pub governor: Pubkey,
     pub vote: Pubkey,
     pub proposal: Pubkey,
     /// CHECK: verify the voter is authorised
     pub voter: Pubkey,
     pub side: crate::enums::VoteSide,
     pub weight: u64,
+    pub voted_at: i64,
+    /// Reasons given with this side, if any; a later change of vote does
+    /// not inherit them.
+    pub rationale: Option<crate::state::VoteRationale>,
+}
+
+/// A cast or committed vote taken back out; it is pending again.
+#[event]
+pub struct VoteWithdrawn {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub vote: Pubkey,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    /// Side and weight removed from the tally; zero weight for a commitment.
+    pub side: crate::enums::VoteSide,
+    pub weight: u64,
+    pub withdrawn_at: i64,
+}
+
+#[event]
+pub struct RankedVoteCast {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub vote: Pubkey,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub ranking: Vec<u8>,
+    pub weight: u64,
+    pub voted_at: i64,
+}
+
+/// A completed instant-runoff round that did not produce a winner.
+#[event]
+pub struct RankedRoundTallied {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    pub round: u8,
+    /// Tallies of the round, indexed like `Proposal::options`.
+    pub option_votes: Vec<u64>,
+    pub eliminated_option: u8,
+}
+
+/// A secret ballot committed; side and weight stay hidden until revealed.
+#[event]
+pub struct VoteCommitted {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub vote: Pubkey,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub commitment: [u8; 32],
+    pub committed_at: i64,
+}
+
+/// One NFT's weight added to a vote; followed by the vote's `VoteSetEvent`.
+#[event]
+pub struct NftVoteRecorded {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub proposal: Pubkey,
+    pub vote: Pubkey,
+    pub voter: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub weight: u64,
+    pub voted_at: i64,
 }
 
 #[event]
 pub struct LockEvent {
+    pub version: u8,
+    pub governor: Pubkey,
     pub lock_account: Pubkey,
     pub user: Pubkey,
     pub amount: u64,
     pub start_time: i64,
     pub end_time: i64,
+    pub lock_id: u64,
+}
+
+#[event]
+pub struct VestingLockEvent {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub lock_account: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+    pub lock_id: u64,
+}
+
+#[event]
+pub struct ClaimVestedEvent {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub lock_account: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub claimed_amount: u64,
+    pub remaining_locked: u64,
+    pub lock_id: u64,
+    pub claimed_at: i64,
 }
 
 #[event]
 pub struct WithdrawEvent {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub lock_account: Pubkey,
     pub user: Pubkey,
     pub amount: u64,
     pub lock_id: u64,
+    pub withdrawn_at: i64,
 }
 
 #[event]
 pub struct NewLockerEvent {
+    pub version: u8,
     pub locker: Pubkey,
     pub governor: Pubkey,
+    pub mint: Pubkey,
     pub voting_power_multiplier: u64,
     pub min_lock_duration: i64,
     pub max_lock_duration: i64,
@@ -77,7 +336,11 @@ pub struct NewLockerEvent {
 
 #[event]
 pub struct LockerSetParamsEvent {
+    pub version: u8,
     pub locker: Pubkey,
+    pub governor: Pubkey,
+    /// Executed proposal that approved the change.
+    pub proposal: Pubkey,
     pub new_voting_power_multiplier: u64,
     pub new_min_lock_duration: i64,
     pub new_max_lock_duration: i64,
@@ -85,10 +348,92 @@ pub struct LockerSetParamsEvent {
 
 #[event]
 pub struct NewEscrowEvent {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub escrow: Pubkey,
+    pub locker: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub escrow_id: u64,
+}
+
+#[event]
+pub struct EscrowWithdrawn {
+    pub version: u8,
+    pub governor: Pubkey,
     pub escrow: Pubkey,
     pub locker: Pubkey,
     pub user: Pubkey,
     pub amount: u64,
+    pub escrow_id: u64,
+    pub withdrawn_at: i64,
+}
+
+#[event]
+pub struct ProposalClosed {
+    pub version: u8,
+    pub proposal: Pubkey,
+    pub governor: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub state: crate::enums::ProposalState,
+    pub for_votes: u64,
+    pub against_votes: u64,
+    pub abstain_votes: u64,
+    /// Final tallies of a multiple-choice proposal, indexed like `Proposal::options`.
+    pub option_votes: Vec<u64>,
+    pub winning_option: Option<u8>,
+    pub finalised_at: i64,
+    pub closed_at: i64,
+}
+
+#[event]
+pub struct VoteClosed {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub vote: Pubkey,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub side: crate::enums::VoteSide,
+    pub weight: u64,
+    pub closed_at: i64,
+}
+
+#[event]
+pub struct LockClosed {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub lock_account: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
     pub start_time: i64,
     pub end_time: i64,
+    pub withdrawn_at: i64,
+    pub closed_at: i64,
+}
+
+#[event]
+pub struct EscrowClosed {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub escrow: Pubkey,
+    pub locker: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub withdrawn_at: i64,
+    pub closed_at: i64,
+}
+
+#[event]
+pub struct NftVoteRecordClosed {
+    pub version: u8,
+    pub governor: Pubkey,
+    pub record: Pubkey,
+    pub proposal: Pubkey,
+    pub mint: Pubkey,
+    pub closed_at: i64,
 }
@@ -1,9 +1,17 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::clock::Clock;
 use crate::{
-    contexts::{InitGovernor, AddVoter},
+    contexts::{
+        InitGovernor, AddVoter, SetVotingStrategy, SetWeightSource, SetVoteMutability, SetLateVoteExtension,
+        SetVotingSchedule,
+    },
+    enums::{VoteMutability, VotingStrategy, WeightSource},
     errors::ErrorCode,
-    events::GovernorCreated,
-    state::VoterInfo,
+    events::{
+        GovernorCreated, LateVoteExtensionSet, VoterAdded, VotingScheduleSet, VotingStrategySet, WeightSourceSet,
+        VoteMutabilitySet, EVENT_VERSION,
+    },
+    state::{LateVoteExtension, VoterInfo, VotingSchedule},
 };
 
 pub fn init_governor(
@@ -25,14 +33,23 @@ pub fn init_governor(
     governor.proposal_count = 0;
     governor.voters = Vec::new();
     governor.governance_mint = governance_mint;
-    governor.padding = [0u8; 2];
+    governor.voting_strategy = VotingStrategy::Linear;
+    governor.weight_source = WeightSource::Declared;
+    governor.vote_mutability = VoteMutability::Free;
+    governor.late_vote_extension = None;
+    governor.category_count = 0;
+    governor.voting_schedule = VotingSchedule::default();
+    governor.padding = [0u8; 1];
 
     emit!(GovernorCreated {
+        version: EVENT_VERSION,
         governor: governor.key(),
         smart_wallet: governor.smart_wallet,
         electorate: governor.electorate,
         vote_threshold,
         timelock_delay,
+        governance_mint,
+        created_at: Clock::get()?.unix_timestamp,
     });
     Ok(())
 }
@@ -44,5 +61,104 @@ pub fn add_voter(ctx: Context<AddVoter>, new_voter: Pubkey, weight: u64) -> Resu
         ErrorCode::DuplicateVoter
     );
     governor.voters.push(VoterInfo { pubkey: new_voter, weight });
+
+    emit!(VoterAdded {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        voter: new_voter,
+        weight,
+        voter_count: governor.voters.len() as u32,
+        added_at: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Switches how votes are counted on proposals created afterwards.
+///
+/// Proposals already created keep the strategy they were created with.
+pub fn set_voting_strategy(ctx: Context<SetVotingStrategy>, voting_strategy: VotingStrategy) -> Result<()> {
+    let governor = &mut ctx.accounts.governor;
+    governor.voting_strategy = voting_strategy;
+
+    emit!(VotingStrategySet {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        voting_strategy,
+        set_at: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Points voting at a different source of voting power.
+///
+/// Takes effect for every vote cast or changed afterwards, including votes
+/// on proposals that are already active.
+pub fn set_weight_source(ctx: Context<SetWeightSource>, weight_source: WeightSource) -> Result<()> {
+    let governor = &mut ctx.accounts.governor;
+    governor.weight_source = weight_source;
+
+    emit!(WeightSourceSet {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        weight_source,
+        set_at: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Sets when cast votes may be changed or withdrawn, on every proposal
+/// including those already being voted on.
+pub fn set_vote_mutability(ctx: Context<SetVoteMutability>, vote_mutability: VoteMutability) -> Result<()> {
+    if let VoteMutability::UntilCutoff { cutoff } = vote_mutability {
+        require!(cutoff >= 0, ErrorCode::InvalidVoteMutability);
+    }
+    let governor = &mut ctx.accounts.governor;
+    governor.vote_mutability = vote_mutability;
+
+    emit!(VoteMutabilitySet {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        vote_mutability,
+        set_at: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Sets or clears the late-vote extension rule. Proposals keep any extension
+/// they already received.
+pub fn set_late_vote_extension(
+    ctx: Context<SetLateVoteExtension>,
+    late_vote_extension: Option<LateVoteExtension>,
+) -> Result<()> {
+    require!(
+        late_vote_extension.iter().all(LateVoteExtension::is_valid),
+        ErrorCode::InvalidLateVoteExtension
+    );
+    let governor = &mut ctx.accounts.governor;
+    governor.late_vote_extension = late_vote_extension;
+
+    emit!(LateVoteExtensionSet {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        late_vote_extension,
+        set_at: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Sets the voting schedule. The bounds apply to uncategorised proposals
+/// created from now on; the default and review period to any proposal
+/// activated from now on.
+pub fn set_voting_schedule(ctx: Context<SetVotingSchedule>, voting_schedule: VotingSchedule) -> Result<()> {
+    require!(voting_schedule.is_valid(), ErrorCode::InvalidVotingSchedule);
+    let governor = &mut ctx.accounts.governor;
+    governor.voting_schedule = voting_schedule;
+
+    emit!(VotingScheduleSet {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        voting_schedule,
+        set_at: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
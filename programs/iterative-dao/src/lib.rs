@@ -1,12 +1,19 @@
 use anchor_lang::prelude::*;
-use crate::AddVoter;
 
 pub mod governor;
 pub mod proposals;
+pub mod categories;
 pub mod votes;
+pub mod ranked;
+pub mod secret;
+pub mod relay;
+pub mod voter_weight;
+pub mod nft;
+pub mod metadata;
 pub mod tokens;
 pub mod locker;
 pub mod escrow;
+pub mod close;
 pub mod events;
 pub mod errors;
 pub mod contexts;
@@ -14,7 +21,7 @@ pub mod state;
 pub mod enums;
 
 pub use contexts::*;
-pub use errors::*;
+pub use errors::ErrorCode;
 pub use state::*;
     
 
@@ -39,7 +46,52 @@ pub mod iterative_dao {
         governor::add_voter(ctx, new_voter, weight)
     }
 
+    pub fn set_voting_strategy(
+        ctx: Context<SetVotingStrategy>,
+        voting_strategy: enums::VotingStrategy,
+    ) -> Result<()> {
+        governor::set_voting_strategy(ctx, voting_strategy)
+    }
+
+    pub fn set_weight_source(ctx: Context<SetWeightSource>, weight_source: enums::WeightSource) -> Result<()> {
+        governor::set_weight_source(ctx, weight_source)
+    }
+
+    pub fn set_vote_mutability(
+        ctx: Context<SetVoteMutability>,
+        vote_mutability: enums::VoteMutability,
+    ) -> Result<()> {
+        governor::set_vote_mutability(ctx, vote_mutability)
+    }
+
+    pub fn set_late_vote_extension(
+        ctx: Context<SetLateVoteExtension>,
+        late_vote_extension: Option<state::LateVoteExtension>,
+    ) -> Result<()> {
+        governor::set_late_vote_extension(ctx, late_vote_extension)
+    }
+
+    pub fn set_voting_schedule(ctx: Context<SetVotingSchedule>, voting_schedule: state::VotingSchedule) -> Result<()> {
+        governor::set_voting_schedule(ctx, voting_schedule)
+    }
+
+    pub fn configure_nft_collection(
+        ctx: Context<ConfigureNftCollection>,
+        collection: Pubkey,
+        weight: u64,
+    ) -> Result<()> {
+        nft::configure_nft_collection(ctx, collection, weight)
+    }
+
     //  Proposal Management 
+    pub fn configure_proposal_category(
+        ctx: Context<ConfigureProposalCategory>,
+        id: u8,
+        config: state::ProposalCategoryConfig,
+    ) -> Result<()> {
+        categories::configure_proposal_category(ctx, id, config)
+    }
+
     pub fn create_proposal(
         ctx: Context<CreateProposal>,
         instructions: Vec<state::ProposalInstruction>,
@@ -47,10 +99,26 @@ pub mod iterative_dao {
         proposals::create_proposal(ctx, instructions)
     }
 
-    pub fn activate_proposal(ctx: Context<ActivateProposal>, voting_period: i64) -> Result<()> {
+    pub fn create_multiple_choice_proposal(
+        ctx: Context<CreateProposal>,
+        options: Vec<state::NewProposalOption>,
+        selection: enums::OptionSelection,
+    ) -> Result<()> {
+        proposals::create_multiple_choice_proposal(ctx, options, selection)
+    }
+
+    pub fn activate_proposal(ctx: Context<ActivateProposal>, voting_period: Option<i64>) -> Result<()> {
         proposals::activate_proposal(ctx, voting_period)
     }
 
+    pub fn activate_secret_proposal(
+        ctx: Context<ActivateProposal>,
+        voting_period: Option<i64>,
+        reveal_period: i64,
+    ) -> Result<()> {
+        proposals::activate_secret_proposal(ctx, voting_period, reveal_period)
+    }
+
     pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
         proposals::cancel_proposal(ctx)
     }
@@ -63,6 +131,14 @@ pub mod iterative_dao {
         proposals::finalise_proposal(ctx)
     }
 
+    pub fn finalise_proposal_early(ctx: Context<FinaliseProposal>) -> Result<()> {
+        proposals::finalise_proposal_early(ctx)
+    }
+
+    pub fn fast_track_proposal(ctx: Context<FinaliseProposal>) -> Result<()> {
+        proposals::fast_track_proposal(ctx)
+    }
+
     pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
         proposals::execute_proposal(ctx)
     }
@@ -76,12 +152,64 @@ pub mod iterative_dao {
         ctx: Context<CastVote>,
         side: enums::VoteSide,
         weight: u64,
+        rationale: Option<state::VoteRationale>,
     ) -> Result<()> {
-        votes::cast_vote(ctx, side, weight)
+        votes::cast_vote(ctx, side, weight, rationale)
     }
 
-    pub fn set_vote(ctx: Context<SetVote>, new_side: enums::VoteSide) -> Result<()> {
-        votes::set_vote(ctx, new_side)
+    pub fn create_and_cast_vote(
+        ctx: Context<CreateVote>,
+        side: enums::VoteSide,
+        weight: u64,
+        rationale: Option<state::VoteRationale>,
+    ) -> Result<()> {
+        votes::create_and_cast_vote(ctx, side, weight, rationale)
+    }
+
+    pub fn cast_votes<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CastVotes<'info>>,
+        votes: Vec<BatchedVote>,
+    ) -> Result<()> {
+        votes::cast_votes(ctx, votes)
+    }
+
+    pub fn set_vote(
+        ctx: Context<SetVote>,
+        new_side: enums::VoteSide,
+        rationale: Option<state::VoteRationale>,
+    ) -> Result<()> {
+        votes::set_vote(ctx, new_side, rationale)
+    }
+
+    pub fn withdraw_vote(ctx: Context<SetVote>) -> Result<()> {
+        votes::withdraw_vote(ctx)
+    }
+
+    pub fn commit_vote(ctx: Context<SetVote>, commitment: [u8; 32], weight: u64) -> Result<()> {
+        secret::commit_vote(ctx, commitment, weight)
+    }
+
+    pub fn reveal_vote(ctx: Context<SetVote>, side: enums::VoteSide, salt: [u8; 32]) -> Result<()> {
+        secret::reveal_vote(ctx, side, salt)
+    }
+
+    pub fn cast_ranked_vote(ctx: Context<CastVote>, ranking: Vec<u8>, weight: u64) -> Result<()> {
+        ranked::cast_ranked_vote(ctx, ranking, weight)
+    }
+
+    pub fn tally_ranked_votes(ctx: Context<TallyRankedVotes>) -> Result<()> {
+        ranked::tally_ranked_votes(ctx)
+    }
+
+    pub fn cast_nft_vote(ctx: Context<CastNftVote>, side: enums::VoteSide) -> Result<()> {
+        nft::cast_nft_vote(ctx, side)
+    }
+
+    pub fn cast_relayed_votes<'info>(
+        ctx: Context<'_, '_, '_, 'info, CastRelayedVotes<'info>>,
+        votes: Vec<SignedVote>,
+    ) -> Result<()> {
+        relay::cast_relayed_votes(ctx, votes)
     }
 
     //  Token Locking 
@@ -98,6 +226,20 @@ pub mod iterative_dao {
         tokens::withdraw_tokens(ctx, lock_id)
     }
 
+    pub fn lock_vesting_tokens(
+        ctx: Context<LockVestingTokens>,
+        amount: u64,
+        cliff_duration: i64,
+        duration: i64,
+        lock_id: u64,
+    ) -> Result<()> {
+        tokens::lock_vesting_tokens(ctx, amount, cliff_duration, duration, lock_id)
+    }
+
+    pub fn claim_vested(ctx: Context<ClaimVested>, lock_id: u64) -> Result<()> {
+        tokens::claim_vested(ctx, lock_id)
+    }
+
     //  Locker 
     pub fn create_locker(
         ctx: Context<CreateLocker>,
@@ -126,4 +268,29 @@ pub mod iterative_dao {
     ) -> Result<()> {
         escrow::create_escrow(ctx, amount, duration, escrow_id)
     }
+
+    pub fn withdraw_escrow(ctx: Context<WithdrawEscrow>, escrow_id: u64) -> Result<()> {
+        escrow::withdraw_escrow(ctx, escrow_id)
+    }
+
+    //  Account Closing 
+    pub fn close_proposal(ctx: Context<CloseProposal>) -> Result<()> {
+        close::close_proposal(ctx)
+    }
+
+    pub fn close_vote(ctx: Context<CloseVote>) -> Result<()> {
+        close::close_vote(ctx)
+    }
+
+    pub fn close_nft_vote_record(ctx: Context<CloseNftVoteRecord>) -> Result<()> {
+        close::close_nft_vote_record(ctx)
+    }
+
+    pub fn close_lock_account(ctx: Context<CloseLockAccount>) -> Result<()> {
+        close::close_lock_account(ctx)
+    }
+
+    pub fn close_escrow(ctx: Context<CloseEscrow>) -> Result<()> {
+        close::close_escrow(ctx)
+    }
 }
@@ -1,10 +1,31 @@
 use anchor_lang::prelude::*;
+use anchor_lang::InstructionData;
 use crate::{
     contexts::{CreateLocker, SetLockerParams},
     errors::ErrorCode,
-    events::{NewLockerEvent, LockerSetParamsEvent},
+    events::{NewLockerEvent, LockerSetParamsEvent, EVENT_VERSION},
 };
 
+pub const MIN_VOTING_POWER_MULTIPLIER: u64 = 1;
+pub const MAX_VOTING_POWER_MULTIPLIER: u64 = 100;
+/// Four years, in seconds.
+pub const MAX_LOCK_DURATION: i64 = 4 * 365 * 24 * 60 * 60;
+
+fn validate_locker_params(
+    voting_power_multiplier: u64,
+    min_lock_duration: i64,
+    max_lock_duration: i64,
+) -> Result<()> {
+    require!(min_lock_duration >= 0, ErrorCode::InvalidLockParameters);
+    require!(max_lock_duration >= min_lock_duration, ErrorCode::InvalidLockParameters);
+    require!(
+        (MIN_VOTING_POWER_MULTIPLIER..=MAX_VOTING_POWER_MULTIPLIER).contains(&voting_power_multiplier),
+        ErrorCode::LockerParamsOutOfBounds
+    );
+    require!(max_lock_duration <= MAX_LOCK_DURATION, ErrorCode::LockerParamsOutOfBounds);
+    Ok(())
+}
+
 pub fn create_locker(
     ctx: Context<CreateLocker>,
     voting_power_multiplier: u64,
@@ -15,20 +36,23 @@ pub fn create_locker(
     let gov = &ctx.accounts.governor;
 
     require!(gov.is_initialised, ErrorCode::GovernorNotInitialised);
-    require!(min_lock_duration >= 0, ErrorCode::InvalidLockParameters);
-    require!(max_lock_duration >= min_lock_duration, ErrorCode::InvalidLockParameters);
+    validate_locker_params(voting_power_multiplier, min_lock_duration, max_lock_duration)?;
 
     locker.governor = gov.key();
     locker.authority = ctx.accounts.smart_wallet.key();
+    locker.mint = ctx.accounts.mint.key();
     locker.voting_power_multiplier = voting_power_multiplier;
     locker.min_lock_duration = min_lock_duration;
     locker.max_lock_duration = max_lock_duration;
     locker.total_locked = 0;
     locker.bump = ctx.bumps.locker; 
+    locker.next_params_proposal_id = 0;
 
     emit!(NewLockerEvent {
+        version: EVENT_VERSION,
         locker: locker.key(),
         governor: locker.governor,
+        mint: locker.mint,
         voting_power_multiplier,
         min_lock_duration,
         max_lock_duration,
@@ -46,15 +70,37 @@ pub fn set_locker_params(
     let gov = &ctx.accounts.governor;
 
     require!(gov.is_initialised, ErrorCode::GovernorNotInitialised);
-    require!(new_min_lock_duration >= 0, ErrorCode::InvalidLockParameters);
-    require!(new_max_lock_duration >= new_min_lock_duration, ErrorCode::InvalidLockParameters);
+    validate_locker_params(new_voting_power_multiplier, new_min_lock_duration, new_max_lock_duration)?;
+    let proposal_id = ctx.accounts.proposal.proposal_id;
+    require!(proposal_id >= locker.next_params_proposal_id, ErrorCode::LockerParamsProposalUsed);
+
+    // The executed proposal must carry this exact call against this locker.
+    let expected_data = crate::instruction::SetLockerParams {
+        new_voting_power_multiplier,
+        new_min_lock_duration,
+        new_max_lock_duration,
+    }
+    .data();
+    require!(
+        ctx.accounts.proposal.executable_instructions().iter().any(|ix| {
+            ix.program_id == crate::ID
+                && ix.data == expected_data
+                && ix.accounts.iter().any(|a| a.pubkey == locker.key())
+        }),
+        ErrorCode::LockerParamsNotApproved
+    );
 
+    // Existing escrows keep the multiplier they were created with.
     locker.voting_power_multiplier = new_voting_power_multiplier;
     locker.min_lock_duration = new_min_lock_duration;
     locker.max_lock_duration = new_max_lock_duration;
+    locker.next_params_proposal_id = proposal_id + 1;
 
     emit!(LockerSetParamsEvent {
+        version: EVENT_VERSION,
         locker: locker.key(),
+        governor: gov.key(),
+        proposal: ctx.accounts.proposal.key(),
         new_voting_power_multiplier,
         new_min_lock_duration,
         new_max_lock_duration,
@@ -0,0 +1,66 @@
+//! The part of a Metaplex Token Metadata account needed to check collection
+//! membership, read without depending on the Metaplex crates.
+
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+/// The Metaplex Token Metadata program.
+pub const TOKEN_METADATA_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bgbGdFRs");
+
+/// `Key::MetadataV1`, the first byte of every metadata account.
+pub const METADATA_V1: u8 = 4;
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct Collection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+/// Leading fields of a metadata account, up to and including `collection`.
+///
+/// Later fields are ignored, so accounts written by newer Token Metadata
+/// versions still parse.
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct Metadata {
+    pub key: u8,
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub edition_nonce: Option<u8>,
+    pub token_standard: Option<u8>,
+    pub collection: Option<Collection>,
+}
+
+pub fn find_metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"metadata", TOKEN_METADATA_ID.as_ref(), mint.as_ref()], &TOKEN_METADATA_ID).0
+}
+
+/// The collection `mint` is verified into, read from its metadata account.
+pub fn verified_collection(info: &AccountInfo, mint: &Pubkey) -> Result<Pubkey> {
+    require!(
+        info.owner == &TOKEN_METADATA_ID && info.key() == find_metadata_address(mint),
+        ErrorCode::InvalidNft
+    );
+    let metadata = {
+        let data = info.try_borrow_data()?;
+        Metadata::deserialize(&mut &data[..]).map_err(|_| error!(ErrorCode::InvalidNft))?
+    };
+    require!(metadata.key == METADATA_V1 && metadata.mint == *mint, ErrorCode::InvalidNft);
+    match metadata.collection {
+        Some(Collection { verified: true, key }) => Ok(key),
+        _ => err!(ErrorCode::InvalidNft),
+    }
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::clock::Clock;
+use crate::{
+    contexts::{ConfigureNftCollection, CastNftVote},
+    enums::{ProposalState, VoteSide, VoteState, WeightSource},
+    errors::ErrorCode,
+    events::{NftCollectionConfigured, NftVoteRecorded, VoteSetEvent, EVENT_VERSION},
+    metadata::verified_collection,
+    proposals::provisional_outcome,
+    votes::{extend_for_late_vote, require_voting_open, tally_for},
+};
+
+/// Sets the per-NFT weight of a collection, adding it if it is new.
+pub fn configure_nft_collection(ctx: Context<ConfigureNftCollection>, collection: Pubkey, weight: u64) -> Result<()> {
+    let governor = &ctx.accounts.governor;
+    let config = &mut ctx.accounts.nft_collection;
+    config.governor = governor.key();
+    config.collection = collection;
+    config.weight = weight;
+    config.bump = ctx.bumps.nft_collection;
+
+    emit!(NftCollectionConfigured {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        collection,
+        weight,
+        configured_at: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Adds one NFT's weight to the voter's vote.
+///
+/// Each NFT can back one vote per proposal: its `NftVoteRecord` stays with
+/// the proposal, so a later holder cannot vote with it again. Further NFTs
+/// may be added to a cast vote, but only on the same side.
+pub fn cast_nft_vote(ctx: Context<CastNftVote>, side: VoteSide) -> Result<()> {
+    let governor = &ctx.accounts.governor;
+    let proposal = &mut ctx.accounts.proposal;
+    let vote = &mut ctx.accounts.vote;
+    let config = &ctx.accounts.nft_collection;
+    let voter_key = ctx.accounts.voter.key();
+    let mint = ctx.accounts.nft_token_account.mint;
+
+    require!(governor.weight_source == WeightSource::Nft, ErrorCode::NftVotingDisabled);
+    require!(proposal.state == ProposalState::Active, ErrorCode::InvalidStateTransition);
+    require!(!proposal.is_secret_ballot(), ErrorCode::SecretBallot);
+    require!(vote.state == VoteState::Pending || vote.side == side, ErrorCode::InvalidStateTransition);
+    require!(config.weight > 0, ErrorCode::InvalidNft);
+    let now = Clock::get()?.unix_timestamp;
+    require_voting_open(proposal, now)?;
+    require!(
+        verified_collection(&ctx.accounts.nft_metadata, &mint)? == config.collection,
+        ErrorCode::InvalidNft
+    );
+
+    let power = vote.power.checked_add(config.weight).ok_or(ErrorCode::NumericalOverflow)?;
+    let weight = proposal.voting_strategy.votes(power);
+    let before = provisional_outcome(proposal)?;
+    let tally = tally_for(proposal, &side)?;
+    *tally = tally
+        .checked_sub(vote.weight)
+        .and_then(|t| t.checked_add(weight))
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    vote.side = side.clone();
+    vote.power = power;
+    vote.weight = weight;
+    vote.state = VoteState::Cast;
+
+    let record = &mut ctx.accounts.nft_vote_record;
+    record.proposal = proposal.key();
+    record.mint = mint;
+    record.vote = vote.key();
+    record.weight = config.weight;
+    record.payer = ctx.accounts.payer.key();
+    record.bump = ctx.bumps.nft_vote_record;
+    record.governor = governor.key();
+
+    emit!(NftVoteRecorded {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        proposal: proposal.key(),
+        vote: vote.key(),
+        voter: voter_key,
+        mint,
+        collection: config.collection,
+        weight: config.weight,
+        voted_at: now,
+    });
+    emit!(VoteSetEvent {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        vote: vote.key(),
+        proposal: proposal.key(),
+        voter: voter_key,
+        side,
+        weight,
+        voted_at: now,
+        rationale: None,
+    });
+    extend_for_late_vote(governor, proposal, before, now)
+}
@@ -1,27 +1,70 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::sysvar::clock::Clock;
 use crate::{
+    categories::require_allowed_programs,
     contexts::{CreateProposal, ActivateProposal, CancelProposal, QueueProposal, FinaliseProposal, ExecuteProposal},
     errors::ErrorCode,
-    events::{ProposalActivated, ProposalCanceled, ProposalQueued},
-    state::{Governor, Proposal},
-    enums::ProposalState,
+    events::{
+        ProposalActivated, ProposalCanceled, ProposalCreated, ProposalExecuted, ProposalFinalised,
+        ProposalQueued, EVENT_VERSION,
+    },
+    enums::{OptionSelection, ProposalState, WeightSource},
+    state::{Governor, NewProposalOption, Proposal, ProposalInstruction, ProposalOption},
+    votes::votes_are_final,
 };
 
 pub fn create_proposal(
     ctx: Context<CreateProposal>,
-    instructions: Vec<crate::state::ProposalInstruction>,
+    instructions: Vec<ProposalInstruction>,
+) -> Result<()> {
+    require!(!instructions.is_empty(), ErrorCode::InvalidInstructions);
+    init_proposal(ctx, instructions, Vec::new(), OptionSelection::Plurality)
+}
+
+pub fn create_multiple_choice_proposal(
+    ctx: Context<CreateProposal>,
+    options: Vec<NewProposalOption>,
+    selection: OptionSelection,
+) -> Result<()> {
+    require!(
+        (2..=Proposal::MAX_OPTIONS).contains(&options.len())
+            && options
+                .iter()
+                .all(|o| !o.label.is_empty() && o.label.len() <= Proposal::MAX_OPTION_LABEL_LEN),
+        ErrorCode::InvalidProposalOptions
+    );
+    let instruction_count: usize = options.iter().map(|o| o.instructions.len()).sum();
+    require!(instruction_count <= Proposal::MAX_INSTRUCTIONS, ErrorCode::InvalidInstructions);
+
+    let options = options
+        .into_iter()
+        .map(|o| ProposalOption { label: o.label, instructions: o.instructions, votes: 0 })
+        .collect();
+    init_proposal(ctx, Vec::new(), options, selection)
+}
+
+fn init_proposal(
+    ctx: Context<CreateProposal>,
+    instructions: Vec<ProposalInstruction>,
+    options: Vec<ProposalOption>,
+    selection: OptionSelection,
 ) -> Result<()> {
     let governor = &mut ctx.accounts.governor;
     let proposal = &mut ctx.accounts.proposal;
 
-    require!(!instructions.is_empty(), ErrorCode::InvalidInstructions);
-
     let proposer_key = ctx.accounts.proposer.key();
     require!(
         governor.voters.iter().any(|v| v.pubkey == proposer_key),
         ErrorCode::UnauthorisedVoter
     );
+    let category = ctx.accounts.category.as_deref();
+    match category {
+        Some(category) => require_allowed_programs(
+            category,
+            instructions.iter().chain(options.iter().flat_map(|o| o.instructions.iter())),
+        )?,
+        None => require!(governor.category_count == 0, ErrorCode::ProposalCategoryRequired),
+    }
 
     proposal.governor = governor.key();
     proposal.proposer = proposer_key;
@@ -33,31 +76,105 @@ pub fn create_proposal(
     proposal.abstain_votes = 0;
     proposal.activated_at = 0;
     proposal.voting_period = 0;
-    proposal.timelock_delay = governor.timelock_delay;
+    proposal.timelock_delay = category.map_or(governor.timelock_delay, |c| c.timelock_delay);
     proposal.queued_at = 0;
     proposal.ready_to_execute_at = 0;
-    proposal.padding = [0u8; 3];
+    proposal.payer = ctx.accounts.payer.key();
+    proposal.finalised_at = 0;
+    proposal.options = options;
+    proposal.selection = selection;
+    proposal.winning_option = None;
+    proposal.ranked_ballots = 0;
+    proposal.tally_round = 0;
+    proposal.tallied_ballots = 0;
+    proposal.eliminated_options = 0;
+    proposal.voting_strategy = governor.voting_strategy;
+    proposal.reveal_period = 0;
+    proposal.voting_extension = 0;
+    proposal.category = category.map(|c| c.id);
+    proposal.vote_threshold = category.map_or(governor.vote_threshold, |c| c.vote_threshold);
+    proposal.quorum = category.map_or(0, |c| c.quorum);
+    // The schedule may have narrowed since the category was configured.
+    let schedule = &governor.voting_schedule;
+    proposal.min_voting_period =
+        category.map_or(schedule.min_voting_period, |c| c.min_voting_period.max(schedule.min_voting_period));
+    proposal.max_voting_period =
+        category.map_or(schedule.max_voting_period, |c| c.max_voting_period.min(schedule.max_voting_period));
+    require!(proposal.min_voting_period <= proposal.max_voting_period, ErrorCode::InvalidProposalCategory);
+    proposal.emergency = category.is_some_and(|c| c.emergency);
+    proposal.review_period = 0;
+    proposal.padding = [0u8; 2];
 
     governor.proposal_count += 1;
+
+    emit!(ProposalCreated {
+        version: EVENT_VERSION,
+        governor: proposal.governor,
+        proposal: proposal.key(),
+        proposal_id: proposal.proposal_id,
+        proposer: proposal.proposer,
+        instruction_count: (proposal.instructions.len()
+            + proposal.options.iter().map(|o| o.instructions.len()).sum::<usize>()) as u32,
+        option_count: proposal.options.len() as u8,
+        category: proposal.category,
+        created_at: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
 
-pub fn activate_proposal(ctx: Context<ActivateProposal>, voting_period: i64) -> Result<()> {
+/// Starts the review period, after which voting runs for `voting_period`,
+/// or the governor's default brought within the proposal's bounds.
+pub fn activate_proposal(ctx: Context<ActivateProposal>, voting_period: Option<i64>) -> Result<()> {
+    activate(ctx, voting_period, 0)
+}
+
+/// Opens voting by commitment, followed by `reveal_period` for revealing.
+///
+/// Instant-runoff proposals are refused: their ranked ballots cannot be
+/// committed to and revealed as a single side.
+pub fn activate_secret_proposal(
+    ctx: Context<ActivateProposal>,
+    voting_period: Option<i64>,
+    reveal_period: i64,
+) -> Result<()> {
+    require!(reveal_period > 0, ErrorCode::InvalidVotingPeriod);
+    require!(
+        ctx.accounts.proposal.selection != OptionSelection::InstantRunoff,
+        ErrorCode::SecretBallotUnsupported
+    );
+    activate(ctx, voting_period, reveal_period)
+}
+
+fn activate(ctx: Context<ActivateProposal>, voting_period: Option<i64>, reveal_period: i64) -> Result<()> {
+    let schedule = ctx.accounts.governor.voting_schedule;
     let proposal = &mut ctx.accounts.proposal;
     require!(proposal.state == ProposalState::Draft, ErrorCode::InvalidStateTransition);
-    require!(voting_period > 0, ErrorCode::InvalidVotingPeriod);
+    // A category's bounds may exclude the governor-wide default.
+    let voting_period = voting_period.unwrap_or_else(|| {
+        schedule.default_voting_period.max(proposal.min_voting_period).min(proposal.max_voting_period)
+    });
+    require!(
+        (proposal.min_voting_period..=proposal.max_voting_period).contains(&voting_period),
+        ErrorCode::InvalidVotingPeriod
+    );
 
     let now = Clock::get()?.unix_timestamp;
     proposal.state = ProposalState::Active;
     proposal.activated_at = now;
     proposal.voting_period = voting_period;
-    proposal.timelock_delay = ctx.accounts.governor.timelock_delay;
+    proposal.reveal_period = reveal_period;
+    proposal.review_period = if proposal.emergency { 0 } else { schedule.review_period };
 
     emit!(ProposalActivated {
+        version: EVENT_VERSION,
+        governor: proposal.governor,
         proposal: proposal.key(),
+        proposal_id: proposal.proposal_id,
         activated_at: proposal.activated_at,
         voting_period: proposal.voting_period,
         timelock_delay: proposal.timelock_delay,
+        review_period: proposal.review_period,
+        reveal_period,
     });
     Ok(())
 }
@@ -70,11 +187,16 @@ pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
         ErrorCode::UnauthorisedCancellation
     );
 
+    let now = Clock::get()?.unix_timestamp;
     proposal.state = ProposalState::Canceled;
+    proposal.finalised_at = now;
     emit!(ProposalCanceled {
+        version: EVENT_VERSION,
+        governor: proposal.governor,
         proposal: proposal.key(),
+        proposal_id: proposal.proposal_id,
         canceled_by: ctx.accounts.proposer.key(),
-        canceled_at: Clock::get()?.unix_timestamp,
+        canceled_at: now,
     });
     Ok(())
 }
@@ -91,7 +213,10 @@ pub fn queue_proposal(ctx: Context<QueueProposal>) -> Result<()> {
     proposal.state = ProposalState::Queued;
 
     emit!(ProposalQueued {
+        version: EVENT_VERSION,
+        governor: proposal.governor,
         proposal: proposal.key(),
+        proposal_id: proposal.proposal_id,
         queued_at: proposal.queued_at,
         ready_to_execute_at: proposal.ready_to_execute_at,
     });
@@ -100,39 +225,185 @@ pub fn queue_proposal(ctx: Context<QueueProposal>) -> Result<()> {
 
 pub fn finalise_proposal(ctx: Context<FinaliseProposal>) -> Result<()> {
     let proposal = &mut ctx.accounts.proposal;
-    let governor = &ctx.accounts.governor;
 
     require!(proposal.state == ProposalState::Active, ErrorCode::InvalidStateTransition);
 
     let now = Clock::get()?.unix_timestamp;
-    let end_time = proposal
-        .activated_at
-        .checked_add(proposal.voting_period)
-        .ok_or(ErrorCode::NumericalOverflow)?;
-    require!(now >= end_time, ErrorCode::VotingPeriodActive);
+    require_voting_ended(proposal, now)?;
 
-    let total_cast = proposal
-        .for_votes
-        .checked_add(proposal.against_votes)
-        .and_then(|v| v.checked_add(proposal.abstain_votes))
-        .ok_or(ErrorCode::NumericalOverflow)?;
-
-    if total_cast > 0 {
-        let for_percent = (proposal.for_votes as u128)
-            .checked_mul(100)
-            .ok_or(ErrorCode::NumericalOverflow)?
-            / (total_cast as u128);
-        proposal.state = if for_percent >= governor.vote_threshold as u128 {
+    if proposal.selection == OptionSelection::InstantRunoff {
+        // Rounds after the first need every ballot re-read, which
+        // `tally_ranked_votes` does; it concludes the proposal itself.
+        if !crate::ranked::run_rounds(proposal, &[])? {
+            return Ok(());
+        }
+    } else if proposal.is_multiple_choice() {
+        proposal.winning_option = winning_option(proposal)?;
+        proposal.state = if proposal.winning_option.is_some() {
             ProposalState::Succeeded
         } else {
             ProposalState::Rejected
         };
     } else {
-        proposal.state = ProposalState::Rejected;
+        tally_binary(proposal);
     }
+    record_outcome(proposal, now);
     Ok(())
 }
 
+/// Finalises a proposal before voting ends once no remaining vote can change
+/// the outcome.
+///
+/// Only registry-weighted binary proposals qualify: the registry bounds the
+/// weight still to be cast, and the governor's vote mutability must already
+/// stop cast votes from moving. The undecided weight is assumed to stay
+/// away, to go all against and to go all for; the outcome stands only if all
+/// three agree.
+pub fn finalise_proposal_early(ctx: Context<FinaliseProposal>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let governor = &ctx.accounts.governor;
+
+    require!(proposal.state == ProposalState::Active, ErrorCode::InvalidStateTransition);
+    require!(
+        governor.weight_source == WeightSource::Registry
+            && !proposal.is_multiple_choice()
+            && !proposal.is_secret_ballot(),
+        ErrorCode::EarlyFinalisationUnsupported
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(votes_are_final(governor, proposal, now)?, ErrorCode::OutcomeNotDecided);
+
+    let for_votes = proposal.for_votes as u128;
+    let cast = proposal.votes_cast();
+    let remaining = registry_votes(governor, proposal)
+        .checked_sub(cast)
+        .ok_or(ErrorCode::OutcomeNotDecided)?;
+    let outcomes = [
+        passes(proposal, for_votes, cast),
+        passes(proposal, for_votes, cast + remaining),
+        passes(proposal, for_votes + remaining, cast + remaining),
+    ];
+    require!(outcomes.iter().all(|&o| o == outcomes[0]), ErrorCode::OutcomeNotDecided);
+
+    tally_binary(proposal);
+    record_outcome(proposal, now);
+    Ok(())
+}
+
+/// Passes an emergency proposal while voting is still open.
+///
+/// The votes for it must reach the quorum on their own and make up the
+/// category's supermajority of everything cast so far. Queueing then applies
+/// the category's timelock, which may be zero.
+pub fn fast_track_proposal(ctx: Context<FinaliseProposal>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+
+    require!(proposal.state == ProposalState::Active, ErrorCode::InvalidStateTransition);
+    require!(
+        proposal.emergency && !proposal.is_multiple_choice() && !proposal.is_secret_ballot(),
+        ErrorCode::NotEmergencyProposal
+    );
+    let for_votes = proposal.for_votes as u128;
+    require!(
+        for_votes >= proposal.quorum as u128 && passes(proposal, for_votes, proposal.votes_cast()),
+        ErrorCode::EmergencyThresholdNotMet
+    );
+
+    proposal.state = ProposalState::Succeeded;
+    record_outcome(proposal, Clock::get()?.unix_timestamp);
+    Ok(())
+}
+
+/// Votes the whole registry would cast on `proposal`.
+fn registry_votes(governor: &Governor, proposal: &Proposal) -> u128 {
+    governor.voters.iter().map(|vi| proposal.voting_strategy.votes(vi.weight) as u128).sum()
+}
+
+/// Checks voting, and the reveal window of a secret ballot, are over.
+pub(crate) fn require_voting_ended(proposal: &Proposal, now: i64) -> Result<()> {
+    let end_time = proposal.counting_starts_at().ok_or(ErrorCode::NumericalOverflow)?;
+    require!(now >= end_time, ErrorCode::VotingPeriodActive);
+    Ok(())
+}
+
+/// Stamps a just-decided proposal and announces the outcome.
+pub(crate) fn record_outcome(proposal: &mut Account<Proposal>, now: i64) {
+    if proposal.state.is_terminal() {
+        proposal.finalised_at = now;
+    }
+
+    emit!(ProposalFinalised {
+        version: EVENT_VERSION,
+        governor: proposal.governor,
+        proposal: proposal.key(),
+        proposal_id: proposal.proposal_id,
+        state: proposal.state.clone(),
+        for_votes: proposal.for_votes,
+        against_votes: proposal.against_votes,
+        abstain_votes: proposal.abstain_votes,
+        winning_option: proposal.winning_option,
+        finalised_at: now,
+    });
+}
+
+fn tally_binary(proposal: &mut Proposal) {
+    proposal.state = if passes(proposal, proposal.for_votes as u128, proposal.votes_cast()) {
+        ProposalState::Succeeded
+    } else {
+        ProposalState::Rejected
+    };
+}
+
+/// What finalising now would decide, as `(succeeds, winning_option)`.
+pub(crate) fn provisional_outcome(proposal: &Proposal) -> Result<(bool, Option<u8>)> {
+    if proposal.is_multiple_choice() {
+        let winner = winning_option(proposal)?;
+        return Ok((winner.is_some(), winner));
+    }
+    Ok((passes(proposal, proposal.for_votes as u128, proposal.votes_cast()), None))
+}
+
+/// Whether `for_votes` out of `total` carry a yes/no proposal: `total` meets
+/// the quorum and `for_votes` make up at least the threshold. Never when
+/// nothing was cast.
+fn passes(proposal: &Proposal, for_votes: u128, total: u128) -> bool {
+    total > 0 && total >= proposal.quorum as u128 && for_votes * 100 / total >= proposal.vote_threshold as u128
+}
+
+/// The option that won under the proposal's selection rule, if any.
+fn winning_option(proposal: &Proposal) -> Result<Option<u8>> {
+    if !proposal.quorum_reached() {
+        return Ok(None);
+    }
+    let mut total_cast = proposal.abstain_votes;
+    let mut leader: Option<(usize, u64)> = None;
+    let mut tied = false;
+    for (index, option) in proposal.options.iter().enumerate() {
+        total_cast = total_cast.checked_add(option.votes).ok_or(ErrorCode::NumericalOverflow)?;
+        match leader {
+            Some((_, votes)) if option.votes == votes => tied = true,
+            Some((_, votes)) if option.votes < votes => {},
+            _ => {
+                leader = Some((index, option.votes));
+                tied = false;
+            },
+        }
+    }
+
+    let Some((index, votes)) = leader else { return Ok(None) };
+    if votes == 0 || tied {
+        return Ok(None);
+    }
+    if proposal.selection == OptionSelection::Threshold {
+        let percent = (votes as u128) * 100 / (total_cast as u128);
+        if percent < proposal.vote_threshold as u128 {
+            return Ok(None);
+        }
+    }
+    Ok(Some(index as u8))
+}
+
 pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
     let proposal = &mut ctx.accounts.proposal;
     require!(proposal.state == ProposalState::Queued, ErrorCode::InvalidStateTransition);
@@ -141,5 +412,14 @@ pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
     require!(now >= proposal.ready_to_execute_at, ErrorCode::TimelockNotExpired);
 
     proposal.state = ProposalState::Executed;
+    proposal.finalised_at = now;
+
+    emit!(ProposalExecuted {
+        version: EVENT_VERSION,
+        governor: proposal.governor,
+        proposal: proposal.key(),
+        proposal_id: proposal.proposal_id,
+        executed_at: now,
+    });
     Ok(())
 }
@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::clock::Clock;
+use crate::{
+    contexts::{CastVote, TallyRankedVotes},
+    errors::ErrorCode,
+    events::{RankedRoundTallied, RankedVoteCast, EVENT_VERSION},
+    enums::{OptionSelection, ProposalState, VoteSide, VoteState},
+    proposals::{provisional_outcome, record_outcome, require_voting_ended},
+    state::{Proposal, Vote},
+    voter_weight::{require_member, voting_power},
+    votes::{extend_for_late_vote, require_voting_open},
+};
+
+pub fn cast_ranked_vote(ctx: Context<CastVote>, ranking: Vec<u8>, weight: u64) -> Result<()> {
+    let governor = &ctx.accounts.governor;
+    let proposal = &mut ctx.accounts.proposal;
+    let vote = &mut ctx.accounts.vote;
+    let voter_key = ctx.accounts.voter.key();
+
+    require_member(governor, &voter_key)?;
+    require!(proposal.state == ProposalState::Active, ErrorCode::InvalidStateTransition);
+    require!(!proposal.is_secret_ballot(), ErrorCode::SecretBallot);
+    require!(vote.state == VoteState::Pending, ErrorCode::InvalidStateTransition);
+    require!(proposal.selection == OptionSelection::InstantRunoff, ErrorCode::InvalidRanking);
+    // Once counting has moved past the first round new ballots cannot join it.
+    require!(proposal.tally_round == 0, ErrorCode::InvalidStateTransition);
+    require!(
+        !ranking.is_empty()
+            && ranking.iter().enumerate().all(|(i, option)| {
+                (*option as usize) < proposal.options.len() && !ranking[..i].contains(option)
+            }),
+        ErrorCode::InvalidRanking
+    );
+
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    require_voting_open(proposal, now)?;
+    let power = voting_power(governor, voter_key, proposal.key(), Some(weight), ctx.remaining_accounts, &clock)?;
+    let weight = proposal.voting_strategy.votes(power);
+
+    let before = provisional_outcome(proposal)?;
+    let first = &mut proposal.options[ranking[0] as usize];
+    first.votes = first.votes.checked_add(weight).ok_or(ErrorCode::NumericalOverflow)?;
+    proposal.ranked_ballots = proposal.ranked_ballots.checked_add(1).ok_or(ErrorCode::NumericalOverflow)?;
+    proposal.tallied_ballots = proposal.ranked_ballots;
+
+    vote.side = VoteSide::Choice { index: ranking[0] };
+    vote.power = power;
+    vote.weight = weight;
+    vote.state = VoteState::Cast;
+    vote.ranking = ranking;
+    vote.tallied_round = 0;
+
+    emit!(RankedVoteCast {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        vote: vote.key(),
+        proposal: proposal.key(),
+        voter: voter_key,
+        ranking: vote.ranking.clone(),
+        weight,
+        voted_at: now,
+    });
+    extend_for_late_vote(governor, proposal, before, now)
+}
+
+/// Counts a batch of ranked ballots, passed as writable remaining accounts.
+///
+/// Permissionless. Call repeatedly with disjoint batches until every ballot
+/// has been counted; ballots already counted in the current round are
+/// skipped, so a batch can safely be resent.
+pub fn tally_ranked_votes(ctx: Context<TallyRankedVotes>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    require!(proposal.state == ProposalState::Active, ErrorCode::InvalidStateTransition);
+    require!(proposal.selection == OptionSelection::InstantRunoff, ErrorCode::InvalidRanking);
+
+    let now = Clock::get()?.unix_timestamp;
+    require_voting_ended(proposal, now)?;
+
+    if run_rounds(proposal, ctx.remaining_accounts)? {
+        record_outcome(proposal, now);
+    }
+    Ok(())
+}
+
+/// Advances instant-runoff counting as far as `votes` allows.
+///
+/// Every completed round either decides the proposal or eliminates the
+/// weakest option and recounts `votes` for the next round. Returns whether
+/// the proposal was decided.
+pub(crate) fn run_rounds(proposal: &mut Account<Proposal>, votes: &[AccountInfo]) -> Result<bool> {
+    // Round 0 holds every first preference, so turnout is judged there.
+    if proposal.tally_round == 0 && !proposal.quorum_reached() {
+        proposal.winning_option = None;
+        proposal.state = ProposalState::Rejected;
+        return Ok(true);
+    }
+    loop {
+        if proposal.tallied_ballots == proposal.ranked_ballots {
+            if let Some(decided) = complete_round(proposal)? {
+                proposal.winning_option = decided;
+                proposal.state = if decided.is_some() {
+                    ProposalState::Succeeded
+                } else {
+                    ProposalState::Rejected
+                };
+                return Ok(true);
+            }
+        }
+        for info in votes {
+            count_ballot(proposal, info)?;
+        }
+        if proposal.tallied_ballots < proposal.ranked_ballots {
+            return Ok(false);
+        }
+    }
+}
+
+/// Closes the current round: `Some(winner)` once decided, `None` after
+/// eliminating an option and opening the next round.
+fn complete_round(proposal: &mut Account<Proposal>) -> Result<Option<Option<u8>>> {
+    let live: Vec<usize> = (0..proposal.options.len())
+        .filter(|i| proposal.eliminated_options & (1 << i) == 0)
+        .collect();
+    let mut total: u64 = 0;
+    for &i in &live {
+        total = total.checked_add(proposal.options[i].votes).ok_or(ErrorCode::NumericalOverflow)?;
+    }
+    if total == 0 {
+        return Ok(Some(None));
+    }
+
+    // `max_by_key` keeps the last maximum, so iterate in reverse to favour
+    // the earlier option when leaders tie.
+    let leader = *live.iter().rev().max_by_key(|&&i| proposal.options[i].votes).unwrap();
+    let leader_votes = proposal.options[leader].votes as u128;
+    if live.len() == 1 || leader_votes * 2 > total as u128 {
+        return Ok(Some(Some(leader as u8)));
+    }
+
+    // Ties for last place eliminate the later option first.
+    let eliminated = *live.iter().rev().min_by_key(|&&i| proposal.options[i].votes).unwrap();
+    emit!(RankedRoundTallied {
+        version: EVENT_VERSION,
+        governor: proposal.governor,
+        proposal: proposal.key(),
+        proposal_id: proposal.proposal_id,
+        round: proposal.tally_round,
+        option_votes: proposal.options.iter().map(|o| o.votes).collect(),
+        eliminated_option: eliminated as u8,
+    });
+
+    proposal.eliminated_options |= 1 << eliminated;
+    proposal.tally_round = proposal.tally_round.checked_add(1).ok_or(ErrorCode::NumericalOverflow)?;
+    proposal.tallied_ballots = 0;
+    for option in proposal.options.iter_mut() {
+        option.votes = 0;
+    }
+    Ok(None)
+}
+
+/// Adds one ballot to its highest-ranked live option for the current round.
+fn count_ballot(proposal: &mut Account<Proposal>, info: &AccountInfo) -> Result<()> {
+    require!(info.owner == &crate::ID && info.is_writable, ErrorCode::InvalidVoteAccount);
+    let mut vote = {
+        let data = info.try_borrow_data()?;
+        Vote::try_deserialize(&mut &data[..])?
+    };
+    require!(
+        vote.proposal == proposal.key() && vote.state == VoteState::Cast && !vote.ranking.is_empty(),
+        ErrorCode::InvalidVoteAccount
+    );
+    if vote.tallied_round == proposal.tally_round {
+        return Ok(());
+    }
+
+    let eliminated = proposal.eliminated_options;
+    // Exhausted ballots still count as counted, but add to no option.
+    if let Some(&choice) = vote.ranking.iter().find(|&&o| eliminated & (1 << o) == 0) {
+        let option = &mut proposal.options[choice as usize];
+        option.votes = option.votes.checked_add(vote.weight).ok_or(ErrorCode::NumericalOverflow)?;
+    }
+    proposal.tallied_ballots = proposal.tallied_ballots.checked_add(1).ok_or(ErrorCode::NumericalOverflow)?;
+
+    vote.tallied_round = proposal.tally_round;
+    let mut data = info.try_borrow_mut_data()?;
+    vote.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
@@ -0,0 +1,148 @@
+//! Votes signed off-chain and submitted in batches by a relayer.
+//!
+//! Voters sign `relayed_vote_message` with their wallet key. The relayer
+//! verifies the signatures with ed25519 program instructions placed earlier
+//! in the same transaction, and `cast_relayed_votes` reads them back through
+//! the instructions sysvar. Each vote is recorded in the voter's ordinary
+//! `Vote` account, whose rent the relayer pays, so voters need no SOL. The
+//! vote account is also the nullifier: once it exists, the voter cannot be
+//! relayed or create a vote again on that proposal.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    sysvar::{
+        clock::Clock,
+        instructions::{load_current_index_checked, load_instruction_at_checked},
+    },
+};
+use crate::{
+    contexts::CastRelayedVotes,
+    enums::{VoteSide, VoteState, WeightSource},
+    errors::ErrorCode,
+    events::{VoteSetEvent, EVENT_VERSION},
+    proposals::provisional_outcome,
+    state::{SignedVote, Vote},
+    voter_weight::{require_member, voting_power},
+    votes::{create_vote_account, extend_for_late_vote, require_voting_open, tally_for},
+};
+
+/// Prefix of every relayed vote message, so a vote signature cannot be
+/// passed off as a signature over anything else.
+pub const RELAYED_VOTE_DOMAIN: &[u8] = b"iterative-dao:relayed-vote:v1";
+
+/// The count byte and padding byte ahead of the first signature's offsets.
+const SIGNATURE_OFFSETS_START: usize = 2;
+/// Seven little-endian `u16` offsets per signature.
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+
+/// The bytes a voter signs to vote `side` with `weight` on `proposal`.
+pub fn relayed_vote_message(proposal: &Pubkey, side: &VoteSide, weight: u64) -> Vec<u8> {
+    let mut message = RELAYED_VOTE_DOMAIN.to_vec();
+    message.extend_from_slice(proposal.as_ref());
+    side.serialize(&mut message).expect("VoteSide serialises");
+    message.extend_from_slice(&weight.to_le_bytes());
+    message
+}
+
+/// Signer and message of every signature verified by the ed25519 program
+/// instructions that precede this one.
+fn verified_signatures(instructions: &AccountInfo) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    let current = load_current_index_checked(instructions)?;
+    let mut verified = Vec::new();
+    for index in 0..current {
+        let ix = load_instruction_at_checked(index as usize, instructions)?;
+        if ix.program_id == ed25519_program::ID {
+            read_signatures(&ix.data, index, &mut verified)?;
+        }
+    }
+    Ok(verified)
+}
+
+/// Reads the signatures of the ed25519 program instruction at `index`.
+///
+/// Keys and messages must sit in that instruction's own data; the precompile
+/// would also verify data held by other instructions, but this program does
+/// not follow such references.
+fn read_signatures(data: &[u8], index: u16, verified: &mut Vec<(Pubkey, Vec<u8>)>) -> Result<()> {
+    let malformed = || error!(ErrorCode::InvalidSignatureInstruction);
+    let count = *data.first().ok_or_else(malformed)? as usize;
+    for n in 0..count {
+        let start = SIGNATURE_OFFSETS_START + n * SIGNATURE_OFFSETS_LEN;
+        let offsets = data.get(start..start + SIGNATURE_OFFSETS_LEN).ok_or_else(malformed)?;
+        let field = |i: usize| u16::from_le_bytes([offsets[2 * i], offsets[2 * i + 1]]) as usize;
+        let inline = |i: usize| field(i) == u16::MAX as usize || field(i) == index as usize;
+        // (signature, signature ix, key, key ix, message, message size, message ix)
+        require!(inline(3) && inline(6), ErrorCode::InvalidSignatureInstruction);
+
+        let key = data.get(field(2)..field(2) + 32).ok_or_else(malformed)?;
+        let message = data.get(field(4)..field(4) + field(5)).ok_or_else(malformed)?;
+        verified.push((Pubkey::try_from(key).map_err(|_| malformed())?, message.to_vec()));
+    }
+    Ok(())
+}
+
+/// Records a batch of votes signed off-chain.
+///
+/// The `i`th remaining account is the vote address of `votes[i].voter`,
+/// which must not exist yet. Votes count at the signed weight under declared
+/// weighting, or at the voter's registered weight.
+pub fn cast_relayed_votes<'info>(
+    ctx: Context<'_, '_, '_, 'info, CastRelayedVotes<'info>>,
+    votes: Vec<SignedVote>,
+) -> Result<()> {
+    let governor = &ctx.accounts.governor;
+    let proposal_key = ctx.accounts.proposal.key();
+    require!(
+        matches!(governor.weight_source, WeightSource::Declared | WeightSource::Registry),
+        ErrorCode::RelayedVotesUnsupported
+    );
+    require!(!ctx.accounts.proposal.is_secret_ballot(), ErrorCode::SecretBallot);
+    require!(votes.len() == ctx.remaining_accounts.len(), ErrorCode::InvalidVoteAccount);
+
+    let verified = verified_signatures(&ctx.accounts.instructions)?;
+    let payer = ctx.accounts.payer.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let clock = Clock::get()?;
+    require_voting_open(&ctx.accounts.proposal, clock.unix_timestamp)?;
+    for (signed, info) in votes.into_iter().zip(ctx.remaining_accounts) {
+        require_member(governor, &signed.voter)?;
+        let message = relayed_vote_message(&proposal_key, &signed.side, signed.weight);
+        require!(
+            verified.iter().any(|(key, signed_message)| *key == signed.voter && *signed_message == message),
+            ErrorCode::MissingVoteSignature
+        );
+
+        let power = voting_power(governor, signed.voter, proposal_key, Some(signed.weight), &[], &clock)?;
+        let proposal = &mut ctx.accounts.proposal;
+        let weight = proposal.voting_strategy.votes(power);
+        let before = provisional_outcome(proposal)?;
+        let tally = tally_for(proposal, &signed.side)?;
+        *tally = tally.checked_add(weight).ok_or(ErrorCode::NumericalOverflow)?;
+
+        require!(info.data_is_empty() && info.owner == &System::id(), ErrorCode::AlreadyVoted);
+        create_vote_account(info, &proposal_key, &signed.voter, &payer, &system_program)?;
+        let vote = Vote {
+            side: signed.side.clone(),
+            weight,
+            state: VoteState::Cast,
+            power,
+            ..Vote::new(governor.key(), proposal_key, signed.voter, payer.key())
+        };
+        vote.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        emit!(VoteSetEvent {
+            version: EVENT_VERSION,
+            governor: governor.key(),
+            vote: info.key(),
+            proposal: proposal_key,
+            voter: signed.voter,
+            side: signed.side,
+            weight,
+            voted_at: clock.unix_timestamp,
+            rationale: None,
+        });
+        extend_for_late_vote(governor, proposal, before, clock.unix_timestamp)?;
+    }
+    Ok(())
+}
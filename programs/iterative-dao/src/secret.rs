@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hashv, sysvar::clock::Clock};
+use crate::{
+    contexts::SetVote,
+    enums::{ProposalState, VoteSide, VoteState},
+    errors::ErrorCode,
+    events::{VoteCommitted, VoteSetEvent, EVENT_VERSION},
+    voter_weight::{require_member, voting_power},
+    votes::{require_vote_changeable, require_voting_open, tally_for},
+};
+
+/// The hash a voter commits to for `side` on a secret ballot.
+///
+/// Binding the proposal and voter stops a commitment being copied onto
+/// another ballot and revealed once its author has revealed the salt.
+pub fn ballot_commitment(proposal: &Pubkey, voter: &Pubkey, side: &VoteSide, salt: &[u8; 32]) -> [u8; 32] {
+    let side = side.try_to_vec().expect("VoteSide serialises");
+    hashv(&[proposal.as_ref(), voter.as_ref(), &side, salt]).to_bytes()
+}
+
+/// Records a commitment and the voter's power while voting is open.
+///
+/// May be repeated until voting closes, as the governor's vote mutability
+/// allows; the last commitment is the one that must be revealed.
+pub fn commit_vote(ctx: Context<SetVote>, commitment: [u8; 32], weight: u64) -> Result<()> {
+    let governor = &ctx.accounts.governor;
+    let proposal = &ctx.accounts.proposal;
+    let vote = &mut ctx.accounts.vote;
+    let voter_key = ctx.accounts.voter.key();
+
+    require_member(governor, &voter_key)?;
+    require!(proposal.state == ProposalState::Active, ErrorCode::InvalidStateTransition);
+    require!(proposal.is_secret_ballot(), ErrorCode::NotSecretBallot);
+    require!(vote.state != VoteState::Cast, ErrorCode::InvalidStateTransition);
+
+    let clock = Clock::get()?;
+    require_voting_open(proposal, clock.unix_timestamp)?;
+    if vote.state == VoteState::Committed {
+        require_vote_changeable(governor, proposal, clock.unix_timestamp)?;
+    }
+
+    let power = voting_power(governor, voter_key, proposal.key(), Some(weight), ctx.remaining_accounts, &clock)?;
+    vote.power = power;
+    vote.weight = proposal.voting_strategy.votes(power);
+    vote.commitment = commitment;
+    vote.state = VoteState::Committed;
+
+    emit!(VoteCommitted {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        vote: vote.key(),
+        proposal: proposal.key(),
+        voter: voter_key,
+        commitment,
+        committed_at: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Opens a commitment during the reveal window and counts it.
+pub fn reveal_vote(ctx: Context<SetVote>, side: VoteSide, salt: [u8; 32]) -> Result<()> {
+    let governor = &ctx.accounts.governor;
+    let proposal = &mut ctx.accounts.proposal;
+    let vote = &mut ctx.accounts.vote;
+    let voter_key = ctx.accounts.voter.key();
+
+    require!(proposal.state == ProposalState::Active, ErrorCode::InvalidStateTransition);
+    require!(proposal.is_secret_ballot(), ErrorCode::NotSecretBallot);
+    require!(vote.state == VoteState::Committed, ErrorCode::InvalidStateTransition);
+
+    let now = Clock::get()?.unix_timestamp;
+    let ends_at = proposal.voting_ends_at().ok_or(ErrorCode::NumericalOverflow)?;
+    let reveal_ends_at = proposal.counting_starts_at().ok_or(ErrorCode::NumericalOverflow)?;
+    require!(now >= ends_at, ErrorCode::VotingPeriodActive);
+    require!(now < reveal_ends_at, ErrorCode::RevealPeriodEnded);
+    require!(
+        ballot_commitment(&proposal.key(), &voter_key, &side, &salt) == vote.commitment,
+        ErrorCode::CommitmentMismatch
+    );
+
+    let tally = tally_for(proposal, &side)?;
+    *tally = tally.checked_add(vote.weight).ok_or(ErrorCode::NumericalOverflow)?;
+    vote.side = side.clone();
+    vote.state = VoteState::Cast;
+
+    emit!(VoteSetEvent {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        vote: vote.key(),
+        proposal: proposal.key(),
+        voter: voter_key,
+        side,
+        weight: vote.weight,
+        voted_at: now,
+        rationale: None,
+    });
+    Ok(())
+}
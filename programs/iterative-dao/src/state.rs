@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::enums::{ProposalState, VoteSide, VoteState};
+use crate::enums::{
+    LockKind, OptionSelection, ProposalState, VoteMutability, VoteSide, VoteState, VoterWeightAction, VotingStrategy,
+    WeightSource,
+};
 
 #[account]
 pub struct Governor {
@@ -11,11 +14,67 @@ pub struct Governor {
     pub proposal_count: u64,
     pub governance_mint: Pubkey,
     pub voters: Vec<VoterInfo>,
-    pub padding: [u8; 2],
+    /// Applied to proposals created from now on.
+    pub voting_strategy: VotingStrategy,
+    /// Where `cast_vote`, `set_vote` and `cast_ranked_vote` read voting power from.
+    pub weight_source: WeightSource,
+    /// Whether votes can be changed or withdrawn after they are cast.
+    pub vote_mutability: VoteMutability,
+    /// Extends voting when a late vote moves the outcome; `None` to never extend.
+    pub late_vote_extension: Option<LateVoteExtension>,
+    /// Proposal categories configured; once there is one, every new proposal
+    /// must name a category.
+    pub category_count: u8,
+    /// Voting period bounds and review period of uncategorised proposals.
+    pub voting_schedule: VotingSchedule,
+    pub padding: [u8; 1],
 }
 
 impl Governor {
-    pub const LEN: usize = 8 + 1 + 8 + 32 + 32 + 1 + 8 + 32 + 4 + (16 * 40) + 2;
+    pub const LEN: usize = 8 + 1 + 8 + 32 + 32 + 1 + 8 + 32 + 4 + (16 * 40) + 1 + 33 + 9 + 25 + 1 + 32 + 1;
+}
+
+/// How long proposals may be voted on, and how long members get to review
+/// them before voting opens.
+#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug)]
+pub struct VotingSchedule {
+    pub min_voting_period: i64,
+    pub max_voting_period: i64,
+    /// Used when activation does not name a voting period.
+    pub default_voting_period: i64,
+    /// Seconds from activation to the first vote; emergency proposals skip it.
+    pub review_period: i64,
+}
+
+impl VotingSchedule {
+    pub fn is_valid(&self) -> bool {
+        self.min_voting_period > 0
+            && (self.min_voting_period..=self.max_voting_period).contains(&self.default_voting_period)
+            && self.review_period >= 0
+    }
+}
+
+impl Default for VotingSchedule {
+    /// Any positive voting period, three days unless named, with no review.
+    fn default() -> Self {
+        Self { min_voting_period: 1, max_voting_period: i64::MAX, default_voting_period: 259_200, review_period: 0 }
+    }
+}
+
+/// A vote cast in the last `window` seconds of voting that moves the
+/// provisional outcome adds `extension` seconds to the voting period, up to
+/// `max_extension` seconds per proposal.
+#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq, Debug)]
+pub struct LateVoteExtension {
+    pub window: i64,
+    pub extension: i64,
+    pub max_extension: i64,
+}
+
+impl LateVoteExtension {
+    pub fn is_valid(&self) -> bool {
+        self.window > 0 && self.extension > 0 && self.max_extension >= self.extension
+    }
 }
 
 #[derive(Clone, AnchorSerialize, AnchorDeserialize)]
@@ -39,11 +98,170 @@ pub struct Proposal {
     pub for_votes: u64,
     pub against_votes: u64,
     pub abstain_votes: u64,
-    pub padding: [u8; 3],
+    /// Account that paid rent and receives it back on close.
+    pub payer: Pubkey,
+    /// When the proposal reached a terminal state; zero until then.
+    pub finalised_at: i64,
+    /// Empty for a yes/no proposal; otherwise votes pick one of these.
+    pub options: Vec<ProposalOption>,
+    pub selection: OptionSelection,
+    /// Index into `options` chosen at finalisation, if any option won.
+    pub winning_option: Option<u8>,
+    /// Ranked ballots cast on an `InstantRunoff` proposal.
+    pub ranked_ballots: u32,
+    /// Instant-runoff round being counted; round 0 is tallied as votes are cast.
+    pub tally_round: u8,
+    /// Ballots counted so far in `tally_round`.
+    pub tallied_ballots: u32,
+    /// Bit `i` is set once `options[i]` has been eliminated.
+    pub eliminated_options: u8,
+    /// The governor's strategy when the proposal was created; every vote on
+    /// the proposal, and so its threshold check, is counted with it.
+    pub voting_strategy: VotingStrategy,
+    /// Reveal window after voting for a secret ballot; zero for an open one.
+    pub reveal_period: i64,
+    /// Seconds added to `voting_period` by late votes; see `LateVoteExtension`.
+    pub voting_extension: i64,
+    /// `ProposalCategory::id` the proposal was created under, if any.
+    pub category: Option<u8>,
+    /// Terms fixed at creation, from the category or else the governor.
+    pub vote_threshold: u8,
+    /// Votes, abstentions included, needed for the proposal to succeed.
+    pub quorum: u64,
+    pub min_voting_period: i64,
+    pub max_voting_period: i64,
+    /// Created under an emergency category.
+    pub emergency: bool,
+    /// Seconds between activation and the start of voting.
+    pub review_period: i64,
+    pub padding: [u8; 2],
 }
 
 impl Proposal {
-    pub const LEN: usize = 8 + 32 + 32 + 4 + (10 * 136) + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 3;
+    /// Instructions stored across `instructions` and every option.
+    pub const MAX_INSTRUCTIONS: usize = 10;
+    /// Also bounded by the width of `eliminated_options`.
+    pub const MAX_OPTIONS: usize = 8;
+    pub const MAX_OPTION_LABEL_LEN: usize = 32;
+    pub const LEN: usize = 8 + 32 + 32 + 4 + (Self::MAX_INSTRUCTIONS * 136) + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8
+        + 32 + 8 + 4 + (Self::MAX_OPTIONS * (4 + Self::MAX_OPTION_LABEL_LEN + 4 + 8)) + 1 + 2 + 4 + 1 + 4 + 1 + 1
+        + 8 + 8 + 2 + 1 + 8 + 8 + 8 + 1 + 8 + 2;
+
+    pub fn is_multiple_choice(&self) -> bool {
+        !self.options.is_empty()
+    }
+
+    pub fn is_secret_ballot(&self) -> bool {
+        self.reveal_period > 0
+    }
+
+    /// Every vote counted so far, abstentions included.
+    pub fn votes_cast(&self) -> u128 {
+        let options: u128 = self.options.iter().map(|o| o.votes as u128).sum();
+        self.for_votes as u128 + self.against_votes as u128 + self.abstain_votes as u128 + options
+    }
+
+    pub fn quorum_reached(&self) -> bool {
+        self.votes_cast() >= self.quorum as u128
+    }
+
+    /// When voting opens, once the review period has passed.
+    pub fn voting_starts_at(&self) -> Option<i64> {
+        self.activated_at.checked_add(self.review_period)
+    }
+
+    /// When voting, or committing on a secret ballot, closes.
+    pub fn voting_ends_at(&self) -> Option<i64> {
+        self.voting_starts_at()?.checked_add(self.voting_period)
+    }
+
+    /// When votes can be counted: once the reveal window, if any, has closed.
+    pub fn counting_starts_at(&self) -> Option<i64> {
+        self.voting_ends_at()?.checked_add(self.reveal_period)
+    }
+
+    /// What an executed proposal approved: the winning option's
+    /// instructions for a multiple-choice proposal, `instructions` otherwise.
+    pub fn executable_instructions(&self) -> &[ProposalInstruction] {
+        match self.winning_option {
+            Some(index) => &self.options[index as usize].instructions,
+            None => &self.instructions,
+        }
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct ProposalOption {
+    pub label: String,
+    /// May be empty for options that only record a preference.
+    pub instructions: Vec<ProposalInstruction>,
+    pub votes: u64,
+}
+
+/// An option as supplied to `create_multiple_choice_proposal`.
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct NewProposalOption {
+    pub label: String,
+    pub instructions: Vec<ProposalInstruction>,
+}
+
+/// A vote signed off-chain and submitted by a relayer; see `relay`.
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SignedVote {
+    pub voter: Pubkey,
+    pub side: VoteSide,
+    /// Weight the voter declared, as signed.
+    pub weight: u64,
+}
+
+/// A voter's reasons given with a vote: a short text, a link to a longer
+/// write-up and the hash of that write-up, any of which may be empty.
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct VoteRationale {
+    pub reason: String,
+    pub uri: String,
+    pub uri_hash: [u8; 32],
+}
+
+impl VoteRationale {
+    pub const MAX_REASON_LEN: usize = 256;
+    pub const MAX_URI_LEN: usize = 200;
+
+    /// Within the length limits and with a reason or a URI.
+    pub fn is_valid(&self) -> bool {
+        self.reason.len() <= Self::MAX_REASON_LEN
+            && self.uri.len() <= Self::MAX_URI_LEN
+            && !(self.reason.is_empty() && self.uri.is_empty())
+    }
+}
+
+/// One proposal's vote in a `cast_votes` batch.
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct BatchedVote {
+    pub side: VoteSide,
+    pub weight: u64,
+}
+
+/// Voting power published by a voter-weight add-in, laid out as SPL
+/// Governance's `VoterWeightRecord` so existing add-ins can be reused.
+///
+/// `realm` holds the governor's address.
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct VoterWeightRecord {
+    pub realm: Pubkey,
+    pub governing_token_mint: Pubkey,
+    pub governing_token_owner: Pubkey,
+    pub voter_weight: u64,
+    /// Last slot the weight may be used in; `None` never expires.
+    pub voter_weight_expiry: Option<u64>,
+    pub weight_action: Option<VoterWeightAction>,
+    pub weight_action_target: Option<Pubkey>,
+    pub reserved: [u8; 8],
+}
+
+impl VoterWeightRecord {
+    /// `sha256("account:VoterWeightRecord")[..8]`, the prefix every add-in writes.
+    pub const DISCRIMINATOR: [u8; 8] = [46, 249, 155, 75, 153, 248, 116, 9];
 }
 
 #[account]
@@ -51,45 +269,209 @@ pub struct Vote {
     pub proposal: Pubkey,
     pub voter: Pubkey,
     pub side: VoteSide,
+    /// Votes counted for this ballot under the proposal's voting strategy.
     pub weight: u64,
     pub state: VoteState,
+    pub payer: Pubkey,
+    /// Option indices in order of preference, for instant-runoff proposals.
+    pub ranking: Vec<u8>,
+    /// Last instant-runoff round this ballot was counted in.
+    pub tallied_round: u8,
+    /// Voting power behind `weight`, before the voting strategy is applied.
+    pub power: u64,
+    /// Hash committed to on a secret ballot; see `secret::ballot_commitment`.
+    pub commitment: [u8; 32],
+    /// Kept so the vote can still be attributed once its proposal is closed.
+    pub governor: Pubkey,
     pub padding: [u8; 6],
 }
 
 impl Vote {
-    pub const LEN: usize = 8 + 32 + 32 + 1 + 8 + 1 + 6;
+    pub const LEN: usize = 8 + 32 + 32 + 2 + 8 + 1 + 32 + 4 + Proposal::MAX_OPTIONS + 1 + 8 + 32 + 32 + 6;
+
+    /// A pending vote with nothing cast yet.
+    pub fn new(governor: Pubkey, proposal: Pubkey, voter: Pubkey, payer: Pubkey) -> Self {
+        Self {
+            proposal,
+            voter,
+            side: VoteSide::Abstain {},
+            weight: 0,
+            state: VoteState::Pending,
+            payer,
+            ranking: Vec::new(),
+            tallied_round: 0,
+            power: 0,
+            commitment: [0u8; 32],
+            governor,
+            padding: [0u8; 6],
+        }
+    }
+}
+
+/// A class of proposal with its own bar to pass, such as treasury grants or
+/// program upgrades.
+///
+/// Proposals created under a category may only call `programs`, and take
+/// the category's terms in place of the governor's.
+#[account]
+pub struct ProposalCategory {
+    pub governor: Pubkey,
+    pub id: u8,
+    pub name: String,
+    pub vote_threshold: u8,
+    pub quorum: u64,
+    pub min_voting_period: i64,
+    pub max_voting_period: i64,
+    pub timelock_delay: i64,
+    pub programs: Vec<Pubkey>,
+    /// Lets yes/no proposals pass through `fast_track_proposal` as soon as
+    /// the votes for them alone meet the quorum and threshold.
+    pub emergency: bool,
+    pub bump: u8,
+}
+
+impl ProposalCategory {
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const MAX_PROGRAMS: usize = 8;
+    /// Lowest `vote_threshold` an emergency category may have.
+    pub const MIN_EMERGENCY_THRESHOLD: u8 = 67;
+    pub const LEN: usize =
+        8 + 32 + 1 + 4 + Self::MAX_NAME_LEN + 1 + 8 + 8 + 8 + 8 + 4 + (Self::MAX_PROGRAMS * 32) + 1 + 1;
+}
+
+/// The settable fields of a `ProposalCategory`.
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct ProposalCategoryConfig {
+    pub name: String,
+    pub vote_threshold: u8,
+    pub quorum: u64,
+    pub min_voting_period: i64,
+    pub max_voting_period: i64,
+    pub timelock_delay: i64,
+    pub programs: Vec<Pubkey>,
+    pub emergency: bool,
+}
+
+impl ProposalCategoryConfig {
+    /// Voting periods must lie within the governor's `schedule`. Emergency
+    /// categories also need a supermajority threshold and a quorum, since
+    /// their proposals can pass before anyone else votes.
+    pub fn is_valid(&self, schedule: &VotingSchedule) -> bool {
+        (1..=ProposalCategory::MAX_NAME_LEN).contains(&self.name.len())
+            && self.vote_threshold <= 100
+            && self.min_voting_period >= schedule.min_voting_period
+            && self.min_voting_period <= self.max_voting_period
+            && self.max_voting_period <= schedule.max_voting_period
+            && self.timelock_delay >= 0
+            && (1..=ProposalCategory::MAX_PROGRAMS).contains(&self.programs.len())
+            && (!self.emergency
+                || (self.vote_threshold >= ProposalCategory::MIN_EMERGENCY_THRESHOLD && self.quorum > 0))
+    }
+}
+
+/// A Metaplex collection whose NFTs vote under `WeightSource::Nft`.
+///
+/// Traits can be weighted differently by verifying each tier into its own
+/// collection and configuring each with its own weight.
+#[account]
+pub struct NftCollection {
+    pub governor: Pubkey,
+    /// Mint of the collection NFT.
+    pub collection: Pubkey,
+    /// Voting power per NFT; zero stops the collection from voting.
+    pub weight: u64,
+    pub bump: u8,
+}
+
+impl NftCollection {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 1;
+}
+
+/// Marks an NFT as used on a proposal, whoever holds it afterwards.
+#[account]
+pub struct NftVoteRecord {
+    pub proposal: Pubkey,
+    pub mint: Pubkey,
+    /// The vote the NFT's weight was added to.
+    pub vote: Pubkey,
+    pub weight: u64,
+    pub payer: Pubkey,
+    pub bump: u8,
+    pub governor: Pubkey,
+}
+
+impl NftVoteRecord {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 32 + 1 + 32;
 }
 
 #[account]
 pub struct LockAccount {
     pub user: Pubkey,
+    pub governor: Pubkey,
     pub amount: u64,
     pub start_time: i64,
     pub duration: i64,
     pub end_time: i64,
     pub withdrawn: bool,
     pub escrow_token_account: Pubkey,
+    pub kind: LockKind,
+    pub cliff_time: i64,
+    pub claimed_amount: u64,
+    pub payer: Pubkey,
+    pub withdrawn_at: i64,
     pub padding: [u8; 6],
     pub bump: u8,
 }
 
 impl LockAccount {
-    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 8 + 1 + 32 + 6 + 1;
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 32 + 1 + 8 + 8 + 32 + 8 + 6 + 1;
+
+    /// Total amount released by `now`, including anything already claimed.
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        match self.kind {
+            LockKind::Standard => {
+                if now >= self.end_time { self.amount } else { 0 }
+            },
+            LockKind::Vesting => {
+                if now < self.cliff_time {
+                    0
+                } else if now >= self.end_time || self.duration <= 0 {
+                    self.amount
+                } else {
+                    let elapsed = (now - self.start_time) as u128;
+                    ((self.amount as u128) * elapsed / (self.duration as u128)) as u64
+                }
+            },
+        }
+    }
+
+    /// Amount still held in escrow and not yet released.
+    pub fn remaining_locked(&self, now: i64) -> u64 {
+        if self.withdrawn {
+            return 0;
+        }
+        self.amount.saturating_sub(self.vested_amount(now))
+    }
 }
 
 #[account]
 pub struct Locker {
     pub governor: Pubkey,
     pub authority: Pubkey,
+    pub mint: Pubkey,
+    /// Voting power granted per escrowed token of `mint`.
     pub voting_power_multiplier: u64,
     pub min_lock_duration: i64,
     pub max_lock_duration: i64,
     pub total_locked: u64,
     pub bump: u8,
+    /// Parameter changes must come from a proposal with at least this id,
+    /// so an applied or older proposal cannot be replayed over newer ones.
+    pub next_params_proposal_id: u64,
 }
 
 impl Locker {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1;
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 8;
 }
 
 #[account]
@@ -102,11 +484,15 @@ pub struct Escrow {
     pub end_time: i64,
     pub withdrawn: bool,
     pub escrow_token_account: Pubkey,
+    /// Locker multiplier at creation; later parameter changes do not apply.
+    pub voting_power_multiplier: u64,
+    pub payer: Pubkey,
+    pub withdrawn_at: i64,
     pub bump: u8,
 }
 
 impl Escrow {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 32 + 1;
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 32 + 8 + 32 + 8 + 1;
 }
 
 #[derive(Clone, AnchorSerialize, AnchorDeserialize)]
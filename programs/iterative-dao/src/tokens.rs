@@ -1,11 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::sysvar::clock::Clock;
-use anchor_spl::token::{self, Transfer, Token, TokenAccount};
+use anchor_spl::token::{self, Transfer};
 use crate::{
-    contexts::{LockTokens, WithdrawTokens},
+    contexts::{LockTokens, WithdrawTokens, LockVestingTokens, ClaimVested},
     errors::ErrorCode,
-    events::{LockEvent, WithdrawEvent},
-    state::LockAccount,
+    events::{LockEvent, WithdrawEvent, VestingLockEvent, ClaimVestedEvent, EVENT_VERSION},
+    enums::LockKind,
 };
 
 pub fn lock_tokens(ctx: Context<LockTokens>, amount: u64, duration: i64, lock_id: u64) -> Result<()> {
@@ -31,21 +31,30 @@ pub fn lock_tokens(ctx: Context<LockTokens>, amount: u64, duration: i64, lock_id
 
     let now = Clock::get()?.unix_timestamp;
     lock_acc.user = user_key;
+    lock_acc.governor = governor.key();
     lock_acc.amount = amount;
     lock_acc.start_time = now;
     lock_acc.duration = duration;
     lock_acc.end_time = now.checked_add(duration).ok_or(ErrorCode::NumericalOverflow)?;
     lock_acc.withdrawn = false;
     lock_acc.escrow_token_account = ctx.accounts.escrow_token_account.key();
+    lock_acc.kind = LockKind::Standard;
+    lock_acc.cliff_time = lock_acc.end_time;
+    lock_acc.claimed_amount = 0;
+    lock_acc.payer = ctx.accounts.payer.key();
+    lock_acc.withdrawn_at = 0;
     lock_acc.padding = [0u8; 6];
     lock_acc.bump = ctx.bumps.lock_account; // Use indexing
 
     emit!(LockEvent {
+        version: EVENT_VERSION,
+        governor: governor.key(),
         lock_account: lock_acc.key(),
         user: user_key,
         amount,
         start_time: lock_acc.start_time,
         end_time: lock_acc.end_time,
+        lock_id,
     });
     Ok(())
 }
@@ -65,12 +74,127 @@ pub fn withdraw_tokens(ctx: Context<WithdrawTokens>, lock_id: u64) -> Result<()>
         authority: ctx.accounts.smart_wallet.to_account_info(),
     };
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-    token::transfer(cpi_ctx, lock_account.amount).map_err(|_| ErrorCode::InsufficientBalance)?;
+    let remaining = lock_account
+        .amount
+        .checked_sub(lock_account.claimed_amount)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    token::transfer(cpi_ctx, remaining).map_err(|_| ErrorCode::InsufficientBalance)?;
+    lock_account.claimed_amount = lock_account.amount;
     lock_account.withdrawn = true;
+    lock_account.withdrawn_at = now;
     emit!(WithdrawEvent {
+        version: EVENT_VERSION,
+        governor: ctx.accounts.governor.key(),
+        lock_account: lock_account.key(),
+        user: lock_account.user,
+        amount: remaining,
+        lock_id,
+        withdrawn_at: now,
+    });
+    Ok(())
+}
+
+pub fn lock_vesting_tokens(
+    ctx: Context<LockVestingTokens>,
+    amount: u64,
+    cliff_duration: i64,
+    duration: i64,
+    lock_id: u64,
+) -> Result<()> {
+    let governor = &ctx.accounts.governor;
+    let lock_acc = &mut ctx.accounts.lock_account;
+    let user_key = ctx.accounts.user.key();
+
+    require!(
+        governor.voters.iter().any(|v| v.pubkey == user_key),
+        ErrorCode::UnauthorisedVoter
+    );
+    require!(amount > 0 && duration > 0, ErrorCode::InvalidLockParameters);
+    require!(
+        cliff_duration >= 0 && cliff_duration <= duration,
+        ErrorCode::InvalidLockParameters
+    );
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.user_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+    token::transfer(cpi_ctx, amount).map_err(|_| ErrorCode::InsufficientBalance)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    lock_acc.user = user_key;
+    lock_acc.governor = governor.key();
+    lock_acc.amount = amount;
+    lock_acc.start_time = now;
+    lock_acc.duration = duration;
+    lock_acc.end_time = now.checked_add(duration).ok_or(ErrorCode::NumericalOverflow)?;
+    lock_acc.withdrawn = false;
+    lock_acc.escrow_token_account = ctx.accounts.escrow_token_account.key();
+    lock_acc.kind = LockKind::Vesting;
+    lock_acc.cliff_time = now.checked_add(cliff_duration).ok_or(ErrorCode::NumericalOverflow)?;
+    lock_acc.claimed_amount = 0;
+    lock_acc.payer = ctx.accounts.payer.key();
+    lock_acc.withdrawn_at = 0;
+    lock_acc.padding = [0u8; 6];
+    lock_acc.bump = ctx.bumps.lock_account;
+
+    emit!(VestingLockEvent {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        lock_account: lock_acc.key(),
+        user: user_key,
+        amount,
+        start_time: lock_acc.start_time,
+        cliff_time: lock_acc.cliff_time,
+        end_time: lock_acc.end_time,
+        lock_id,
+    });
+    Ok(())
+}
+
+pub fn claim_vested(ctx: Context<ClaimVested>, lock_id: u64) -> Result<()> {
+    let lock_account = &mut ctx.accounts.lock_account;
+    require!(lock_account.kind == LockKind::Vesting, ErrorCode::NotVestingLock);
+    require!(!lock_account.withdrawn, ErrorCode::AlreadyWithdrawn);
+
+    let now = Clock::get()?.unix_timestamp;
+    let claimable = lock_account
+        .vested_amount(now)
+        .checked_sub(lock_account.claimed_amount)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    require!(claimable > 0, ErrorCode::NothingToClaim);
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.escrow_token_account.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: ctx.accounts.smart_wallet.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, claimable).map_err(|_| ErrorCode::InsufficientBalance)?;
+
+    lock_account.claimed_amount = lock_account
+        .claimed_amount
+        .checked_add(claimable)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    if lock_account.claimed_amount == lock_account.amount {
+        lock_account.withdrawn = true;
+        lock_account.withdrawn_at = now;
+    }
+
+    emit!(ClaimVestedEvent {
+        version: EVENT_VERSION,
+        governor: ctx.accounts.governor.key(),
+        lock_account: lock_account.key(),
         user: lock_account.user,
-        amount: lock_account.amount,
+        amount: claimable,
+        claimed_amount: lock_account.claimed_amount,
+        remaining_locked: lock_account.remaining_locked(now),
         lock_id,
+        claimed_at: now,
     });
     Ok(())
 }
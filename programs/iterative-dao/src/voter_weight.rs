@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::clock::Clock;
+use crate::{
+    enums::{VoterWeightAction, WeightSource},
+    errors::ErrorCode,
+    escrow::escrowed_voting_power,
+    state::{Governor, VoterWeightRecord},
+};
+
+/// Checks `voter` may vote: anyone may under NFT weighting, where holding a
+/// configured NFT is checked per vote; otherwise only listed voters.
+pub fn require_member(governor: &Governor, voter: &Pubkey) -> Result<()> {
+    require!(
+        governor.weight_source == WeightSource::Nft || governor.voters.iter().any(|vi| vi.pubkey == *voter),
+        ErrorCode::UnauthorisedVoter
+    );
+    Ok(())
+}
+
+/// Voting power of `voter` on `proposal` under the governor's weight source.
+///
+/// `declared` is the weight passed to `cast_vote`; `set_vote` passes `None`.
+/// `accounts` are the instruction's remaining accounts: `(locker, escrow)`
+/// pairs and lock accounts for the declared and locker sources, or the
+/// voter's `VoterWeightRecord` for an add-in.
+pub fn voting_power(
+    governor: &Account<Governor>,
+    voter: Pubkey,
+    proposal: Pubkey,
+    declared: Option<u64>,
+    accounts: &[AccountInfo],
+    clock: &Clock,
+) -> Result<u64> {
+    let registered = || -> Result<u64> {
+        governor
+            .voters
+            .iter()
+            .find(|vi| vi.pubkey == voter)
+            .map(|vi| vi.weight)
+            .ok_or_else(|| error!(ErrorCode::UnauthorisedVoter))
+    };
+    match governor.weight_source {
+        WeightSource::Declared => {
+            let base = match declared {
+                Some(weight) => weight,
+                None => registered()?,
+            };
+            let escrowed = escrowed_voting_power(governor.key(), voter, accounts, clock.unix_timestamp)?;
+            Ok(base.checked_add(escrowed).ok_or(ErrorCode::NumericalOverflow)?)
+        }
+        WeightSource::Registry => registered(),
+        WeightSource::Locker => escrowed_voting_power(governor.key(), voter, accounts, clock.unix_timestamp),
+        WeightSource::AddIn { program } => {
+            let [record] = accounts else {
+                return err!(ErrorCode::InvalidVoterWeightRecord);
+            };
+            add_in_weight(governor, &program, voter, proposal, record, clock.slot)
+        }
+        WeightSource::Nft => err!(ErrorCode::NftVoteRequired),
+    }
+}
+
+/// Reads an add-in's record, accepting it on the same terms SPL Governance does.
+fn add_in_weight(
+    governor: &Account<Governor>,
+    program: &Pubkey,
+    voter: Pubkey,
+    proposal: Pubkey,
+    info: &AccountInfo,
+    slot: u64,
+) -> Result<u64> {
+    require!(info.owner == program, ErrorCode::InvalidVoterWeightRecord);
+    let record = {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == VoterWeightRecord::DISCRIMINATOR,
+            ErrorCode::InvalidVoterWeightRecord
+        );
+        VoterWeightRecord::deserialize(&mut &data[8..])?
+    };
+    require!(
+        record.realm == governor.key()
+            && record.governing_token_mint == governor.governance_mint
+            && record.governing_token_owner == voter,
+        ErrorCode::InvalidVoterWeightRecord
+    );
+    require!(
+        !matches!(record.voter_weight_expiry, Some(expiry) if expiry < slot)
+            && !matches!(record.weight_action, Some(action) if action != VoterWeightAction::CastVote)
+            && !matches!(record.weight_action_target, Some(target) if target != proposal),
+        ErrorCode::StaleVoterWeightRecord
+    );
+    Ok(record.voter_weight)
+}
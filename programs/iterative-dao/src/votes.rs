@@ -1,146 +1,409 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{
+    allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+};
 use crate::{
-    contexts::{CreateVote, CastVote, SetVote},
+    contexts::{CreateVote, CastVote, CastVotes, SetVote},
     errors::ErrorCode,
-    events::{VoteCreateEvent, VoteSetEvent},
-    state::{Governor, Proposal, Vote},
-    enums::{VoteSide, VoteState, ProposalState},
+    events::{VoteCreateEvent, VoteSetEvent, VoteWithdrawn, VotingExtended, EVENT_VERSION},
+    enums::{OptionSelection, VoteMutability, VoteSide, VoteState, ProposalState, WeightSource},
+    proposals::provisional_outcome,
+    state::{BatchedVote, Governor, Proposal, Vote, VoteRationale},
+    voter_weight::{require_member, voting_power},
 };
 
 pub fn create_vote(ctx: Context<CreateVote>) -> Result<()> {
-    let governor = &ctx.accounts.governor;
-    let proposal = &ctx.accounts.proposal;
-    let vote = &mut ctx.accounts.vote;
-    let voter_key = ctx.accounts.voter.key();
+    initialise_vote(ctx.accounts)
+}
 
-    require!(
-        governor.voters.iter().any(|vi| vi.pubkey == voter_key),
-        ErrorCode::UnauthorisedVoter
-    );
+/// Creates the vote account and casts it in one instruction.
+pub fn create_and_cast_vote(
+    ctx: Context<CreateVote>,
+    side: VoteSide,
+    weight: u64,
+    rationale: Option<VoteRationale>,
+) -> Result<()> {
+    initialise_vote(ctx.accounts)?;
+    let accounts = ctx.accounts;
+    let vote_key = accounts.vote.key();
+    cast(
+        &accounts.governor,
+        &mut accounts.proposal,
+        vote_key,
+        &mut accounts.vote,
+        side,
+        weight,
+        rationale,
+        ctx.remaining_accounts,
+    )
+}
+
+fn initialise_vote(accounts: &mut CreateVote) -> Result<()> {
+    let governor = &accounts.governor;
+    let proposal = &accounts.proposal;
+    let voter_key = accounts.voter.key();
+
+    require_member(governor, &voter_key)?;
     require!(proposal.state == ProposalState::Active, ErrorCode::InvalidStateTransition);
 
-    vote.proposal = proposal.key();
-    vote.voter = voter_key;
-    vote.side = VoteSide::Abstain {};
-    vote.weight = 0;
-    vote.state = VoteState::Pending;
-    vote.padding = [0u8; 6];
+    accounts.vote.set_inner(Vote::new(governor.key(), proposal.key(), voter_key, accounts.payer.key()));
+    emit_vote_created(governor.key(), accounts.vote.key(), &accounts.vote)
+}
 
+fn emit_vote_created(governor: Pubkey, vote_key: Pubkey, vote: &Vote) -> Result<()> {
     emit!(VoteCreateEvent {
-        vote: vote.key(),
-        proposal: proposal.key(),
+        version: EVENT_VERSION,
+        governor,
+        vote: vote_key,
+        proposal: vote.proposal,
         voter: vote.voter,
         state: vote.state.clone(),
+        created_at: Clock::get()?.unix_timestamp,
     });
     Ok(())
 }
 
-pub fn cast_vote(ctx: Context<CastVote>, side: VoteSide, weight: u64) -> Result<()> {
+/// Creates `voter`'s vote account on `proposal` at `info` as `init` would,
+/// including when the address has already been sent lamports.
+pub(crate) fn create_vote_account<'info>(
+    info: &AccountInfo<'info>,
+    proposal: &Pubkey,
+    voter: &Pubkey,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let (address, bump) = Pubkey::find_program_address(&[b"vote", proposal.as_ref(), voter.as_ref()], &crate::ID);
+    require_keys_eq!(info.key(), address, ErrorCode::InvalidVoteAccount);
+
+    let bump = [bump];
+    let seeds: &[&[u8]] = &[b"vote", proposal.as_ref(), voter.as_ref(), &bump];
+    let signer = &[seeds];
+    let rent = Rent::get()?.minimum_balance(Vote::LEN);
+    if info.lamports() == 0 {
+        let accounts = CreateAccount { from: payer.clone(), to: info.clone() };
+        let cpi = CpiContext::new_with_signer(system_program.clone(), accounts, signer);
+        return create_account(cpi, rent, Vote::LEN as u64, &crate::ID);
+    }
+    let top_up = rent.saturating_sub(info.lamports());
+    if top_up > 0 {
+        let accounts = Transfer { from: payer.clone(), to: info.clone() };
+        transfer(CpiContext::new(system_program.clone(), accounts), top_up)?;
+    }
+    let accounts = Allocate { account_to_allocate: info.clone() };
+    allocate(CpiContext::new_with_signer(system_program.clone(), accounts, signer), Vote::LEN as u64)?;
+    let accounts = Assign { account_to_assign: info.clone() };
+    assign(CpiContext::new_with_signer(system_program.clone(), accounts, signer), &crate::ID)
+}
+
+/// Casts a pending vote, optionally with the voter's reasons for it.
+pub fn cast_vote(ctx: Context<CastVote>, side: VoteSide, weight: u64, rationale: Option<VoteRationale>) -> Result<()> {
+    let accounts = ctx.accounts;
+    let vote_key = accounts.vote.key();
+    cast(
+        &accounts.governor,
+        &mut accounts.proposal,
+        vote_key,
+        &mut accounts.vote,
+        side,
+        weight,
+        rationale,
+        ctx.remaining_accounts,
+    )
+}
+
+/// Casts `voter`'s votes on several proposals of the same governor.
+///
+/// Remaining accounts start with a `(proposal, vote)` pair per entry in
+/// `votes`; vote accounts that do not exist yet are created. Any accounts
+/// after the pairs are the voting-power accounts `cast_vote` takes, and
+/// count towards every vote in the batch.
+pub fn cast_votes<'info>(ctx: Context<'_, '_, 'info, 'info, CastVotes<'info>>, votes: Vec<BatchedVote>) -> Result<()> {
     let governor = &ctx.accounts.governor;
-    let proposal = &mut ctx.accounts.proposal;
-    let vote = &mut ctx.accounts.vote;
-    let voter_key = ctx.accounts.voter.key();
+    let voter = ctx.accounts.voter.key();
+    let payer = ctx.accounts.payer.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let pairs_len = votes.len().checked_mul(2).ok_or(ErrorCode::NumericalOverflow)?;
+    require!(ctx.remaining_accounts.len() >= pairs_len, ErrorCode::InvalidBatchAccounts);
+    let (pairs, weight_accounts) = ctx.remaining_accounts.split_at(pairs_len);
 
-    require!(
-        governor.voters.iter().any(|vi| vi.pubkey == voter_key),
-        ErrorCode::UnauthorisedVoter
-    );
+    for (batched, pair) in votes.into_iter().zip(pairs.chunks_exact(2)) {
+        let (proposal_info, vote_info) = (&pair[0], &pair[1]);
+        let mut proposal = Account::<Proposal>::try_from(proposal_info)?;
+        require_keys_eq!(proposal.governor, governor.key(), ErrorCode::InvalidBatchAccounts);
+
+        let mut vote = if vote_info.data_is_empty() && vote_info.owner == &System::id() {
+            create_vote_account(vote_info, &proposal.key(), &voter, &payer, &system_program)?;
+            let vote = Vote::new(governor.key(), proposal.key(), voter, payer.key());
+            emit_vote_created(governor.key(), vote_info.key(), &vote)?;
+            vote
+        } else {
+            let vote = Account::<Vote>::try_from(vote_info)?;
+            require!(vote.proposal == proposal.key() && vote.voter == voter, ErrorCode::InvalidBatchAccounts);
+            vote.into_inner()
+        };
+        cast(
+            governor,
+            &mut proposal,
+            vote_info.key(),
+            &mut vote,
+            batched.side,
+            batched.weight,
+            None,
+            weight_accounts,
+        )?;
+        vote.try_serialize(&mut &mut vote_info.try_borrow_mut_data()?[..])?;
+        proposal.exit(&crate::ID)?;
+    }
+    Ok(())
+}
+
+/// Casts a pending `vote` and adds its weight to the proposal's tally.
+#[allow(clippy::too_many_arguments)]
+fn cast(
+    governor: &Account<Governor>,
+    proposal: &mut Account<Proposal>,
+    vote_key: Pubkey,
+    vote: &mut Vote,
+    side: VoteSide,
+    weight: u64,
+    rationale: Option<VoteRationale>,
+    weight_accounts: &[AccountInfo],
+) -> Result<()> {
+    require_member(governor, &vote.voter)?;
     require!(proposal.state == ProposalState::Active, ErrorCode::InvalidStateTransition);
+    require!(!proposal.is_secret_ballot(), ErrorCode::SecretBallot);
     require!(vote.state == VoteState::Pending, ErrorCode::InvalidStateTransition);
+    require_valid_rationale(&rationale)?;
+
+    let clock = Clock::get()?;
+    require_voting_open(proposal, clock.unix_timestamp)?;
+    let power = voting_power(governor, vote.voter, proposal.key(), Some(weight), weight_accounts, &clock)?;
+    let weight = proposal.voting_strategy.votes(power);
 
     vote.side = side.clone();
+    vote.power = power;
     vote.weight = weight;
     vote.state = VoteState::Cast;
 
-    match side {
-        VoteSide::For {} => {
-            proposal.for_votes = proposal
-                .for_votes
-                .checked_add(weight)
-                .ok_or(ErrorCode::NumericalOverflow)?;
-        },
-        VoteSide::Against {} => {
-            proposal.against_votes = proposal
-                .against_votes
-                .checked_add(weight)
-                .ok_or(ErrorCode::NumericalOverflow)?;
-        },
-        VoteSide::Abstain {} => {
-            proposal.abstain_votes = proposal
-                .abstain_votes
-                .checked_add(weight)
-                .ok_or(ErrorCode::NumericalOverflow)?;
-        },
-    }
+    let before = provisional_outcome(proposal)?;
+    let tally = tally_for(proposal, &side)?;
+    *tally = tally.checked_add(weight).ok_or(ErrorCode::NumericalOverflow)?;
 
     emit!(VoteSetEvent {
-        vote: vote.key(),
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        vote: vote_key,
         proposal: proposal.key(),
-        voter: voter_key,
-        side: side.clone(),
+        voter: vote.voter,
+        side,
         weight,
+        voted_at: clock.unix_timestamp,
+        rationale,
     });
-    Ok(())
+    extend_for_late_vote(governor, proposal, before, clock.unix_timestamp)
 }
 
-pub fn set_vote(ctx: Context<SetVote>, new_side: VoteSide) -> Result<()> {
+pub fn set_vote(ctx: Context<SetVote>, new_side: VoteSide, rationale: Option<VoteRationale>) -> Result<()> {
     let governor = &ctx.accounts.governor;
     let proposal = &mut ctx.accounts.proposal;
     let vote = &mut ctx.accounts.vote;
     let voter_key = ctx.accounts.voter.key();
 
-    require!(
-        governor.voters.iter().any(|vi| vi.pubkey == voter_key),
-        ErrorCode::UnauthorisedVoter
-    );
+    require_member(governor, &voter_key)?;
     require!(proposal.state == ProposalState::Active, ErrorCode::InvalidStateTransition);
+    require!(!proposal.is_secret_ballot(), ErrorCode::SecretBallot);
+    require_valid_rationale(&rationale)?;
+    let now = Clock::get()?.unix_timestamp;
+    require_voting_open(proposal, now)?;
+    if vote.state == VoteState::Cast {
+        require_vote_changeable(governor, proposal, now)?;
+    }
 
-    let new_weight = governor
-        .voters
-        .iter()
-        .find(|vi| vi.pubkey == voter_key)
-        .ok_or(ErrorCode::UnauthorisedVoter)?
-        .weight;
+    // NFT votes keep the power their NFTs added; only the side moves.
+    let new_power = if governor.weight_source == WeightSource::Nft {
+        vote.power
+    } else {
+        voting_power(governor, voter_key, proposal.key(), None, ctx.remaining_accounts, &Clock::get()?)?
+    };
+    let new_weight = proposal.voting_strategy.votes(new_power);
 
     let old_side = vote.side.clone();
     let old_weight = vote.weight;
+    let before = provisional_outcome(proposal)?;
 
-    // Subtract old votes 
-    match old_side {
-        VoteSide::For {} => {
-            proposal.for_votes = proposal.for_votes.checked_sub(old_weight).ok_or(ErrorCode::NumericalOverflow)?;
-        },
-        VoteSide::Against {} => {
-            proposal.against_votes = proposal.against_votes.checked_sub(old_weight).ok_or(ErrorCode::NumericalOverflow)?;
-        },
-        VoteSide::Abstain {} => {
-            proposal.abstain_votes = proposal.abstain_votes.checked_sub(old_weight).ok_or(ErrorCode::NumericalOverflow)?;
-        },
-    }
+    // Subtract old votes
+    let old_tally = tally_for(proposal, &old_side)?;
+    *old_tally = old_tally.checked_sub(old_weight).ok_or(ErrorCode::NumericalOverflow)?;
 
-    // Add new votes 
-    match new_side.clone() {
-        VoteSide::For {} => {
-            proposal.for_votes = proposal.for_votes.checked_add(new_weight).ok_or(ErrorCode::NumericalOverflow)?;
-        },
-        VoteSide::Against {} => {
-            proposal.against_votes = proposal.against_votes.checked_add(new_weight).ok_or(ErrorCode::NumericalOverflow)?;
-        },
-        VoteSide::Abstain {} => {
-            proposal.abstain_votes = proposal.abstain_votes.checked_add(new_weight).ok_or(ErrorCode::NumericalOverflow)?;
-        },
-    }
+    // Add new votes
+    let new_tally = tally_for(proposal, &new_side)?;
+    *new_tally = new_tally.checked_add(new_weight).ok_or(ErrorCode::NumericalOverflow)?;
 
     vote.side = new_side.clone();
+    vote.power = new_power;
     vote.weight = new_weight;
     vote.state = VoteState::Cast;
 
     emit!(VoteSetEvent {
+        version: EVENT_VERSION,
+        governor: governor.key(),
         vote: vote.key(),
         proposal: proposal.key(),
         voter: voter_key,
         side: new_side,
         weight: vote.weight,
+        voted_at: now,
+        rationale,
     });
+    extend_for_late_vote(governor, proposal, before, now)
+}
+
+/// Checks `proposal` is past its review period and still taking votes at `now`.
+pub(crate) fn require_voting_open(proposal: &Proposal, now: i64) -> Result<()> {
+    let starts_at = proposal.voting_starts_at().ok_or(ErrorCode::NumericalOverflow)?;
+    require!(now >= starts_at, ErrorCode::VotingNotStarted);
+    let ends_at = proposal.voting_ends_at().ok_or(ErrorCode::NumericalOverflow)?;
+    require!(now < ends_at, ErrorCode::VotingPeriodEnded);
+    Ok(())
+}
+
+/// Checks the governor's `VoteMutability` lets a cast or committed vote on
+/// `proposal` be changed at `now`.
+pub(crate) fn require_vote_changeable(governor: &Governor, proposal: &Proposal, now: i64) -> Result<()> {
+    let cutoff = match governor.vote_mutability {
+        VoteMutability::Free => return Ok(()),
+        VoteMutability::Immutable => return err!(ErrorCode::VoteImmutable),
+        VoteMutability::UntilCutoff { cutoff } => cutoff,
+    };
+    require!(now < changeable_until(proposal, cutoff)?, ErrorCode::VoteChangeCutoffPassed);
     Ok(())
 }
+
+/// Whether votes already cast on `proposal` can no longer change at `now`.
+pub(crate) fn votes_are_final(governor: &Governor, proposal: &Proposal, now: i64) -> Result<bool> {
+    Ok(match governor.vote_mutability {
+        VoteMutability::Free => false,
+        VoteMutability::Immutable => true,
+        VoteMutability::UntilCutoff { cutoff } => now >= changeable_until(proposal, cutoff)?,
+    })
+}
+
+fn changeable_until(proposal: &Proposal, cutoff: i64) -> Result<i64> {
+    let until = proposal
+        .voting_ends_at()
+        .and_then(|ends_at| ends_at.checked_sub(cutoff))
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    Ok(until)
+}
+
+/// Extends voting on `proposal` under the governor's `LateVoteExtension`
+/// when a vote cast in the final window moved the outcome away from
+/// `before`, the `provisional_outcome` ahead of the vote.
+pub(crate) fn extend_for_late_vote(
+    governor: &Governor,
+    proposal: &mut Account<Proposal>,
+    before: (bool, Option<u8>),
+    now: i64,
+) -> Result<()> {
+    let Some(rule) = governor.late_vote_extension else { return Ok(()) };
+    let ends_at = proposal.voting_ends_at().ok_or(ErrorCode::NumericalOverflow)?;
+    let in_window = now < ends_at && now >= ends_at.saturating_sub(rule.window);
+    let extension = rule.extension.min(rule.max_extension - proposal.voting_extension);
+    if !in_window || extension <= 0 || provisional_outcome(proposal)? == before {
+        return Ok(());
+    }
+
+    let voting_ends_at = ends_at.checked_add(extension).ok_or(ErrorCode::NumericalOverflow)?;
+    proposal.voting_period = proposal.voting_period.checked_add(extension).ok_or(ErrorCode::NumericalOverflow)?;
+    proposal.voting_extension += extension;
+    emit!(VotingExtended {
+        version: EVENT_VERSION,
+        governor: proposal.governor,
+        proposal: proposal.key(),
+        proposal_id: proposal.proposal_id,
+        voting_period: proposal.voting_period,
+        voting_ends_at,
+        extended_at: now,
+    });
+    Ok(())
+}
+
+/// Takes a cast or committed vote back out of the count.
+///
+/// The vote returns to `Pending`, so the voter may vote again. Ranked ballots
+/// cannot be withdrawn, as instant-runoff rounds recount them from their
+/// rankings. NFTs behind a withdrawn NFT vote stay spent on the proposal:
+/// their records are kept, so they cannot back another vote on it.
+pub fn withdraw_vote(ctx: Context<SetVote>) -> Result<()> {
+    let governor = &ctx.accounts.governor;
+    let proposal = &mut ctx.accounts.proposal;
+    let vote = &mut ctx.accounts.vote;
+
+    require!(proposal.state == ProposalState::Active, ErrorCode::InvalidStateTransition);
+    require!(
+        vote.state == VoteState::Cast || vote.state == VoteState::Committed,
+        ErrorCode::InvalidStateTransition
+    );
+    require!(vote.ranking.is_empty(), ErrorCode::RankedVoteNotWithdrawable);
+    let now = Clock::get()?.unix_timestamp;
+    require_voting_open(proposal, now)?;
+    require_vote_changeable(governor, proposal, now)?;
+
+    // A commitment was never counted.
+    let before = provisional_outcome(proposal)?;
+    let weight = if vote.state == VoteState::Cast { vote.weight } else { 0 };
+    if vote.state == VoteState::Cast {
+        let tally = tally_for(proposal, &vote.side)?;
+        *tally = tally.checked_sub(weight).ok_or(ErrorCode::NumericalOverflow)?;
+    }
+
+    let side = vote.side.clone();
+    vote.side = VoteSide::Abstain {};
+    vote.weight = 0;
+    vote.power = 0;
+    vote.commitment = [0u8; 32];
+    vote.state = VoteState::Pending;
+
+    emit!(VoteWithdrawn {
+        version: EVENT_VERSION,
+        governor: governor.key(),
+        vote: vote.key(),
+        proposal: proposal.key(),
+        voter: vote.voter,
+        side,
+        weight,
+        withdrawn_at: now,
+    });
+    extend_for_late_vote(governor, proposal, before, now)
+}
+
+/// Checks a rationale given with a vote is within its limits.
+fn require_valid_rationale(rationale: &Option<VoteRationale>) -> Result<()> {
+    if let Some(rationale) = rationale {
+        require!(rationale.is_valid(), ErrorCode::InvalidVoteRationale);
+    }
+    Ok(())
+}
+
+/// The running total that votes on `side` count towards.
+///
+/// Yes/no proposals accept `For`, `Against` and `Abstain`; multiple-choice
+/// proposals accept `Choice` of an existing option and `Abstain`, except
+/// instant-runoff ones, which take ranked ballots through `cast_ranked_vote`.
+pub(crate) fn tally_for<'a>(proposal: &'a mut Proposal, side: &VoteSide) -> Result<&'a mut u64> {
+    match (side, proposal.is_multiple_choice()) {
+        (VoteSide::Abstain {}, _) => Ok(&mut proposal.abstain_votes),
+        (VoteSide::For {}, false) => Ok(&mut proposal.for_votes),
+        (VoteSide::Against {}, false) => Ok(&mut proposal.against_votes),
+        (VoteSide::Choice { index }, true)
+            if proposal.selection != OptionSelection::InstantRunoff =>
+        {
+            proposal
+                .options
+                .get_mut(*index as usize)
+                .map(|option| &mut option.votes)
+                .ok_or_else(|| error!(ErrorCode::InvalidVoteOption))
+        }
+        _ => err!(ErrorCode::InvalidVoteOption),
+    }
+}
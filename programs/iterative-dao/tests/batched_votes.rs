@@ -0,0 +1,154 @@
+//! Creating and casting votes together, and batches across proposals.
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorError, prelude::Pubkey};
+use common::{assert_error, ix, pda, Dao, TxResult};
+use iterative_dao::{
+    enums::{VoteSide, VoteState},
+    errors::ErrorCode,
+    events::{VoteCreateEvent, VoteSetEvent},
+    state::{BatchedVote, Vote},
+};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+};
+
+fn batched(side: VoteSide, weight: u64) -> BatchedVote {
+    BatchedVote { side, weight }
+}
+
+fn cast_votes(dao: &mut Dao, voter: &Keypair, votes: Vec<(Pubkey, BatchedVote)>) -> TxResult {
+    dao.send(ix::cast_votes(dao.wallet(), voter.pubkey(), dao.payer(), votes), &[voter])
+}
+
+#[test]
+fn create_and_cast_vote_in_one_instruction() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = dao.add_member(0);
+    let ix = ix::create_and_cast_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), VoteSide::For {}, 60, None);
+    dao.send(ix, &[&voter]).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).for_votes, 60);
+    let vote: Vote = dao.ctx.account(&pda::find_vote_address(&proposal, &voter.pubkey()).0);
+    assert!(vote.state == VoteState::Cast);
+    assert_eq!((vote.weight, vote.payer), (60, dao.payer()));
+    assert_eq!(dao.ctx.events::<VoteCreateEvent>().len(), 1);
+    assert_eq!(dao.ctx.events::<VoteSetEvent>()[0].weight, 60);
+}
+
+#[test]
+fn create_and_cast_vote_checks_like_cast_vote() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let outsider = Keypair::new();
+    let ix =
+        ix::create_and_cast_vote(dao.wallet(), proposal, outsider.pubkey(), dao.payer(), VoteSide::For {}, 60, None);
+    assert_error(dao.send(ix, &[&outsider]), ErrorCode::UnauthorisedVoter);
+
+    let voter = dao.add_member(0);
+    let choice = VoteSide::Choice { index: 0 };
+    let ix = ix::create_and_cast_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), choice, 60, None);
+    assert_error(dao.send(ix, &[&voter]), ErrorCode::InvalidVoteOption);
+    assert!(!dao.ctx.exists(&pda::find_vote_address(&proposal, &voter.pubkey()).0));
+}
+
+#[test]
+fn one_transaction_votes_on_several_proposals() {
+    let mut dao = Dao::new();
+    let proposals = [dao.active_proposal(3600), dao.active_proposal(3600), dao.active_proposal(3600)];
+    let voter = dao.add_member(0);
+    let votes = vec![
+        (proposals[0], batched(VoteSide::For {}, 10)),
+        (proposals[1], batched(VoteSide::Against {}, 20)),
+        (proposals[2], batched(VoteSide::Abstain {}, 30)),
+    ];
+    cast_votes(&mut dao, &voter, votes).unwrap();
+
+    assert_eq!(dao.proposal(&proposals[0]).for_votes, 10);
+    assert_eq!(dao.proposal(&proposals[1]).against_votes, 20);
+    assert_eq!(dao.proposal(&proposals[2]).abstain_votes, 30);
+    for proposal in &proposals {
+        let vote: Vote = dao.ctx.account(&pda::find_vote_address(proposal, &voter.pubkey()).0);
+        assert!(vote.state == VoteState::Cast);
+    }
+    assert_eq!(dao.ctx.events::<VoteCreateEvent>().len(), 3);
+    assert_eq!(dao.ctx.events::<VoteSetEvent>().len(), 3);
+}
+
+#[test]
+fn batch_casts_existing_pending_votes() {
+    let mut dao = Dao::new();
+    let (created, fresh) = (dao.active_proposal(3600), dao.active_proposal(3600));
+    let voter = dao.add_member(0);
+    dao.send(ix::create_vote(dao.wallet(), created, voter.pubkey(), dao.payer()), &[&voter]).unwrap();
+    let votes = vec![(created, batched(VoteSide::For {}, 10)), (fresh, batched(VoteSide::For {}, 10))];
+    cast_votes(&mut dao, &voter, votes).unwrap();
+
+    assert_eq!(dao.proposal(&created).for_votes, 10);
+    assert_eq!(dao.proposal(&fresh).for_votes, 10);
+    assert_eq!(dao.ctx.events::<VoteCreateEvent>().len(), 1);
+}
+
+#[test]
+fn batch_is_atomic() {
+    let mut dao = Dao::new();
+    let (open, voted) = (dao.active_proposal(3600), dao.active_proposal(3600));
+    let voter = dao.add_member(0);
+    dao.vote(voted, &voter, VoteSide::For {}, 10).unwrap();
+
+    let votes = vec![(open, batched(VoteSide::For {}, 10)), (voted, batched(VoteSide::Against {}, 10))];
+    assert_error(cast_votes(&mut dao, &voter, votes), ErrorCode::InvalidStateTransition);
+    assert_eq!(dao.proposal(&open).for_votes, 0);
+    assert!(!dao.ctx.exists(&pda::find_vote_address(&open, &voter.pubkey()).0));
+}
+
+#[test]
+fn same_proposal_twice_in_a_batch_fails() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = dao.add_member(0);
+    let votes = vec![(proposal, batched(VoteSide::For {}, 10)), (proposal, batched(VoteSide::For {}, 10))];
+
+    assert_error(cast_votes(&mut dao, &voter, votes), ErrorCode::InvalidStateTransition);
+}
+
+#[test]
+fn batch_accounts_must_pair_up() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = dao.add_member(0);
+    let other = dao.add_member(0);
+    dao.send(ix::create_vote(dao.wallet(), proposal, other.pubkey(), dao.payer()), &[&other]).unwrap();
+
+    let one_vote = vec![(proposal, batched(VoteSide::For {}, 1))];
+
+    // Another voter's vote account.
+    let mut batch = ix::cast_votes(dao.wallet(), voter.pubkey(), dao.payer(), one_vote.clone());
+    batch.accounts.last_mut().unwrap().pubkey = pda::find_vote_address(&proposal, &other.pubkey()).0;
+    assert_error(dao.send(batch.clone(), &[&voter]), ErrorCode::InvalidBatchAccounts);
+
+    // A pair missing its vote account.
+    batch.accounts.pop();
+    assert_error(dao.send(batch, &[&voter]), ErrorCode::InvalidBatchAccounts);
+
+    // An account that is not a proposal.
+    let not_proposal = Pubkey::new_unique();
+    dao.ctx.set_account(not_proposal, Account { lamports: 1, owner: iterative_dao::ID, ..Account::default() });
+    let mut batch = ix::cast_votes(dao.wallet(), voter.pubkey(), dao.payer(), one_vote);
+    batch.accounts[5].pubkey = not_proposal;
+    assert_error(dao.send(batch, &[&voter]), AnchorError::AccountDiscriminatorNotFound);
+}
+
+#[test]
+fn batch_rejects_secret_ballots() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(0);
+    let secret = dao.create_proposal(&proposer);
+    dao.send(ix::activate_secret_proposal(dao.wallet(), secret, Some(3600), 1800), &[]).unwrap();
+    let voter = dao.add_member(0);
+
+    assert_error(cast_votes(&mut dao, &voter, vec![(secret, batched(VoteSide::For {}, 1))]), ErrorCode::SecretBallot);
+}
@@ -0,0 +1,175 @@
+//! Reclaiming rent from terminal accounts.
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorError, prelude::Pubkey};
+use common::{assert_error, ix, pda, Dao};
+use iterative_dao::{
+    close::RETENTION_PERIOD,
+    enums::VoteSide,
+    errors::ErrorCode,
+    events::{EscrowClosed, LockClosed, ProposalClosed, VoteClosed},
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+/// A rejected proposal with one `Against` vote from `voter`.
+fn rejected_proposal(dao: &mut Dao) -> (Pubkey, Keypair) {
+    let voter = dao.add_member(100);
+    let proposal = dao.create_proposal(&voter);
+    dao.activate(proposal, 60).unwrap();
+    dao.vote(proposal, &voter, VoteSide::Against {}, 100).unwrap();
+    dao.ctx.warp(60);
+    dao.finalise(proposal).unwrap();
+    (proposal, voter)
+}
+
+#[test]
+fn close_proposal_refunds_payer_after_retention() {
+    let mut dao = Dao::new();
+    let (proposal, _) = rejected_proposal(&mut dao);
+    let rent = dao.ctx.lamports(&proposal);
+    let before = dao.ctx.lamports(&dao.payer());
+    dao.ctx.warp(RETENTION_PERIOD);
+
+    dao.send(ix::close_proposal(proposal, dao.payer()), &[]).unwrap();
+    assert!(!dao.ctx.exists(&proposal));
+    assert_eq!(dao.ctx.lamports(&dao.payer()), before + rent);
+    assert_eq!(dao.ctx.events::<ProposalClosed>()[0].proposal, proposal);
+}
+
+#[test]
+fn close_proposal_within_retention_fails() {
+    let mut dao = Dao::new();
+    let (proposal, _) = rejected_proposal(&mut dao);
+    dao.ctx.warp(RETENTION_PERIOD - 1);
+
+    assert_error(dao.send(ix::close_proposal(proposal, dao.payer()), &[]), ErrorCode::RetentionPeriodActive);
+}
+
+#[test]
+fn close_active_proposal_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+    dao.activate(proposal, 60).unwrap();
+    dao.ctx.warp(RETENTION_PERIOD);
+
+    assert_error(dao.send(ix::close_proposal(proposal, dao.payer()), &[]), ErrorCode::AccountNotTerminal);
+}
+
+#[test]
+fn close_proposal_to_other_recipient_fails() {
+    let mut dao = Dao::new();
+    let (proposal, voter) = rejected_proposal(&mut dao);
+    dao.ctx.warp(RETENTION_PERIOD);
+
+    let result = dao.send(ix::close_proposal(proposal, voter.pubkey()), &[]);
+    assert_error(result, AnchorError::ConstraintHasOne);
+}
+
+#[test]
+fn close_vote_after_proposal_retention() {
+    let mut dao = Dao::new();
+    let (proposal, voter) = rejected_proposal(&mut dao);
+    let vote = pda::find_vote_address(&proposal, &voter.pubkey()).0;
+
+    assert_error(
+        dao.send(ix::close_vote(vote, proposal, dao.payer()), &[]),
+        ErrorCode::RetentionPeriodActive,
+    );
+    dao.ctx.warp(RETENTION_PERIOD);
+    dao.send(ix::close_vote(vote, proposal, dao.payer()), &[]).unwrap();
+
+    assert!(!dao.ctx.exists(&vote));
+    assert_eq!(dao.ctx.events::<VoteClosed>()[0].voter, voter.pubkey());
+}
+
+#[test]
+fn close_vote_after_proposal_closed() {
+    let mut dao = Dao::new();
+    let (proposal, voter) = rejected_proposal(&mut dao);
+    dao.ctx.warp(RETENTION_PERIOD);
+    dao.send(ix::close_proposal(proposal, dao.payer()), &[]).unwrap();
+
+    let vote = pda::find_vote_address(&proposal, &voter.pubkey()).0;
+    dao.send(ix::close_vote(vote, proposal, dao.payer()), &[]).unwrap();
+    assert!(!dao.ctx.exists(&vote));
+    assert_eq!(dao.ctx.events::<VoteClosed>()[0].governor, dao.governor);
+}
+
+#[test]
+fn close_vote_on_active_proposal_fails() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(100);
+    let proposal = dao.create_proposal(&voter);
+    dao.activate(proposal, 60).unwrap();
+    dao.vote(proposal, &voter, VoteSide::For {}, 100).unwrap();
+
+    let vote = pda::find_vote_address(&proposal, &voter.pubkey()).0;
+    assert_error(dao.send(ix::close_vote(vote, proposal, dao.payer()), &[]), ErrorCode::AccountNotTerminal);
+}
+
+#[test]
+fn close_lock_account_after_withdrawal() {
+    let mut dao = Dao::new();
+    let user = dao.add_member(100);
+    let tokens = dao.fund_member(&user.pubkey(), 100);
+    let accs = dao.lock_accounts(&user.pubkey(), tokens);
+    dao.send(ix::lock_tokens(accs, dao.payer(), 100, 10, 1), &[&user]).unwrap();
+    let lock_account = pda::find_lock_address(&dao.governor, &user.pubkey(), 1).0;
+
+    assert_error(
+        dao.send(ix::close_lock_account(lock_account, dao.payer()), &[]),
+        ErrorCode::AccountNotTerminal,
+    );
+    dao.ctx.warp(10);
+    dao.send(ix::withdraw_tokens(accs, 1), &[&user]).unwrap();
+    dao.ctx.warp(RETENTION_PERIOD);
+    dao.send(ix::close_lock_account(lock_account, dao.payer()), &[]).unwrap();
+
+    assert!(!dao.ctx.exists(&lock_account));
+    let event = &dao.ctx.events::<LockClosed>()[0];
+    assert_eq!((event.governor, event.amount), (dao.governor, 100));
+}
+
+#[test]
+fn close_active_escrow_fails() {
+    let mut dao = Dao::new();
+    let mint = dao.governance_mint;
+    dao.send(ix::create_locker(dao.wallet(), mint, dao.payer(), 1, 0, 100), &[]).unwrap();
+    let locker = pda::find_locker_address(&dao.governor, &mint).0;
+    let user = dao.add_member(0);
+    let tokens = dao.fund_member(&user.pubkey(), 10);
+    let accs = dao.lock_accounts(&user.pubkey(), tokens);
+    dao.send(ix::create_escrow(accs, locker, dao.payer(), 10, 10, 1), &[&user]).unwrap();
+    dao.ctx.warp(RETENTION_PERIOD);
+
+    let escrow = pda::find_escrow_address(&locker, &user.pubkey(), 1).0;
+    assert_error(dao.send(ix::close_escrow(escrow, locker, dao.payer()), &[]), ErrorCode::AccountNotTerminal);
+}
+
+#[test]
+fn close_escrow_after_withdrawal() {
+    let mut dao = Dao::new();
+    let mint = dao.governance_mint;
+    dao.send(ix::create_locker(dao.wallet(), mint, dao.payer(), 1, 0, 100), &[]).unwrap();
+    let locker = pda::find_locker_address(&dao.governor, &mint).0;
+    let user = dao.add_member(0);
+    let tokens = dao.fund_member(&user.pubkey(), 10);
+    let accs = dao.lock_accounts(&user.pubkey(), tokens);
+    dao.send(ix::create_escrow(accs, locker, dao.payer(), 10, 10, 1), &[&user]).unwrap();
+    let escrow = pda::find_escrow_address(&locker, &user.pubkey(), 1).0;
+    dao.ctx.warp(10);
+    dao.send(ix::withdraw_escrow(accs, locker, 1), &[&user]).unwrap();
+
+    assert_error(dao.send(ix::close_escrow(escrow, locker, dao.payer()), &[]), ErrorCode::RetentionPeriodActive);
+    let rent = dao.ctx.lamports(&escrow);
+    let before = dao.ctx.lamports(&dao.payer());
+    dao.ctx.warp(RETENTION_PERIOD);
+    dao.send(ix::close_escrow(escrow, locker, dao.payer()), &[]).unwrap();
+
+    assert!(!dao.ctx.exists(&escrow));
+    assert_eq!(dao.ctx.lamports(&dao.payer()), before + rent);
+    let event = &dao.ctx.events::<EscrowClosed>()[0];
+    assert_eq!((event.governor, event.amount), (dao.governor, 10));
+}
@@ -0,0 +1,368 @@
+//! Synchronous wrapper around a `solana-program-test` bank.
+//!
+//! The program runs natively through `processor!`, so the bank applies the
+//! real runtime's account, rent, CPI and fee rules while tests keep a plain
+//! blocking API. Two gaps in program-test are filled here:
+//!
+//! - Native programs log events through the `sol_log_data` stub, which
+//!   program-test leaves printing to stdout. The stubs are wrapped so events
+//!   are captured per transaction instead.
+//! - Only the confirming send path verifies precompiles, so ed25519
+//!   instructions are checked before a transaction is submitted.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::sync::Once;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    entrypoint::ProgramResult,
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+};
+use anchor_lang::{Discriminator, Event};
+use solana_program_test::{processor, tokio::runtime::Runtime, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Slot,
+    feature_set::FeatureSet,
+    message::Message,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+/// Unix timestamp every context starts at.
+pub const GENESIS_TIMESTAMP: i64 = 1_700_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TxError {
+    /// A required signer was not among the transaction signers.
+    MissingSignature(Pubkey),
+    /// Instruction `index` of the transaction failed.
+    Program { index: usize, error: InstructionError },
+    /// The runtime rejected the transaction as a whole.
+    Transaction(TransactionError),
+}
+
+impl TxError {
+    /// Custom error code returned by the failing instruction, if any.
+    pub fn custom_code(&self) -> Option<u32> {
+        match self {
+            TxError::Program { error: InstructionError::Custom(code), .. } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+impl From<TransactionError> for TxError {
+    fn from(err: TransactionError) -> Self {
+        match err {
+            TransactionError::InstructionError(index, error) => TxError::Program { index: index as usize, error },
+            other => TxError::Transaction(other),
+        }
+    }
+}
+
+pub type TxResult = std::result::Result<(), TxError>;
+
+/// Asserts that `result` failed with the given Anchor or program error code.
+#[track_caller]
+pub fn assert_error(result: TxResult, code: impl Into<u32>) {
+    let code = code.into();
+    match result {
+        Ok(()) => panic!("expected error {code}, transaction succeeded"),
+        Err(err) => assert_eq!(err.custom_code(), Some(code), "unexpected error: {err:?}"),
+    }
+}
+
+thread_local! {
+    // Builtins run on the thread driving the bank, which is the test's own.
+    static EVENTS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Program-test's stubs, with `sol_log_data` captured into `EVENTS`.
+struct EventStubs(Box<dyn SyscallStubs>);
+
+impl SyscallStubs for EventStubs {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        EVENTS.with(|events| events.borrow_mut().extend(fields.iter().map(|field| field.to_vec())));
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0.sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
+
+/// Holds the stubs slot while program-test's stubs are being wrapped.
+struct Unset;
+
+impl SyscallStubs for Unset {}
+
+/// Adapts Anchor's entrypoint, which ties the account slice to the
+/// `AccountInfo` lifetime, to the signature `processor!` expects.
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    iterative_dao::entry(program_id, accounts, data)
+}
+
+/// A program-test bank with a warpable clock.
+pub struct TestContext {
+    /// Funds accounts the program creates. Fees are paid by the bank's mint
+    /// keypair so this balance only moves with rent.
+    pub payer: Keypair,
+    context: ProgramTestContext,
+    runtime: Runtime,
+    clock: Clock,
+    bank_slot: Slot,
+    logs: Vec<String>,
+    events: Vec<Vec<u8>>,
+}
+
+impl Default for TestContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestContext {
+    pub fn new() -> Self {
+        let runtime = solana_program_test::tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let mut program_test = ProgramTest::new("iterative_dao", iterative_dao::ID, processor!(process_instruction));
+        program_test.prefer_bpf(false);
+        let context = runtime.block_on(program_test.start_with_context());
+
+        // Program-test installs its stubs while starting, so they can be wrapped now.
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            let stubs = set_syscall_stubs(Box::new(Unset));
+            set_syscall_stubs(Box::new(EventStubs(stubs)));
+        });
+
+        let mut ctx = Self {
+            payer: Keypair::new(),
+            context,
+            runtime,
+            clock: Clock { unix_timestamp: GENESIS_TIMESTAMP, ..Clock::default() },
+            bank_slot: 1,
+            logs: Vec::new(),
+            events: Vec::new(),
+        };
+        ctx.airdrop(&ctx.payer.pubkey(), 1_000_000_000_000);
+        ctx
+    }
+
+    pub fn now(&self) -> i64 {
+        self.clock.unix_timestamp
+    }
+
+    pub fn slot(&self) -> u64 {
+        self.clock.slot
+    }
+
+    /// Moves the clock forward by `seconds`.
+    pub fn warp(&mut self, seconds: i64) {
+        self.clock.unix_timestamp += seconds;
+        self.clock.slot += (seconds.max(0) as u64).saturating_mul(2);
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, account: Account) {
+        self.context.set_account(&key, &account.into());
+    }
+
+    pub fn raw_account(&self, key: &Pubkey) -> Option<Account> {
+        let mut banks_client = self.context.banks_client.clone();
+        self.runtime.block_on(banks_client.get_account(*key)).expect("banks client")
+    }
+
+    pub fn exists(&self, key: &Pubkey) -> bool {
+        self.raw_account(key).is_some()
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.raw_account(key).map_or(0, |a| a.lamports)
+    }
+
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        let mut account = self.raw_account(key).unwrap_or_default();
+        account.lamports += lamports;
+        self.set_account(*key, account);
+    }
+
+    /// Deserializes an Anchor account, or `None` if it does not exist.
+    pub fn try_account<T: AccountDeserialize>(&self, key: &Pubkey) -> Option<T> {
+        let account = self.raw_account(key)?;
+        T::try_deserialize(&mut account.data.as_slice()).ok()
+    }
+
+    #[track_caller]
+    pub fn account<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        self.try_account(key).unwrap_or_else(|| panic!("account {key} missing or invalid"))
+    }
+
+    /// Log lines from the most recent transaction.
+    pub fn logs(&self) -> &[String] {
+        &self.logs
+    }
+
+    /// Events of type `E` emitted by the most recent transaction.
+    pub fn events<E: Event + Discriminator>(&self) -> Vec<E> {
+        self.events
+            .iter()
+            .filter(|data| data.len() >= 8 && data[..8] == E::DISCRIMINATOR)
+            .filter_map(|data| E::deserialize(&mut &data[8..]).ok())
+            .collect()
+    }
+
+    pub fn create_mint(&mut self, authority: &Pubkey, decimals: u8) -> Pubkey {
+        let key = Pubkey::new_unique();
+        let mint = spl_token::state::Mint {
+            mint_authority: Some(*authority).into(),
+            supply: 0,
+            decimals,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        };
+        let mut data = vec![0u8; spl_token::state::Mint::LEN];
+        spl_token::state::Mint::pack(mint, &mut data).unwrap();
+        self.set_token_program_account(key, data);
+        key
+    }
+
+    pub fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let key = Pubkey::new_unique();
+        let account = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            state: spl_token::state::AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        };
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        spl_token::state::Account::pack(account, &mut data).unwrap();
+        self.set_token_program_account(key, data);
+        key
+    }
+
+    /// Credits `amount` tokens to `token_account`, bumping the mint supply.
+    pub fn mint_to(&mut self, token_account: &Pubkey, amount: u64) {
+        let mut account = self.token_account(token_account);
+        account.amount += amount;
+        let mint_key = account.mint;
+        self.pack_into(token_account, account);
+
+        let mut mint = spl_token::state::Mint::unpack(&self.raw_account(&mint_key).unwrap().data).unwrap();
+        mint.supply += amount;
+        self.pack_into(&mint_key, mint);
+    }
+
+    #[track_caller]
+    pub fn token_account(&self, key: &Pubkey) -> spl_token::state::Account {
+        let account = self.raw_account(key).unwrap_or_else(|| panic!("token account {key} missing"));
+        spl_token::state::Account::unpack(&account.data).unwrap()
+    }
+
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        self.token_account(key).amount
+    }
+
+    fn pack_into<T: Pack>(&mut self, key: &Pubkey, state: T) {
+        let mut account = self.raw_account(key).unwrap();
+        T::pack(state, &mut account.data).unwrap();
+        self.set_account(*key, account);
+    }
+
+    fn set_token_program_account(&mut self, key: Pubkey, data: Vec<u8>) {
+        let lamports = Rent::default().minimum_balance(data.len());
+        self.set_account(key, Account { lamports, data, owner: spl_token::ID, ..Account::default() });
+    }
+
+    /// Processes `instructions` atomically, signed by the payer and `signers`.
+    pub fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> TxResult {
+        let fee_payer = self.context.payer.insecure_clone();
+        let payer = self.payer.insecure_clone();
+        let mut keypairs = vec![&fee_payer, &payer];
+        keypairs.extend_from_slice(signers);
+        for meta in instructions.iter().flat_map(|ix| ix.accounts.iter()) {
+            if meta.is_signer && !keypairs.iter().any(|keypair| keypair.pubkey() == meta.pubkey) {
+                return Err(TxError::MissingSignature(meta.pubkey));
+            }
+        }
+
+        let message = Message::new(instructions, Some(&fee_payer.pubkey()));
+        let required = &message.account_keys[..message.header.num_required_signatures as usize];
+        let mut signed = HashSet::new();
+        keypairs.retain(|keypair| required.contains(&keypair.pubkey()) && signed.insert(keypair.pubkey()));
+
+        loop {
+            let transaction = Transaction::new(&keypairs, message.clone(), self.context.last_blockhash);
+            transaction.verify_precompiles(&FeatureSet::all_enabled()).map_err(TxError::Transaction)?;
+
+            self.context.set_sysvar(&self.clock);
+            EVENTS.with(|events| events.borrow_mut().clear());
+            let outcome = self
+                .runtime
+                .block_on(self.context.banks_client.process_transaction_with_metadata(transaction))
+                .expect("banks client");
+            if outcome.result == Err(TransactionError::AlreadyProcessed) {
+                // Resending an identical transaction: move to a slot with a fresh blockhash.
+                self.bank_slot += 1;
+                self.context.warp_to_slot(self.bank_slot).unwrap();
+                continue;
+            }
+
+            self.logs = outcome.metadata.map(|metadata| metadata.log_messages).unwrap_or_default();
+            self.events = EVENTS.with(|events| events.take());
+            return outcome.result.map_err(TxError::from);
+        }
+    }
+
+    /// Shorthand for a single-instruction transaction.
+    pub fn send(&mut self, instruction: Instruction, signers: &[&Keypair]) -> TxResult {
+        self.process(&[instruction], signers)
+    }
+}
@@ -0,0 +1,340 @@
+//! Shared harness for the integration tests.
+//!
+//! Each test binary pulls this in with `mod common;` and only uses part of it.
+#![allow(dead_code, unused_imports)]
+
+pub mod context;
+
+use anchor_lang::prelude::*;
+use iterative_dao::{
+    enums::{OptionSelection, VoteMutability, VoteSide, VotingStrategy, WeightSource},
+    state::{
+        LateVoteExtension, NewProposalOption, ProposalAccount, ProposalCategoryConfig, ProposalInstruction,
+        VotingSchedule,
+    },
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+pub use iterative_dao_client::{instructions as ix, pda};
+pub use context::{assert_error, TestContext, TxError, TxResult};
+
+/// A governor initialised by a fresh smart wallet, plus its token plumbing.
+pub struct Dao {
+    pub ctx: TestContext,
+    pub smart_wallet: Keypair,
+    pub electorate: Keypair,
+    pub governor: Pubkey,
+    pub governance_mint: Pubkey,
+    /// Vault for locked governance tokens, owned by the smart wallet.
+    pub escrow_token_account: Pubkey,
+}
+
+impl Dao {
+    /// Initialises a governor with a 60% threshold and one hour timelock.
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    /// A governor with settings beyond the defaults of [`Dao::new`].
+    pub fn builder() -> DaoBuilder {
+        DaoBuilder {
+            vote_threshold: 60,
+            timelock_delay: 3600,
+            voting_strategy: None,
+            weight_source: None,
+            vote_mutability: None,
+            late_vote_extension: None,
+            voting_schedule: None,
+            nft_collections: Vec::new(),
+            categories: Vec::new(),
+        }
+    }
+
+    /// Everything except the governor account itself.
+    pub fn uninitialised() -> Self {
+        let mut ctx = TestContext::new();
+        let smart_wallet = Keypair::new();
+        let electorate = Keypair::new();
+        let governance_mint = ctx.create_mint(&smart_wallet.pubkey(), 0);
+        let escrow_token_account = ctx.create_token_account(&governance_mint, &smart_wallet.pubkey());
+        Self {
+            governor: pda::find_governor_address(&smart_wallet.pubkey()).0,
+            ctx,
+            smart_wallet,
+            electorate,
+            governance_mint,
+            escrow_token_account,
+        }
+    }
+
+    pub fn wallet(&self) -> Pubkey {
+        self.smart_wallet.pubkey()
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.ctx.payer.pubkey()
+    }
+
+    /// Sends `ix` signed by the payer, the smart wallet and `signers`.
+    pub fn send(&mut self, ix: anchor_lang::solana_program::instruction::Instruction, signers: &[&Keypair]) -> TxResult {
+        let wallet = self.smart_wallet.insecure_clone();
+        let mut all: Vec<&Keypair> = vec![&wallet];
+        all.extend_from_slice(signers);
+        self.ctx.send(ix, &all)
+    }
+
+    pub fn governor(&self) -> iterative_dao::state::Governor {
+        self.ctx.account(&self.governor)
+    }
+
+    pub fn proposal(&self, key: &Pubkey) -> iterative_dao::state::Proposal {
+        self.ctx.account(key)
+    }
+
+    /// Registers a new member with `weight` and returns their keypair.
+    pub fn add_member(&mut self, weight: u64) -> Keypair {
+        let member = Keypair::new();
+        self.ctx.airdrop(&member.pubkey(), 1_000_000_000);
+        self.send(ix::add_voter(self.wallet(), member.pubkey(), weight), &[])
+            .expect("add_voter");
+        member
+    }
+
+    /// Registers one member per entry of `weights`.
+    pub fn add_members(&mut self, weights: &[u64]) -> Vec<Keypair> {
+        weights.iter().map(|&weight| self.add_member(weight)).collect()
+    }
+
+    /// Creates a draft proposal from `proposer` and returns its address.
+    pub fn create_proposal(&mut self, proposer: &Keypair) -> Pubkey {
+        self.try_create_proposal(proposer, vec![mock_instruction()])
+            .expect("create_proposal")
+    }
+
+    pub fn try_create_proposal(
+        &mut self,
+        proposer: &Keypair,
+        instructions: Vec<ProposalInstruction>,
+    ) -> std::result::Result<Pubkey, TxError> {
+        self.try_create_proposal_in(proposer, None, instructions)
+    }
+
+    /// Creates a draft proposal in `category`, or in none.
+    pub fn try_create_proposal_in(
+        &mut self,
+        proposer: &Keypair,
+        category: Option<u8>,
+        instructions: Vec<ProposalInstruction>,
+    ) -> std::result::Result<Pubkey, TxError> {
+        let (proposal_id, payer) = (self.governor().proposal_count, self.payer());
+        let ix = ix::create_proposal(self.governor, proposal_id, proposer.pubkey(), payer, category, instructions);
+        self.send(ix, &[proposer])?;
+        Ok(pda::find_proposal_address(&self.governor, proposal_id).0)
+    }
+
+    pub fn try_create_multiple_choice_proposal(
+        &mut self,
+        proposer: &Keypair,
+        options: Vec<NewProposalOption>,
+        selection: OptionSelection,
+    ) -> std::result::Result<Pubkey, TxError> {
+        let proposal_id = self.governor().proposal_count;
+        let ix = ix::create_multiple_choice_proposal(
+            self.governor,
+            proposal_id,
+            proposer.pubkey(),
+            self.payer(),
+            None,
+            options,
+            selection,
+        );
+        self.send(ix, &[proposer])?;
+        Ok(pda::find_proposal_address(&self.governor, proposal_id).0)
+    }
+
+    pub fn activate(&mut self, proposal: Pubkey, voting_period: i64) -> TxResult {
+        self.send(ix::activate_proposal(self.wallet(), proposal, Some(voting_period)), &[])
+    }
+
+    /// Creates a proposal from a new zero-weight member and activates it.
+    pub fn active_proposal(&mut self, voting_period: i64) -> Pubkey {
+        self.active_proposal_in(None, voting_period)
+    }
+
+    /// Like [`Dao::active_proposal`], for a proposal in `category`.
+    pub fn active_proposal_in(&mut self, category: Option<u8>, voting_period: i64) -> Pubkey {
+        let proposer = self.add_member(0);
+        let proposal = self
+            .try_create_proposal_in(&proposer, category, vec![mock_instruction()])
+            .expect("create_proposal");
+        self.activate(proposal, voting_period).expect("activate");
+        proposal
+    }
+
+    /// Creates and casts `voter`'s vote on `proposal`.
+    pub fn vote(&mut self, proposal: Pubkey, voter: &Keypair, side: VoteSide, weight: u64) -> TxResult {
+        self.send(ix::create_vote(self.wallet(), proposal, voter.pubkey(), self.payer()), &[voter])?;
+        self.send(
+            ix::cast_vote(self.wallet(), proposal, voter.pubkey(), self.payer(), side, weight, None),
+            &[voter],
+        )
+    }
+
+    /// Moves `voter`'s cast vote on `proposal` to `side`.
+    pub fn change(&mut self, proposal: Pubkey, voter: &Keypair, side: VoteSide) -> TxResult {
+        self.send(ix::set_vote(self.wallet(), proposal, voter.pubkey(), self.payer(), side, None), &[voter])
+    }
+
+    pub fn finalise(&mut self, proposal: Pubkey) -> TxResult {
+        self.send(ix::finalise_proposal(self.wallet(), proposal), &[])
+    }
+
+    pub fn queue(&mut self, proposal: Pubkey) -> TxResult {
+        self.send(ix::queue_proposal(self.wallet(), proposal), &[])
+    }
+
+    pub fn execute(&mut self, proposal: Pubkey) -> TxResult {
+        self.send(ix::execute_proposal(self.wallet(), proposal), &[])
+    }
+
+    /// Drives a proposal from creation to `Succeeded` with a single yes vote.
+    pub fn passed_proposal(&mut self, proposer: &Keypair, voting_period: i64) -> Pubkey {
+        let proposal = self.create_proposal(proposer);
+        self.activate(proposal, voting_period).expect("activate");
+        self.vote(proposal, proposer, VoteSide::For {}, 100).expect("vote");
+        self.ctx.warp(voting_period);
+        self.finalise(proposal).expect("finalise");
+        proposal
+    }
+
+    /// Creates a funded governance-token account for `owner`.
+    pub fn fund_member(&mut self, owner: &Pubkey, amount: u64) -> Pubkey {
+        let account = self.ctx.create_token_account(&self.governance_mint, owner);
+        self.ctx.mint_to(&account, amount);
+        account
+    }
+
+    pub fn lock_accounts(&self, user: &Pubkey, user_token_account: Pubkey) -> ix::LockAccounts {
+        ix::LockAccounts {
+            smart_wallet: self.wallet(),
+            user: *user,
+            user_token_account,
+            escrow_token_account: self.escrow_token_account,
+        }
+    }
+}
+
+impl Default for Dao {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Governor settings applied by [`DaoBuilder::build`], in the order listed.
+pub struct DaoBuilder {
+    vote_threshold: u8,
+    timelock_delay: i64,
+    voting_strategy: Option<VotingStrategy>,
+    weight_source: Option<WeightSource>,
+    vote_mutability: Option<VoteMutability>,
+    late_vote_extension: Option<LateVoteExtension>,
+    voting_schedule: Option<VotingSchedule>,
+    nft_collections: Vec<(Pubkey, u64)>,
+    categories: Vec<(u8, ProposalCategoryConfig)>,
+}
+
+impl DaoBuilder {
+    pub fn vote_threshold(mut self, vote_threshold: u8) -> Self {
+        self.vote_threshold = vote_threshold;
+        self
+    }
+
+    pub fn timelock_delay(mut self, timelock_delay: i64) -> Self {
+        self.timelock_delay = timelock_delay;
+        self
+    }
+
+    pub fn strategy(mut self, voting_strategy: VotingStrategy) -> Self {
+        self.voting_strategy = Some(voting_strategy);
+        self
+    }
+
+    pub fn weight_source(mut self, weight_source: WeightSource) -> Self {
+        self.weight_source = Some(weight_source);
+        self
+    }
+
+    pub fn vote_mutability(mut self, vote_mutability: VoteMutability) -> Self {
+        self.vote_mutability = Some(vote_mutability);
+        self
+    }
+
+    pub fn late_vote_extension(mut self, rule: LateVoteExtension) -> Self {
+        self.late_vote_extension = Some(rule);
+        self
+    }
+
+    pub fn schedule(mut self, voting_schedule: VotingSchedule) -> Self {
+        self.voting_schedule = Some(voting_schedule);
+        self
+    }
+
+    /// Counts NFTs of `collection` for `weight` votes each.
+    pub fn nft_collection(mut self, collection: Pubkey, weight: u64) -> Self {
+        self.nft_collections.push((collection, weight));
+        self
+    }
+
+    /// Configures category `id`; categories are configured in the order given.
+    pub fn category(mut self, id: u8, config: ProposalCategoryConfig) -> Self {
+        self.categories.push((id, config));
+        self
+    }
+
+    pub fn build(self) -> Dao {
+        let mut dao = Dao::uninitialised();
+        let (wallet, payer) = (dao.wallet(), dao.payer());
+        let init = ix::init_governor(
+            wallet,
+            payer,
+            self.vote_threshold,
+            self.timelock_delay,
+            dao.electorate.pubkey(),
+            dao.governance_mint,
+        );
+        dao.send(init, &[]).expect("init_governor");
+        if let Some(voting_strategy) = self.voting_strategy {
+            dao.send(ix::set_voting_strategy(wallet, voting_strategy), &[]).expect("set_voting_strategy");
+        }
+        if let Some(weight_source) = self.weight_source {
+            dao.send(ix::set_weight_source(wallet, weight_source), &[]).expect("set_weight_source");
+        }
+        if let Some(vote_mutability) = self.vote_mutability {
+            dao.send(ix::set_vote_mutability(wallet, vote_mutability), &[]).expect("set_vote_mutability");
+        }
+        if let Some(rule) = self.late_vote_extension {
+            dao.send(ix::set_late_vote_extension(wallet, Some(rule)), &[]).expect("set_late_vote_extension");
+        }
+        if let Some(voting_schedule) = self.voting_schedule {
+            dao.send(ix::set_voting_schedule(wallet, voting_schedule), &[]).expect("set_voting_schedule");
+        }
+        for (collection, weight) in self.nft_collections {
+            let configure = ix::configure_nft_collection(wallet, payer, collection, weight);
+            dao.send(configure, &[]).expect("configure_nft_collection");
+        }
+        for (id, config) in self.categories {
+            let configure = ix::configure_proposal_category(wallet, payer, id, config);
+            dao.send(configure, &[]).expect("configure_proposal_category");
+        }
+        dao
+    }
+}
+
+/// A harmless single instruction for proposals whose payload does not matter.
+pub fn mock_instruction() -> ProposalInstruction {
+    ProposalInstruction {
+        program_id: anchor_lang::solana_program::system_program::ID,
+        accounts: vec![ProposalAccount { pubkey: Pubkey::new_unique(), is_signer: false, is_writable: true }],
+        data: vec![1, 2, 3],
+    }
+}
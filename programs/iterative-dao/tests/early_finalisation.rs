@@ -0,0 +1,148 @@
+//! Finalising proposals before voting ends once the outcome is decided.
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorError, prelude::Pubkey};
+use common::{assert_error, ix, Dao, TxResult};
+use iterative_dao::{
+    enums::{ProposalState, VoteMutability, VoteSide, VotingStrategy, WeightSource},
+    errors::ErrorCode,
+    events::ProposalFinalised,
+};
+
+const VOTING: i64 = 3600;
+
+fn finalise_early(dao: &mut Dao, proposal: Pubkey) -> TxResult {
+    dao.send(ix::finalise_proposal_early(dao.wallet(), proposal), &[])
+}
+
+#[test]
+fn decided_support_finalises_early() {
+    let mut dao = Dao::builder()
+        .weight_source(WeightSource::Registry)
+        .vote_mutability(VoteMutability::Immutable)
+        .build();
+    let voters = dao.add_members(&[70, 20, 10]);
+    let proposal = dao.active_proposal(VOTING);
+    dao.vote(proposal, &voters[0], VoteSide::For {}, 0).unwrap();
+    dao.ctx.warp(60);
+    finalise_early(&mut dao, proposal).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert!(account.state == ProposalState::Succeeded);
+    let event = &dao.ctx.events::<ProposalFinalised>()[0];
+    assert_eq!((event.for_votes, event.finalised_at), (70, account.activated_at + 60));
+    assert_error(dao.vote(proposal, &voters[1], VoteSide::Against {}, 0), AnchorError::ConstraintRaw);
+    dao.queue(proposal).unwrap();
+}
+
+#[test]
+fn decided_opposition_finalises_early() {
+    let mut dao = Dao::builder()
+        .weight_source(WeightSource::Registry)
+        .vote_mutability(VoteMutability::Immutable)
+        .build();
+    let voters = dao.add_members(&[50, 40, 10]);
+    let proposal = dao.active_proposal(VOTING);
+    dao.vote(proposal, &voters[0], VoteSide::Against {}, 0).unwrap();
+    finalise_early(&mut dao, proposal).unwrap();
+
+    assert!(dao.proposal(&proposal).state == ProposalState::Rejected);
+}
+
+#[test]
+fn abstentions_count_against_the_threshold() {
+    let mut dao = Dao::builder()
+        .weight_source(WeightSource::Registry)
+        .vote_mutability(VoteMutability::Immutable)
+        .build();
+    let voters = dao.add_members(&[55, 45]);
+    let proposal = dao.active_proposal(VOTING);
+    dao.vote(proposal, &voters[1], VoteSide::Abstain {}, 0).unwrap();
+    finalise_early(&mut dao, proposal).unwrap();
+
+    assert!(dao.proposal(&proposal).state == ProposalState::Rejected);
+}
+
+#[test]
+fn undecided_outcome_must_wait() {
+    let mut dao = Dao::builder()
+        .weight_source(WeightSource::Registry)
+        .vote_mutability(VoteMutability::Immutable)
+        .build();
+    let voters = dao.add_members(&[50, 50]);
+    let proposal = dao.active_proposal(VOTING);
+    dao.vote(proposal, &voters[0], VoteSide::For {}, 0).unwrap();
+
+    assert_error(finalise_early(&mut dao, proposal), ErrorCode::OutcomeNotDecided);
+    dao.vote(proposal, &voters[1], VoteSide::For {}, 0).unwrap();
+    finalise_early(&mut dao, proposal).unwrap();
+    assert!(dao.proposal(&proposal).state == ProposalState::Succeeded);
+}
+
+#[test]
+fn quadratic_votes_bound_the_remaining_weight() {
+    let mut dao = Dao::builder()
+        .strategy(VotingStrategy::Quadratic)
+        .weight_source(WeightSource::Registry)
+        .vote_mutability(VoteMutability::Immutable)
+        .build();
+    let voters = dao.add_members(&[100, 16, 9]);
+    let proposal = dao.active_proposal(VOTING);
+    dao.vote(proposal, &voters[0], VoteSide::For {}, 0).unwrap();
+    // 10 for with at most 7 to come: 10 / 17 is 58%, short of 60%.
+    assert_error(finalise_early(&mut dao, proposal), ErrorCode::OutcomeNotDecided);
+
+    dao.vote(proposal, &voters[2], VoteSide::For {}, 0).unwrap();
+    finalise_early(&mut dao, proposal).unwrap();
+    assert_eq!(dao.proposal(&proposal).for_votes, 13);
+}
+
+#[test]
+fn changeable_votes_keep_the_outcome_open() {
+    let mut dao = Dao::builder().weight_source(WeightSource::Registry).build();
+    let voters = dao.add_members(&[90, 10]);
+    let proposal = dao.active_proposal(VOTING);
+    dao.vote(proposal, &voters[0], VoteSide::For {}, 0).unwrap();
+    dao.vote(proposal, &voters[1], VoteSide::For {}, 0).unwrap();
+    assert_error(finalise_early(&mut dao, proposal), ErrorCode::OutcomeNotDecided);
+
+    let cutoff = VoteMutability::UntilCutoff { cutoff: 600 };
+    dao.send(ix::set_vote_mutability(dao.wallet(), cutoff), &[]).unwrap();
+    assert_error(finalise_early(&mut dao, proposal), ErrorCode::OutcomeNotDecided);
+    dao.ctx.warp(VOTING - 600);
+    finalise_early(&mut dao, proposal).unwrap();
+}
+
+#[test]
+fn unbounded_or_hidden_tallies_are_unsupported() {
+    let mut dao = Dao::builder()
+        .weight_source(WeightSource::Registry)
+        .vote_mutability(VoteMutability::Immutable)
+        .build();
+    let voters = dao.add_members(&[100]);
+    let proposer = dao.add_member(0);
+    let secret = dao.create_proposal(&proposer);
+    dao.send(ix::activate_secret_proposal(dao.wallet(), secret, Some(VOTING), 1800), &[]).unwrap();
+    assert_error(finalise_early(&mut dao, secret), ErrorCode::EarlyFinalisationUnsupported);
+
+    let proposal = dao.active_proposal(VOTING);
+    dao.vote(proposal, &voters[0], VoteSide::For {}, 0).unwrap();
+    dao.send(ix::set_weight_source(dao.wallet(), WeightSource::Declared), &[]).unwrap();
+    assert_error(finalise_early(&mut dao, proposal), ErrorCode::EarlyFinalisationUnsupported);
+}
+
+#[test]
+fn tallies_beyond_the_registry_are_not_trusted() {
+    let mut dao = Dao::builder()
+        .weight_source(WeightSource::Registry)
+        .vote_mutability(VoteMutability::Immutable)
+        .build();
+    let voters = dao.add_members(&[10, 10]);
+    dao.send(ix::set_weight_source(dao.wallet(), WeightSource::Declared), &[]).unwrap();
+    let proposal = dao.active_proposal(VOTING);
+    dao.vote(proposal, &voters[0], VoteSide::Against {}, 1_000).unwrap();
+    dao.send(ix::set_weight_source(dao.wallet(), WeightSource::Registry), &[]).unwrap();
+
+    assert_error(finalise_early(&mut dao, proposal), ErrorCode::OutcomeNotDecided);
+}
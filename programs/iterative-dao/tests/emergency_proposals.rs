@@ -0,0 +1,161 @@
+//! Fast-tracking emergency proposals past the rest of their voting period.
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorError, prelude::Pubkey, solana_program::system_program};
+use common::{assert_error, ix, mock_instruction, pda, Dao, TxResult};
+use iterative_dao::{
+    enums::{OptionSelection, ProposalState, VoteSide},
+    errors::ErrorCode,
+    events::ProposalFinalised,
+    state::{NewProposalOption, ProposalCategoryConfig},
+};
+use solana_sdk::signature::Signer;
+
+const EMERGENCY: u8 = 0;
+const ROUTINE: u8 = 1;
+const VOTING: i64 = 86_400;
+
+/// 80% of at least 100 votes, voted on for up to a day, with no timelock.
+fn emergency() -> ProposalCategoryConfig {
+    ProposalCategoryConfig {
+        name: "emergency".to_string(),
+        vote_threshold: 80,
+        quorum: 100,
+        min_voting_period: 600,
+        max_voting_period: VOTING,
+        timelock_delay: 0,
+        programs: vec![system_program::ID],
+        emergency: true,
+    }
+}
+
+fn configure(dao: &mut Dao, id: u8, config: ProposalCategoryConfig) -> TxResult {
+    dao.send(ix::configure_proposal_category(dao.wallet(), dao.payer(), id, config), &[])
+}
+
+/// The same terms as [`emergency`], without the fast track.
+fn routine() -> ProposalCategoryConfig {
+    ProposalCategoryConfig { name: "routine".to_string(), emergency: false, ..emergency() }
+}
+
+fn vote(dao: &mut Dao, proposal: Pubkey, side: VoteSide, weight: u64) {
+    let voter = dao.add_member(0);
+    dao.vote(proposal, &voter, side, weight).unwrap();
+}
+
+fn fast_track(dao: &mut Dao, proposal: Pubkey) -> TxResult {
+    dao.send(ix::fast_track_proposal(dao.wallet(), proposal), &[])
+}
+
+#[test]
+fn emergency_categories_need_a_supermajority_and_quorum() {
+    let mut dao = Dao::new();
+    let weak = ProposalCategoryConfig { vote_threshold: 66, ..emergency() };
+    assert_error(configure(&mut dao, EMERGENCY, weak), ErrorCode::InvalidProposalCategory);
+    let open = ProposalCategoryConfig { quorum: 0, ..emergency() };
+    assert_error(configure(&mut dao, EMERGENCY, open), ErrorCode::InvalidProposalCategory);
+
+    configure(&mut dao, EMERGENCY, ProposalCategoryConfig { vote_threshold: 67, ..emergency() }).unwrap();
+}
+
+#[test]
+fn fast_track_passes_once_the_bar_is_met_and_skips_the_timelock() {
+    let mut dao = Dao::builder().category(EMERGENCY, emergency()).category(ROUTINE, routine()).build();
+    let proposal = dao.active_proposal_in(Some(EMERGENCY), VOTING);
+    vote(&mut dao, proposal, VoteSide::For {}, 60);
+    assert_error(fast_track(&mut dao, proposal), ErrorCode::EmergencyThresholdNotMet);
+
+    vote(&mut dao, proposal, VoteSide::For {}, 50);
+    fast_track(&mut dao, proposal).unwrap();
+    let event = &dao.ctx.events::<ProposalFinalised>()[0];
+    assert!(event.state == ProposalState::Succeeded);
+    assert_eq!(event.for_votes, 110);
+
+    dao.queue(proposal).unwrap();
+    dao.execute(proposal).unwrap();
+    assert!(dao.proposal(&proposal).state == ProposalState::Executed);
+}
+
+#[test]
+fn shortened_timelock_still_applies() {
+    let delayed = ProposalCategoryConfig { timelock_delay: 600, ..emergency() };
+    let mut dao = Dao::builder().category(EMERGENCY, delayed).category(ROUTINE, routine()).build();
+    let proposal = dao.active_proposal_in(Some(EMERGENCY), VOTING);
+    vote(&mut dao, proposal, VoteSide::For {}, 100);
+    fast_track(&mut dao, proposal).unwrap();
+    dao.queue(proposal).unwrap();
+
+    assert_error(dao.execute(proposal), ErrorCode::TimelockNotExpired);
+    dao.ctx.warp(600);
+    dao.execute(proposal).unwrap();
+}
+
+#[test]
+fn opposition_blocks_the_fast_track() {
+    let mut dao = Dao::builder().category(EMERGENCY, emergency()).category(ROUTINE, routine()).build();
+    let proposal = dao.active_proposal_in(Some(EMERGENCY), VOTING);
+    vote(&mut dao, proposal, VoteSide::For {}, 100);
+    vote(&mut dao, proposal, VoteSide::Against {}, 30);
+
+    assert_error(fast_track(&mut dao, proposal), ErrorCode::EmergencyThresholdNotMet);
+}
+
+#[test]
+fn only_votes_for_count_towards_the_fast_track_quorum() {
+    let mut dao = Dao::builder().category(EMERGENCY, emergency()).category(ROUTINE, routine()).build();
+    let proposal = dao.active_proposal_in(Some(EMERGENCY), VOTING);
+    vote(&mut dao, proposal, VoteSide::For {}, 90);
+    vote(&mut dao, proposal, VoteSide::Abstain {}, 20);
+    assert_error(fast_track(&mut dao, proposal), ErrorCode::EmergencyThresholdNotMet);
+
+    // Abstentions still count towards the quorum once voting ends.
+    dao.ctx.warp(VOTING);
+    dao.finalise(proposal).unwrap();
+    assert!(dao.proposal(&proposal).state == ProposalState::Succeeded);
+}
+
+#[test]
+fn only_open_yes_no_emergency_proposals_can_be_fast_tracked() {
+    let mut dao = Dao::builder().category(EMERGENCY, emergency()).category(ROUTINE, routine()).build();
+    let routine = dao.active_proposal_in(Some(ROUTINE), VOTING);
+    vote(&mut dao, routine, VoteSide::For {}, 100);
+    assert_error(fast_track(&mut dao, routine), ErrorCode::NotEmergencyProposal);
+
+    let proposer = dao.add_member(0);
+    let proposal_id = dao.governor().proposal_count;
+    let options = vec![
+        NewProposalOption { label: "Pause".to_string(), instructions: vec![mock_instruction()] },
+        NewProposalOption { label: "Wait".to_string(), instructions: vec![] },
+    ];
+    let ix = ix::create_multiple_choice_proposal(
+        dao.governor,
+        proposal_id,
+        proposer.pubkey(),
+        dao.payer(),
+        Some(EMERGENCY),
+        options,
+        OptionSelection::Plurality,
+    );
+    dao.send(ix, &[&proposer]).unwrap();
+    let options = pda::find_proposal_address(&dao.governor, proposal_id).0;
+    dao.activate(options, VOTING).unwrap();
+    assert_error(fast_track(&mut dao, options), ErrorCode::NotEmergencyProposal);
+
+    let secret = dao.try_create_proposal_in(&proposer, Some(EMERGENCY), vec![mock_instruction()]).unwrap();
+    dao.send(ix::activate_secret_proposal(dao.wallet(), secret, Some(VOTING), 600), &[]).unwrap();
+    assert_error(fast_track(&mut dao, secret), ErrorCode::NotEmergencyProposal);
+}
+
+#[test]
+fn fast_tracked_proposal_closes_voting() {
+    let mut dao = Dao::builder().category(EMERGENCY, emergency()).category(ROUTINE, routine()).build();
+    let proposal = dao.active_proposal_in(Some(EMERGENCY), VOTING);
+    vote(&mut dao, proposal, VoteSide::For {}, 100);
+    fast_track(&mut dao, proposal).unwrap();
+
+    let late = dao.add_member(0);
+    assert_error(dao.vote(proposal, &late, VoteSide::Against {}, 500), AnchorError::ConstraintRaw);
+    assert_error(dao.finalise(proposal), ErrorCode::InvalidStateTransition);
+    assert_error(fast_track(&mut dao, proposal), ErrorCode::InvalidStateTransition);
+}
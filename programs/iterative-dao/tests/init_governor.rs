@@ -0,0 +1,84 @@
+//! Port of `tests/test-case-1.ts`.
+
+mod common;
+
+use common::{assert_error, ix, pda, Dao, TxError};
+use iterative_dao::{
+    errors::ErrorCode,
+    events::{GovernorCreated, VoterAdded},
+};
+use solana_sdk::signature::Signer;
+
+#[test]
+fn test_case_1_1_init_governor_with_valid_parameters() {
+    let dao = Dao::new();
+    let governor = dao.governor();
+
+    assert!(governor.is_initialised);
+    assert_eq!(governor.vote_threshold, 60);
+    assert_eq!(governor.timelock_delay, 3600);
+    assert_eq!(governor.smart_wallet, dao.wallet());
+    assert_eq!(governor.electorate, dao.electorate.pubkey());
+    assert_eq!(governor.governance_mint, dao.governance_mint);
+    assert_eq!(governor.proposal_count, 0);
+    assert!(governor.voters.is_empty());
+}
+
+#[test]
+fn test_case_1_2_governor_is_linked_to_smart_wallet() {
+    let dao = Dao::new();
+    assert_eq!(dao.governor, pda::find_governor_address(&dao.wallet()).0);
+    assert_eq!(dao.governor().smart_wallet, dao.wallet());
+
+    let events = dao.ctx.events::<GovernorCreated>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].governor, dao.governor);
+    assert_eq!(events[0].smart_wallet, dao.wallet());
+    assert_eq!(events[0].governance_mint, dao.governance_mint);
+}
+
+#[test]
+fn test_case_1_4_init_without_smart_wallet_signature_fails() {
+    let mut dao = Dao::uninitialised();
+    let ix = ix::init_governor(dao.wallet(), dao.payer(), 60, 3600, dao.electorate.pubkey(), dao.governance_mint);
+
+    let result = dao.ctx.send(ix, &[]);
+    assert_eq!(result, Err(TxError::MissingSignature(dao.wallet())));
+    assert!(!dao.ctx.exists(&dao.governor));
+}
+
+#[test]
+fn test_case_1_5_init_with_invalid_threshold_fails() {
+    let mut dao = Dao::uninitialised();
+    let ix = ix::init_governor(dao.wallet(), dao.payer(), 150, 3600, dao.electorate.pubkey(), dao.governance_mint);
+
+    assert_error(dao.send(ix, &[]), ErrorCode::InvalidVoteThreshold);
+}
+
+#[test]
+fn init_with_negative_timelock_fails() {
+    let mut dao = Dao::uninitialised();
+    let ix = ix::init_governor(dao.wallet(), dao.payer(), 60, -1, dao.electorate.pubkey(), dao.governance_mint);
+
+    assert_error(dao.send(ix, &[]), ErrorCode::InvalidTimelockDelay);
+}
+
+#[test]
+fn add_voter_emits_voter_added() {
+    let mut dao = Dao::new();
+    let member = dao.add_member(75);
+
+    let event = &dao.ctx.events::<VoterAdded>()[0];
+    assert_eq!((event.governor, event.voter), (dao.governor, member.pubkey()));
+    assert_eq!((event.weight, event.voter_count), (75, 1));
+}
+
+#[test]
+fn add_voter_rejects_duplicates() {
+    let mut dao = Dao::new();
+    let member = dao.add_member(100);
+
+    let result = dao.send(ix::add_voter(dao.wallet(), member.pubkey(), 50), &[]);
+    assert_error(result, ErrorCode::DuplicateVoter);
+    assert_eq!(dao.governor().voters.len(), 1);
+}
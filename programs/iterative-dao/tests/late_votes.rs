@@ -0,0 +1,152 @@
+//! Extending voting when a late vote moves the outcome.
+
+mod common;
+
+use common::{assert_error, ix, Dao};
+use iterative_dao::{
+    enums::{ProposalState, VoteSide},
+    errors::ErrorCode,
+    events::{LateVoteExtensionSet, VotingExtended},
+    state::{BatchedVote, Governor, LateVoteExtension},
+};
+use solana_sdk::signature::Signer;
+
+const VOTING: i64 = 3600;
+/// Five more minutes for votes in the last ten, at most twice per proposal.
+const EXTENSION: LateVoteExtension = LateVoteExtension { window: 600, extension: 300, max_extension: 600 };
+
+fn rule(window: i64, extension: i64, max_extension: i64) -> LateVoteExtension {
+    LateVoteExtension { window, extension, max_extension }
+}
+
+#[test]
+fn late_vote_that_flips_the_outcome_extends_voting() {
+    let mut dao = Dao::builder().late_vote_extension(EXTENSION).build();
+    let proposal = dao.active_proposal(VOTING);
+    let (early, late) = (dao.add_member(0), dao.add_member(0));
+    dao.vote(proposal, &early, VoteSide::For {}, 40).unwrap();
+    dao.ctx.warp(VOTING - 100);
+    dao.vote(proposal, &late, VoteSide::Against {}, 60).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.voting_period, account.voting_extension), (VOTING + 300, 300));
+    let event = &dao.ctx.events::<VotingExtended>()[0];
+    assert_eq!(event.voting_ends_at, account.activated_at + VOTING + 300);
+    dao.ctx.warp(100);
+    assert_error(dao.finalise(proposal), ErrorCode::VotingPeriodActive);
+    dao.ctx.warp(300);
+    dao.finalise(proposal).unwrap();
+    assert!(dao.proposal(&proposal).state == ProposalState::Rejected);
+}
+
+#[test]
+fn late_vote_that_keeps_the_outcome_does_not_extend() {
+    let mut dao = Dao::builder().late_vote_extension(EXTENSION).build();
+    let proposal = dao.active_proposal(VOTING);
+    let (early, late) = (dao.add_member(0), dao.add_member(0));
+    dao.vote(proposal, &early, VoteSide::For {}, 90).unwrap();
+    dao.ctx.warp(VOTING - 100);
+    dao.vote(proposal, &late, VoteSide::Against {}, 10).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).voting_extension, 0);
+    assert!(dao.ctx.events::<VotingExtended>().is_empty());
+}
+
+#[test]
+fn votes_before_the_window_do_not_extend() {
+    let mut dao = Dao::builder().late_vote_extension(EXTENSION).build();
+    let proposal = dao.active_proposal(VOTING);
+    let (early, late) = (dao.add_member(0), dao.add_member(0));
+    dao.vote(proposal, &early, VoteSide::For {}, 40).unwrap();
+    dao.ctx.warp(VOTING - 601);
+    dao.vote(proposal, &late, VoteSide::Against {}, 60).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).voting_period, VOTING);
+}
+
+#[test]
+fn extensions_stop_at_the_maximum() {
+    let mut dao = Dao::builder().late_vote_extension(EXTENSION).build();
+    let proposal = dao.active_proposal(VOTING);
+    let (steady, swing) = (dao.add_member(40), dao.add_member(60));
+    dao.vote(proposal, &steady, VoteSide::For {}, 40).unwrap();
+    dao.vote(proposal, &swing, VoteSide::For {}, 60).unwrap();
+
+    dao.ctx.warp(VOTING - 10);
+    dao.change(proposal, &swing, VoteSide::Against {}).unwrap();
+    dao.ctx.warp(300);
+    dao.change(proposal, &swing, VoteSide::For {}).unwrap();
+    dao.ctx.warp(300);
+    dao.change(proposal, &swing, VoteSide::Against {}).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.voting_period, account.voting_extension), (VOTING + 600, 600));
+    assert!(dao.ctx.events::<VotingExtended>().is_empty());
+}
+
+#[test]
+fn withdrawing_late_can_extend() {
+    let mut dao = Dao::builder().late_vote_extension(EXTENSION).build();
+    let proposal = dao.active_proposal(VOTING);
+    let (steady, swing) = (dao.add_member(0), dao.add_member(0));
+    dao.vote(proposal, &steady, VoteSide::Against {}, 50).unwrap();
+    dao.vote(proposal, &swing, VoteSide::For {}, 100).unwrap();
+    dao.ctx.warp(VOTING - 1);
+    dao.send(ix::withdraw_vote(dao.wallet(), proposal, swing.pubkey(), dao.payer()), &[&swing]).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).voting_extension, 300);
+}
+
+#[test]
+fn governors_do_not_extend_by_default() {
+    let mut dao = Dao::new();
+    let governor: Governor = dao.ctx.account(&dao.governor);
+    assert!(governor.late_vote_extension.is_none());
+    let proposal = dao.active_proposal(VOTING);
+    let (early, late) = (dao.add_member(0), dao.add_member(0));
+    dao.vote(proposal, &early, VoteSide::For {}, 40).unwrap();
+    dao.ctx.warp(VOTING - 100);
+    dao.vote(proposal, &late, VoteSide::Against {}, 60).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).voting_period, VOTING);
+}
+
+#[test]
+fn rule_must_be_consistent_and_can_be_cleared() {
+    let mut dao = Dao::new();
+    for invalid in [rule(0, 300, 600), rule(600, 0, 600), rule(600, 300, 200)] {
+        let ix = ix::set_late_vote_extension(dao.wallet(), Some(invalid));
+        assert_error(dao.send(ix, &[]), ErrorCode::InvalidLateVoteExtension);
+    }
+
+    dao.send(ix::set_late_vote_extension(dao.wallet(), Some(rule(600, 300, 300))), &[]).unwrap();
+    assert!(dao.ctx.events::<LateVoteExtensionSet>()[0].late_vote_extension == Some(rule(600, 300, 300)));
+    dao.send(ix::set_late_vote_extension(dao.wallet(), None), &[]).unwrap();
+    let governor: Governor = dao.ctx.account(&dao.governor);
+    assert!(governor.late_vote_extension.is_none());
+}
+
+#[test]
+fn votes_after_the_deadline_fail() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(VOTING);
+    let (voted, pending, late) = (dao.add_member(0), dao.add_member(0), dao.add_member(0));
+    dao.vote(proposal, &voted, VoteSide::For {}, 40).unwrap();
+    dao.send(ix::create_vote(dao.wallet(), proposal, pending.pubkey(), dao.payer()), &[&pending]).unwrap();
+    dao.ctx.warp(4000);
+
+    let cast = ix::cast_vote(dao.wallet(), proposal, pending.pubkey(), dao.payer(), VoteSide::Against {}, 60, None);
+    assert_error(dao.send(cast, &[&pending]), ErrorCode::VotingPeriodEnded);
+    let batch = vec![(proposal, BatchedVote { side: VoteSide::Against {}, weight: 60 })];
+    let cast_batch = ix::cast_votes(dao.wallet(), pending.pubkey(), dao.payer(), batch);
+    assert_error(dao.send(cast_batch, &[&pending]), ErrorCode::VotingPeriodEnded);
+    let first_vote =
+        ix::create_and_cast_vote(dao.wallet(), proposal, late.pubkey(), dao.payer(), VoteSide::For {}, 60, None);
+    assert_error(dao.send(first_vote, &[&late]), ErrorCode::VotingPeriodEnded);
+    assert_error(dao.change(proposal, &voted, VoteSide::Against {}), ErrorCode::VotingPeriodEnded);
+    let withdraw = ix::withdraw_vote(dao.wallet(), proposal, voted.pubkey(), dao.payer());
+    assert_error(dao.send(withdraw, &[&voted]), ErrorCode::VotingPeriodEnded);
+
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.for_votes, account.against_votes), (40, 0));
+}
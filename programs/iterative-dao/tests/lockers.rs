@@ -0,0 +1,329 @@
+//! Lockers, escrows and escrowed voting power.
+
+mod common;
+
+use anchor_lang::{prelude::Pubkey, InstructionData};
+use common::{assert_error, ix, pda, Dao};
+use iterative_dao::{
+    enums::VoteSide,
+    errors::ErrorCode,
+    events::{EscrowWithdrawn, LockerSetParamsEvent, NewLockerEvent},
+    state::{Escrow, Locker, ProposalAccount, ProposalInstruction},
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+const DAY: i64 = 24 * 60 * 60;
+
+fn create_locker(dao: &mut Dao, multiplier: u64) -> Pubkey {
+    let mint = dao.governance_mint;
+    dao.send(ix::create_locker(dao.wallet(), mint, dao.payer(), multiplier, DAY, 365 * DAY), &[])
+        .expect("create_locker");
+    pda::find_locker_address(&dao.governor, &mint).0
+}
+
+/// Escrows `amount` of a fresh member's tokens in `locker` and returns the member and escrow.
+fn escrowed_member(dao: &mut Dao, locker: Pubkey, amount: u64) -> (Keypair, Pubkey) {
+    let member = dao.add_member(0);
+    let tokens = dao.fund_member(&member.pubkey(), amount);
+    let accs = dao.lock_accounts(&member.pubkey(), tokens);
+    dao.send(ix::create_escrow(accs, locker, dao.payer(), amount, 30 * DAY, 1), &[&member])
+        .expect("create_escrow");
+    let escrow = pda::find_escrow_address(&locker, &member.pubkey(), 1).0;
+    (member, escrow)
+}
+
+/// A proposal approving `set_locker_params` on `locker`, driven through to `Executed`.
+fn executed_params_proposal(dao: &mut Dao, locker: Pubkey, multiplier: u64, min: i64, max: i64) -> Pubkey {
+    let proposer = dao.add_member(100);
+    let approved = ProposalInstruction {
+        program_id: iterative_dao::ID,
+        accounts: vec![ProposalAccount { pubkey: locker, is_signer: false, is_writable: true }],
+        data: iterative_dao::instruction::SetLockerParams {
+            new_voting_power_multiplier: multiplier,
+            new_min_lock_duration: min,
+            new_max_lock_duration: max,
+        }
+        .data(),
+    };
+    let proposal = dao.try_create_proposal(&proposer, vec![approved]).unwrap();
+    dao.activate(proposal, 60).unwrap();
+    dao.vote(proposal, &proposer, VoteSide::For {}, 100).unwrap();
+    dao.ctx.warp(60);
+    dao.finalise(proposal).unwrap();
+    dao.queue(proposal).unwrap();
+    dao.ctx.warp(3600);
+    dao.execute(proposal).unwrap();
+    proposal
+}
+
+//  Lockers
+
+#[test]
+fn create_locker_for_governance_mint() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 2);
+
+    let account: Locker = dao.ctx.account(&locker);
+    assert_eq!(account.governor, dao.governor);
+    assert_eq!(account.mint, dao.governance_mint);
+    assert_eq!(account.voting_power_multiplier, 2);
+    assert_eq!(account.total_locked, 0);
+    assert_eq!(dao.ctx.events::<NewLockerEvent>()[0].mint, dao.governance_mint);
+}
+
+#[test]
+fn second_locker_for_same_mint_fails() {
+    let mut dao = Dao::new();
+    create_locker(&mut dao, 2);
+    let mint = dao.governance_mint;
+
+    let result = dao.send(ix::create_locker(dao.wallet(), mint, dao.payer(), 3, DAY, 2 * DAY), &[]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn locker_params_out_of_bounds_fail() {
+    let mut dao = Dao::new();
+    let mint = dao.governance_mint;
+    for (multiplier, max) in [(0, DAY), (101, DAY), (1, 5 * 365 * DAY)] {
+        let result = dao.send(ix::create_locker(dao.wallet(), mint, dao.payer(), multiplier, 0, max), &[]);
+        assert_error(result, ErrorCode::LockerParamsOutOfBounds);
+    }
+}
+
+#[test]
+fn set_locker_params_requires_executed_proposal() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 2);
+    let proposer = dao.add_member(100);
+    let pending = dao.create_proposal(&proposer);
+
+    let result = dao.send(ix::set_locker_params(dao.wallet(), locker, pending, 5, DAY, 2 * DAY), &[]);
+    assert_error(result, ErrorCode::LockerParamsNotApproved);
+}
+
+#[test]
+fn set_locker_params_must_match_proposal() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 2);
+    let proposal = executed_params_proposal(&mut dao, locker, 5, DAY, 2 * DAY);
+
+    let result = dao.send(ix::set_locker_params(dao.wallet(), locker, proposal, 6, DAY, 2 * DAY), &[]);
+    assert_error(result, ErrorCode::LockerParamsNotApproved);
+}
+
+#[test]
+fn set_locker_params_through_governance() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 2);
+    let proposal = executed_params_proposal(&mut dao, locker, 5, DAY, 2 * DAY);
+
+    dao.send(ix::set_locker_params(dao.wallet(), locker, proposal, 5, DAY, 2 * DAY), &[])
+        .unwrap();
+    let account: Locker = dao.ctx.account(&locker);
+    assert_eq!(account.voting_power_multiplier, 5);
+    assert_eq!(account.max_lock_duration, 2 * DAY);
+    assert_eq!(dao.ctx.events::<LockerSetParamsEvent>()[0].new_voting_power_multiplier, 5);
+}
+
+#[test]
+fn applied_params_proposal_cannot_be_replayed() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 2);
+    let old = executed_params_proposal(&mut dao, locker, 5, DAY, 2 * DAY);
+    let new = executed_params_proposal(&mut dao, locker, 8, DAY, 3 * DAY);
+    dao.send(ix::set_locker_params(dao.wallet(), locker, old, 5, DAY, 2 * DAY), &[])
+        .unwrap();
+
+    let replay = dao.send(ix::set_locker_params(dao.wallet(), locker, old, 5, DAY, 2 * DAY), &[]);
+    assert_error(replay, ErrorCode::LockerParamsProposalUsed);
+    dao.send(ix::set_locker_params(dao.wallet(), locker, new, 8, DAY, 3 * DAY), &[])
+        .unwrap();
+    // Nor can the older approval revert the newer change.
+    let revert = dao.send(ix::set_locker_params(dao.wallet(), locker, old, 5, DAY, 2 * DAY), &[]);
+    assert_error(revert, ErrorCode::LockerParamsProposalUsed);
+    assert_eq!(dao.ctx.account::<Locker>(&locker).voting_power_multiplier, 8);
+}
+
+//  Escrows
+
+#[test]
+fn escrow_snapshots_multiplier() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 2);
+    let (member, escrow) = escrowed_member(&mut dao, locker, 40);
+
+    let account: Escrow = dao.ctx.account(&escrow);
+    assert_eq!(account.user, member.pubkey());
+    assert_eq!(account.amount, 40);
+    assert_eq!(account.voting_power_multiplier, 2);
+    assert_eq!(dao.ctx.account::<Locker>(&locker).total_locked, 40);
+    assert_eq!(dao.ctx.token_balance(&dao.escrow_token_account), 40);
+}
+
+#[test]
+fn withdraw_escrow_once_it_ends() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 2);
+    let member = dao.add_member(0);
+    let tokens = dao.fund_member(&member.pubkey(), 40);
+    let accs = dao.lock_accounts(&member.pubkey(), tokens);
+    dao.send(ix::create_escrow(accs, locker, dao.payer(), 40, 30 * DAY, 1), &[&member]).unwrap();
+    let escrow = pda::find_escrow_address(&locker, &member.pubkey(), 1).0;
+
+    dao.ctx.warp(30 * DAY - 1);
+    assert_error(dao.send(ix::withdraw_escrow(accs, locker, 1), &[&member]), ErrorCode::LockNotExpired);
+    dao.ctx.warp(1);
+    dao.send(ix::withdraw_escrow(accs, locker, 1), &[&member]).unwrap();
+
+    let account: Escrow = dao.ctx.account(&escrow);
+    assert!(account.withdrawn);
+    assert_eq!(account.withdrawn_at, dao.ctx.now());
+    assert_eq!(dao.ctx.account::<Locker>(&locker).total_locked, 0);
+    assert_eq!(dao.ctx.token_balance(&accs.user_token_account), 40);
+    assert_eq!(dao.ctx.events::<EscrowWithdrawn>()[0].amount, 40);
+    assert_error(dao.send(ix::withdraw_escrow(accs, locker, 1), &[&member]), ErrorCode::AlreadyWithdrawn);
+}
+
+#[test]
+fn escrow_duration_outside_locker_bounds_fails() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 2);
+    let member = dao.add_member(0);
+    let tokens = dao.fund_member(&member.pubkey(), 10);
+    let accs = dao.lock_accounts(&member.pubkey(), tokens);
+
+    let result = dao.send(ix::create_escrow(accs, locker, dao.payer(), 10, DAY - 1, 1), &[&member]);
+    assert_error(result, ErrorCode::InvalidLockParameters);
+}
+
+#[test]
+fn escrow_with_wrong_mint_fails() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 2);
+    let member = dao.add_member(0);
+    let other_mint = dao.ctx.create_mint(&member.pubkey(), 0);
+    let tokens = dao.ctx.create_token_account(&other_mint, &member.pubkey());
+    dao.ctx.mint_to(&tokens, 10);
+    let accs = dao.lock_accounts(&member.pubkey(), tokens);
+
+    let result = dao.send(ix::create_escrow(accs, locker, dao.payer(), 10, DAY, 1), &[&member]);
+    assert_error(result, ErrorCode::InvalidLockerMint);
+}
+
+#[test]
+fn escrow_into_self_owned_vault_fails() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 2);
+    let member = dao.add_member(0);
+    let tokens = dao.fund_member(&member.pubkey(), 10);
+    let vault = dao.ctx.create_token_account(&dao.governance_mint, &member.pubkey());
+    let accs = ix::LockAccounts { escrow_token_account: vault, ..dao.lock_accounts(&member.pubkey(), tokens) };
+
+    let result = dao.send(ix::create_escrow(accs, locker, dao.payer(), 10, DAY, 1), &[&member]);
+    assert_error(result, ErrorCode::InvalidEscrowOwner);
+}
+
+//  Escrowed voting power
+
+#[test]
+fn cast_vote_counts_escrowed_power() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 3);
+    let (member, escrow) = escrowed_member(&mut dao, locker, 40);
+    let proposal = dao.create_proposal(&member);
+    dao.activate(proposal, 3600).unwrap();
+
+    dao.send(ix::create_vote(dao.wallet(), proposal, member.pubkey(), dao.payer()), &[&member])
+        .unwrap();
+    let cast = ix::cast_vote(dao.wallet(), proposal, member.pubkey(), dao.payer(), VoteSide::For {}, 5, None);
+    dao.send(ix::with_escrows(cast, &[(locker, escrow)]), &[&member])
+        .unwrap();
+
+    assert_eq!(dao.proposal(&proposal).for_votes, 5 + 40 * 3);
+}
+
+#[test]
+fn escrow_power_keeps_creation_multiplier() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 3);
+    let (member, escrow) = escrowed_member(&mut dao, locker, 10);
+    let params = executed_params_proposal(&mut dao, locker, 50, DAY, 365 * DAY);
+    dao.send(ix::set_locker_params(dao.wallet(), locker, params, 50, DAY, 365 * DAY), &[])
+        .unwrap();
+
+    let proposal = dao.create_proposal(&member);
+    dao.activate(proposal, 3600).unwrap();
+    dao.send(ix::create_vote(dao.wallet(), proposal, member.pubkey(), dao.payer()), &[&member])
+        .unwrap();
+    let cast = ix::cast_vote(dao.wallet(), proposal, member.pubkey(), dao.payer(), VoteSide::For {}, 0, None);
+    dao.send(ix::with_escrows(cast, &[(locker, escrow)]), &[&member])
+        .unwrap();
+
+    assert_eq!(dao.proposal(&proposal).for_votes, 30);
+}
+
+#[test]
+fn expired_escrow_adds_no_power() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 3);
+    let (member, escrow) = escrowed_member(&mut dao, locker, 40);
+    let proposal = dao.create_proposal(&member);
+    dao.ctx.warp(30 * DAY);
+    dao.activate(proposal, 3600).unwrap();
+
+    dao.send(ix::create_vote(dao.wallet(), proposal, member.pubkey(), dao.payer()), &[&member])
+        .unwrap();
+    let cast = ix::cast_vote(dao.wallet(), proposal, member.pubkey(), dao.payer(), VoteSide::For {}, 5, None);
+    dao.send(ix::with_escrows(cast, &[(locker, escrow)]), &[&member])
+        .unwrap();
+
+    assert_eq!(dao.proposal(&proposal).for_votes, 5);
+}
+
+#[test]
+fn other_members_escrow_is_rejected() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 3);
+    let (_, escrow) = escrowed_member(&mut dao, locker, 40);
+    let voter = dao.add_member(100);
+    let proposal = dao.create_proposal(&voter);
+    dao.activate(proposal, 3600).unwrap();
+
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[&voter])
+        .unwrap();
+    let cast = ix::cast_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), VoteSide::For {}, 5, None);
+    let result = dao.send(ix::with_escrows(cast, &[(locker, escrow)]), &[&voter]);
+    assert_error(result, ErrorCode::InvalidEscrowAccount);
+}
+
+#[test]
+fn duplicate_escrow_is_rejected() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 3);
+    let (member, escrow) = escrowed_member(&mut dao, locker, 40);
+    let proposal = dao.create_proposal(&member);
+    dao.activate(proposal, 3600).unwrap();
+
+    dao.send(ix::create_vote(dao.wallet(), proposal, member.pubkey(), dao.payer()), &[&member])
+        .unwrap();
+    let cast = ix::cast_vote(dao.wallet(), proposal, member.pubkey(), dao.payer(), VoteSide::For {}, 5, None);
+    let result = dao.send(ix::with_escrows(cast, &[(locker, escrow), (locker, escrow)]), &[&member]);
+    assert_error(result, ErrorCode::InvalidEscrowAccount);
+}
+
+#[test]
+fn non_program_locker_is_rejected() {
+    let mut dao = Dao::new();
+    let locker = create_locker(&mut dao, 3);
+    let (member, _) = escrowed_member(&mut dao, locker, 40);
+    let proposal = dao.create_proposal(&member);
+    dao.activate(proposal, 3600).unwrap();
+
+    dao.send(ix::create_vote(dao.wallet(), proposal, member.pubkey(), dao.payer()), &[&member])
+        .unwrap();
+    let cast = ix::cast_vote(dao.wallet(), proposal, member.pubkey(), dao.payer(), VoteSide::For {}, 5, None);
+    let fake = dao.escrow_token_account;
+    let result = dao.send(ix::with_escrows(cast, &[(fake, fake)]), &[&member]);
+    assert_error(result, ErrorCode::InvalidEscrowAccount);
+}
@@ -0,0 +1,264 @@
+//! Multiple-choice proposals and option-level tallies.
+
+mod common;
+
+use anchor_lang::{prelude::Pubkey, InstructionData};
+use common::{assert_error, ix, mock_instruction, pda, Dao};
+use iterative_dao::{
+    close::RETENTION_PERIOD,
+    enums::{OptionSelection, ProposalState, VoteSide},
+    errors::ErrorCode,
+    events::{ProposalClosed, ProposalCreated, ProposalFinalised},
+    state::{Locker, NewProposalOption, ProposalAccount, ProposalInstruction},
+};
+use solana_sdk::signature::Keypair;
+
+fn option(label: &str) -> NewProposalOption {
+    NewProposalOption { label: label.to_string(), instructions: vec![mock_instruction()] }
+}
+
+fn choice(index: u8) -> VoteSide {
+    VoteSide::Choice { index }
+}
+
+/// An active three-option proposal and a proposer who can vote on it.
+fn choice_proposal(dao: &mut Dao, selection: OptionSelection) -> (Pubkey, Keypair) {
+    let proposer = dao.add_member(100);
+    let options = vec![option("Small"), option("Medium"), option("Large")];
+    let proposal = dao.try_create_multiple_choice_proposal(&proposer, options, selection).unwrap();
+    dao.activate(proposal, 3600).unwrap();
+    (proposal, proposer)
+}
+
+fn finalised(dao: &mut Dao, proposal: Pubkey) -> (ProposalState, Option<u8>) {
+    dao.ctx.warp(3600);
+    dao.finalise(proposal).unwrap();
+    let account = dao.proposal(&proposal);
+    (account.state, account.winning_option)
+}
+
+//  Creation
+
+#[test]
+fn create_stores_options() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let options = vec![option("Yes"), NewProposalOption { label: "Status quo".into(), instructions: vec![] }];
+    let proposal = dao
+        .try_create_multiple_choice_proposal(&proposer, options, OptionSelection::Plurality)
+        .unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert!(account.is_multiple_choice());
+    assert!(account.instructions.is_empty());
+    assert_eq!(account.options.len(), 2);
+    assert_eq!(account.options[1].label, "Status quo");
+    assert!(account.options[1].instructions.is_empty());
+    assert_eq!(account.winning_option, None);
+    let event = &dao.ctx.events::<ProposalCreated>()[0];
+    assert_eq!((event.option_count, event.instruction_count), (2, 1));
+}
+
+#[test]
+fn create_with_invalid_options_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let too_many = (0..9).map(|i| option(&format!("Option {i}"))).collect();
+    let long_label = "x".repeat(33);
+    for options in [vec![option("Only")], vec![option("A"), option("")], vec![option("A"), option(&long_label)], too_many] {
+        let result = dao.try_create_multiple_choice_proposal(&proposer, options, OptionSelection::Plurality);
+        assert_error(result.map(|_| ()), ErrorCode::InvalidProposalOptions);
+    }
+}
+
+#[test]
+fn create_over_instruction_budget_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let crowded = NewProposalOption { label: "Crowded".into(), instructions: vec![mock_instruction(); 6] };
+    let options = vec![crowded.clone(), crowded];
+
+    let result = dao.try_create_multiple_choice_proposal(&proposer, options, OptionSelection::Plurality);
+    assert_error(result.map(|_| ()), ErrorCode::InvalidInstructions);
+}
+
+//  Voting
+
+#[test]
+fn votes_tally_per_option() {
+    let mut dao = Dao::new();
+    let (proposal, a) = choice_proposal(&mut dao, OptionSelection::Plurality);
+    let b = dao.add_member(100);
+    let c = dao.add_member(100);
+    dao.vote(proposal, &a, choice(0), 10).unwrap();
+    dao.vote(proposal, &b, choice(2), 25).unwrap();
+    dao.vote(proposal, &c, VoteSide::Abstain {}, 5).unwrap();
+
+    let account = dao.proposal(&proposal);
+    let votes: Vec<u64> = account.options.iter().map(|o| o.votes).collect();
+    assert_eq!(votes, vec![10, 0, 25]);
+    assert_eq!((account.for_votes, account.against_votes, account.abstain_votes), (0, 0, 5));
+}
+
+#[test]
+fn yes_no_sides_on_multiple_choice_fail() {
+    let mut dao = Dao::new();
+    let (proposal, voter) = choice_proposal(&mut dao, OptionSelection::Plurality);
+
+    assert_error(dao.vote(proposal, &voter, VoteSide::For {}, 10), ErrorCode::InvalidVoteOption);
+}
+
+#[test]
+fn choice_out_of_range_fails() {
+    let mut dao = Dao::new();
+    let (proposal, voter) = choice_proposal(&mut dao, OptionSelection::Plurality);
+
+    assert_error(dao.vote(proposal, &voter, choice(3), 10), ErrorCode::InvalidVoteOption);
+}
+
+#[test]
+fn choice_on_yes_no_proposal_fails() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(100);
+    let proposal = dao.create_proposal(&voter);
+    dao.activate(proposal, 3600).unwrap();
+
+    assert_error(dao.vote(proposal, &voter, choice(0), 10), ErrorCode::InvalidVoteOption);
+}
+
+#[test]
+fn set_vote_moves_weight_between_options() {
+    let mut dao = Dao::new();
+    let (proposal, voter) = choice_proposal(&mut dao, OptionSelection::Plurality);
+    dao.vote(proposal, &voter, choice(0), 100).unwrap();
+
+    dao.change(proposal, &voter, choice(1)).unwrap();
+
+    let votes: Vec<u64> = dao.proposal(&proposal).options.iter().map(|o| o.votes).collect();
+    assert_eq!(votes, vec![0, 100, 0]);
+}
+
+//  Finalisation
+
+#[test]
+fn plurality_picks_most_voted_option() {
+    let mut dao = Dao::new();
+    let (proposal, a) = choice_proposal(&mut dao, OptionSelection::Plurality);
+    let b = dao.add_member(100);
+    let c = dao.add_member(100);
+    dao.vote(proposal, &a, choice(0), 30).unwrap();
+    dao.vote(proposal, &b, choice(1), 40).unwrap();
+    dao.vote(proposal, &c, choice(2), 30).unwrap();
+
+    assert!(finalised(&mut dao, proposal) == (ProposalState::Succeeded, Some(1)));
+    assert_eq!(dao.ctx.events::<ProposalFinalised>()[0].winning_option, Some(1));
+}
+
+#[test]
+fn tie_for_first_rejects() {
+    let mut dao = Dao::new();
+    let (proposal, a) = choice_proposal(&mut dao, OptionSelection::Plurality);
+    let b = dao.add_member(100);
+    dao.vote(proposal, &a, choice(0), 30).unwrap();
+    dao.vote(proposal, &b, choice(2), 30).unwrap();
+
+    assert!(finalised(&mut dao, proposal) == (ProposalState::Rejected, None));
+}
+
+#[test]
+fn no_votes_rejects() {
+    let mut dao = Dao::new();
+    let (proposal, _) = choice_proposal(&mut dao, OptionSelection::Plurality);
+
+    assert!(finalised(&mut dao, proposal) == (ProposalState::Rejected, None));
+}
+
+#[test]
+fn threshold_requires_leader_share() {
+    let mut dao = Dao::new();
+    let (proposal, a) = choice_proposal(&mut dao, OptionSelection::Threshold);
+    let b = dao.add_member(100);
+    dao.vote(proposal, &a, choice(0), 50).unwrap();
+    dao.vote(proposal, &b, choice(1), 40).unwrap();
+
+    // 50 of 90 is below the governor's 60% threshold.
+    assert!(finalised(&mut dao, proposal) == (ProposalState::Rejected, None));
+}
+
+#[test]
+fn threshold_met_succeeds() {
+    let mut dao = Dao::new();
+    let (proposal, a) = choice_proposal(&mut dao, OptionSelection::Threshold);
+    let b = dao.add_member(100);
+    dao.vote(proposal, &a, choice(2), 60).unwrap();
+    dao.vote(proposal, &b, VoteSide::Abstain {}, 40).unwrap();
+
+    assert!(finalised(&mut dao, proposal) == (ProposalState::Succeeded, Some(2)));
+}
+
+//  Execution
+
+fn set_multiplier(locker: Pubkey, multiplier: u64) -> ProposalInstruction {
+    ProposalInstruction {
+        program_id: iterative_dao::ID,
+        accounts: vec![ProposalAccount { pubkey: locker, is_signer: false, is_writable: true }],
+        data: iterative_dao::instruction::SetLockerParams {
+            new_voting_power_multiplier: multiplier,
+            new_min_lock_duration: 0,
+            new_max_lock_duration: 100,
+        }
+        .data(),
+    }
+}
+
+#[test]
+fn only_winning_option_is_approved() {
+    let mut dao = Dao::new();
+    let mint = dao.governance_mint;
+    dao.send(ix::create_locker(dao.wallet(), mint, dao.payer(), 1, 0, 100), &[]).unwrap();
+    let locker = pda::find_locker_address(&dao.governor, &mint).0;
+
+    let proposer = dao.add_member(100);
+    let options = vec![
+        NewProposalOption { label: "Double".into(), instructions: vec![set_multiplier(locker, 2)] },
+        NewProposalOption { label: "Triple".into(), instructions: vec![set_multiplier(locker, 3)] },
+    ];
+    let proposal = dao
+        .try_create_multiple_choice_proposal(&proposer, options, OptionSelection::Plurality)
+        .unwrap();
+    dao.activate(proposal, 60).unwrap();
+    dao.vote(proposal, &proposer, choice(1), 100).unwrap();
+    dao.ctx.warp(60);
+    dao.finalise(proposal).unwrap();
+    dao.queue(proposal).unwrap();
+    dao.ctx.warp(3600);
+    dao.execute(proposal).unwrap();
+
+    let losing = ix::set_locker_params(dao.wallet(), locker, proposal, 2, 0, 100);
+    assert_error(dao.send(losing, &[]), ErrorCode::LockerParamsNotApproved);
+    dao.send(ix::set_locker_params(dao.wallet(), locker, proposal, 3, 0, 100), &[])
+        .unwrap();
+    assert_eq!(dao.ctx.account::<Locker>(&locker).voting_power_multiplier, 3);
+}
+
+#[test]
+fn close_reports_final_option_tallies() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let options = vec![option("Change"), NewProposalOption { label: "Status quo".into(), instructions: vec![] }];
+    let proposal = dao
+        .try_create_multiple_choice_proposal(&proposer, options, OptionSelection::Plurality)
+        .unwrap();
+    dao.activate(proposal, 60).unwrap();
+    dao.vote(proposal, &proposer, choice(1), 100).unwrap();
+    dao.ctx.warp(60);
+    dao.finalise(proposal).unwrap();
+    dao.queue(proposal).unwrap();
+    dao.ctx.warp(3600);
+    dao.execute(proposal).unwrap();
+    dao.ctx.warp(RETENTION_PERIOD);
+
+    dao.send(ix::close_proposal(proposal, dao.payer()), &[]).unwrap();
+    let event = &dao.ctx.events::<ProposalClosed>()[0];
+    assert_eq!((event.option_votes.clone(), event.winning_option), (vec![0, 100], Some(1)));
+}
@@ -0,0 +1,261 @@
+//! Membership and voting power from NFTs of verified Metaplex collections.
+
+mod common;
+
+use anchor_lang::{prelude::Pubkey, solana_program::system_instruction::SystemError, AnchorSerialize};
+use common::{assert_error, ix, pda, Dao, TxResult};
+use iterative_dao::{
+    close::RETENTION_PERIOD,
+    enums::{VoteSide, WeightSource},
+    errors::ErrorCode,
+    events::{NftCollectionConfigured, NftVoteRecordClosed, NftVoteRecorded, VoteSetEvent},
+    metadata::{find_metadata_address, Collection, Metadata, METADATA_V1, TOKEN_METADATA_ID},
+    state::{NftVoteRecord, Vote},
+};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+};
+
+fn configure(dao: &mut Dao, collection: Pubkey, weight: u64) {
+    let ix = ix::configure_nft_collection(dao.wallet(), dao.payer(), collection, weight);
+    dao.send(ix, &[]).expect("configure_nft_collection");
+}
+
+/// Mints an NFT to `owner` with metadata placing it in `collection`.
+fn mint_nft(dao: &mut Dao, owner: &Pubkey, collection: Pubkey, verified: bool) -> ix::NftAccounts {
+    let mint = dao.ctx.create_mint(&Pubkey::new_unique(), 0);
+    let token_account = dao.ctx.create_token_account(&mint, owner);
+    dao.ctx.mint_to(&token_account, 1);
+    let metadata = Metadata {
+        key: METADATA_V1,
+        update_authority: Pubkey::new_unique(),
+        mint,
+        name: "Member #1".into(),
+        symbol: "DAO".into(),
+        uri: String::new(),
+        seller_fee_basis_points: 0,
+        creators: None,
+        primary_sale_happened: true,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: Some(0),
+        collection: Some(Collection { verified, key: collection }),
+    };
+    // Trailing bytes stand in for the fields newer metadata versions append.
+    let mut data = metadata.try_to_vec().unwrap();
+    data.extend([0u8; 16]);
+    let account = Account { lamports: 1_000_000, data, owner: TOKEN_METADATA_ID, ..Account::default() };
+    dao.ctx.set_account(find_metadata_address(&mint), account);
+    ix::NftAccounts { mint, token_account, collection }
+}
+
+fn nft_vote(dao: &mut Dao, proposal: Pubkey, voter: &Keypair, nft: ix::NftAccounts, side: VoteSide) -> TxResult {
+    let vote = pda::find_vote_address(&proposal, &voter.pubkey()).0;
+    if !dao.ctx.exists(&vote) {
+        dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[voter])?;
+    }
+    dao.send(ix::cast_nft_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), nft, side), &[voter])
+}
+
+#[test]
+fn configure_nft_collection_sets_weight() {
+    let collection = Pubkey::new_unique();
+    let mut dao = Dao::builder().weight_source(WeightSource::Nft).nft_collection(collection, 1).build();
+    configure(&mut dao, collection, 5);
+
+    let event = &dao.ctx.events::<NftCollectionConfigured>()[0];
+    assert_eq!((event.collection, event.weight), (collection, 5));
+}
+
+#[test]
+fn holders_vote_once_per_nft() {
+    let collection = Pubkey::new_unique();
+    let mut dao = Dao::builder().weight_source(WeightSource::Nft).nft_collection(collection, 1).build();
+    let proposal = dao.active_proposal(3600);
+    // Not in `Governor.voters`; holding the NFTs is enough.
+    let holder = Keypair::new();
+    let first = mint_nft(&mut dao, &holder.pubkey(), collection, true);
+    let second = mint_nft(&mut dao, &holder.pubkey(), collection, true);
+    nft_vote(&mut dao, proposal, &holder, first, VoteSide::For {}).unwrap();
+    nft_vote(&mut dao, proposal, &holder, second, VoteSide::For {}).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).for_votes, 2);
+    let vote: Vote = dao.ctx.account(&pda::find_vote_address(&proposal, &holder.pubkey()).0);
+    assert_eq!((vote.power, vote.weight), (2, 2));
+    assert_eq!(dao.ctx.events::<NftVoteRecorded>()[0].mint, second.mint);
+    assert_eq!(dao.ctx.events::<VoteSetEvent>()[0].weight, 2);
+}
+
+#[test]
+fn collections_carry_their_own_weight() {
+    let (common, rare) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut dao = Dao::builder()
+        .weight_source(WeightSource::Nft)
+        .nft_collection(common, 1)
+        .nft_collection(rare, 10)
+        .build();
+    let proposal = dao.active_proposal(3600);
+    let holder = Keypair::new();
+    let common_nft = mint_nft(&mut dao, &holder.pubkey(), common, true);
+    let rare_nft = mint_nft(&mut dao, &holder.pubkey(), rare, true);
+    nft_vote(&mut dao, proposal, &holder, common_nft, VoteSide::Against {}).unwrap();
+    nft_vote(&mut dao, proposal, &holder, rare_nft, VoteSide::Against {}).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).against_votes, 11);
+}
+
+#[test]
+fn nft_cannot_vote_again_after_changing_hands() {
+    let collection = Pubkey::new_unique();
+    let mut dao = Dao::builder().weight_source(WeightSource::Nft).nft_collection(collection, 1).build();
+    let proposal = dao.active_proposal(3600);
+    let seller = Keypair::new();
+    let nft = mint_nft(&mut dao, &seller.pubkey(), collection, true);
+    nft_vote(&mut dao, proposal, &seller, nft, VoteSide::For {}).unwrap();
+
+    // The buyer's token account holds the same mint.
+    let buyer = Keypair::new();
+    let token_account = dao.ctx.create_token_account(&nft.mint, &buyer.pubkey());
+    dao.ctx.mint_to(&token_account, 1);
+    let result = nft_vote(&mut dao, proposal, &buyer, ix::NftAccounts { token_account, ..nft }, VoteSide::Against {});
+
+    assert_error(result, SystemError::AccountAlreadyInUse as u32);
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.for_votes, account.against_votes), (1, 0));
+}
+
+#[test]
+fn withdrawn_nft_vote_keeps_its_nft_spent() {
+    let collection = Pubkey::new_unique();
+    let mut dao = Dao::builder().weight_source(WeightSource::Nft).nft_collection(collection, 1).build();
+    let proposal = dao.active_proposal(3600);
+    let holder = Keypair::new();
+    let nft = mint_nft(&mut dao, &holder.pubkey(), collection, true);
+    nft_vote(&mut dao, proposal, &holder, nft, VoteSide::For {}).unwrap();
+
+    let withdraw = ix::withdraw_vote(dao.wallet(), proposal, holder.pubkey(), dao.payer());
+    dao.send(withdraw, &[&holder]).unwrap();
+    assert_eq!(dao.proposal(&proposal).for_votes, 0);
+    assert!(dao.ctx.exists(&pda::find_nft_vote_address(&proposal, &nft.mint).0));
+
+    let result = nft_vote(&mut dao, proposal, &holder, nft, VoteSide::Against {});
+    assert_error(result, SystemError::AccountAlreadyInUse as u32);
+}
+
+#[test]
+fn another_holders_nft_is_rejected() {
+    let collection = Pubkey::new_unique();
+    let mut dao = Dao::builder().weight_source(WeightSource::Nft).nft_collection(collection, 1).build();
+    let proposal = dao.active_proposal(3600);
+    let owner = Keypair::new();
+    let nft = mint_nft(&mut dao, &owner.pubkey(), collection, true);
+
+    let voter = Keypair::new();
+    assert_error(nft_vote(&mut dao, proposal, &voter, nft, VoteSide::For {}), ErrorCode::InvalidNft);
+}
+
+#[test]
+fn unverified_or_unconfigured_collections_are_rejected() {
+    let collection = Pubkey::new_unique();
+    let mut dao = Dao::builder().weight_source(WeightSource::Nft).nft_collection(collection, 1).build();
+    let proposal = dao.active_proposal(3600);
+    let holder = Keypair::new();
+
+    let unverified = mint_nft(&mut dao, &holder.pubkey(), collection, false);
+    assert_error(nft_vote(&mut dao, proposal, &holder, unverified, VoteSide::For {}), ErrorCode::InvalidNft);
+
+    // Claims the configured collection while verified into another one.
+    let other = mint_nft(&mut dao, &holder.pubkey(), Pubkey::new_unique(), true);
+    let spoofed = ix::NftAccounts { collection, ..other };
+    assert_error(nft_vote(&mut dao, proposal, &holder, spoofed, VoteSide::For {}), ErrorCode::InvalidNft);
+
+    configure(&mut dao, collection, 0);
+    let disabled = mint_nft(&mut dao, &holder.pubkey(), collection, true);
+    assert_error(nft_vote(&mut dao, proposal, &holder, disabled, VoteSide::For {}), ErrorCode::InvalidNft);
+}
+
+#[test]
+fn later_nfts_must_join_the_same_side() {
+    let collection = Pubkey::new_unique();
+    let mut dao = Dao::builder().weight_source(WeightSource::Nft).nft_collection(collection, 1).build();
+    let proposal = dao.active_proposal(3600);
+    let holder = Keypair::new();
+    let first = mint_nft(&mut dao, &holder.pubkey(), collection, true);
+    let second = mint_nft(&mut dao, &holder.pubkey(), collection, true);
+    nft_vote(&mut dao, proposal, &holder, first, VoteSide::For {}).unwrap();
+
+    let result = nft_vote(&mut dao, proposal, &holder, second, VoteSide::Against {});
+    assert_error(result, ErrorCode::InvalidStateTransition);
+}
+
+#[test]
+fn set_vote_moves_nft_power() {
+    let collection = Pubkey::new_unique();
+    let mut dao = Dao::builder().weight_source(WeightSource::Nft).nft_collection(collection, 1).build();
+    let proposal = dao.active_proposal(3600);
+    let holder = Keypair::new();
+    for _ in 0..3 {
+        let nft = mint_nft(&mut dao, &holder.pubkey(), collection, true);
+        nft_vote(&mut dao, proposal, &holder, nft, VoteSide::For {}).unwrap();
+    }
+    dao.change(proposal, &holder, VoteSide::Against {}).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.for_votes, account.against_votes), (0, 3));
+}
+
+#[test]
+fn cast_vote_under_nft_weighting_fails() {
+    let mut dao = Dao::builder().weight_source(WeightSource::Nft).nft_collection(Pubkey::new_unique(), 1).build();
+    let proposal = dao.active_proposal(3600);
+    let voter = dao.add_member(10);
+
+    assert_error(dao.vote(proposal, &voter, VoteSide::For {}, 10), ErrorCode::NftVoteRequired);
+}
+
+#[test]
+fn nft_vote_without_nft_weighting_fails() {
+    let mut dao = Dao::new();
+    let collection = Pubkey::new_unique();
+    configure(&mut dao, collection, 1);
+    let proposal = dao.active_proposal(3600);
+    let voter = dao.add_member(0);
+    let nft = mint_nft(&mut dao, &voter.pubkey(), collection, true);
+
+    assert_error(nft_vote(&mut dao, proposal, &voter, nft, VoteSide::For {}), ErrorCode::NftVotingDisabled);
+}
+
+#[test]
+fn close_nft_vote_record_after_proposal_retention() {
+    let collection = Pubkey::new_unique();
+    let mut dao = Dao::builder().weight_source(WeightSource::Nft).nft_collection(collection, 1).build();
+    let proposal = dao.active_proposal(3600);
+    let holder = Keypair::new();
+    let nft = mint_nft(&mut dao, &holder.pubkey(), collection, true);
+    nft_vote(&mut dao, proposal, &holder, nft, VoteSide::Against {}).unwrap();
+    let record = pda::find_nft_vote_address(&proposal, &nft.mint).0;
+    let stored: NftVoteRecord = dao.ctx.account(&record);
+    assert_eq!((stored.vote, stored.weight), (pda::find_vote_address(&proposal, &holder.pubkey()).0, 1));
+
+    let close = ix::close_nft_vote_record(record, proposal, dao.payer());
+    assert_error(dao.send(close.clone(), &[]), ErrorCode::AccountNotTerminal);
+    dao.ctx.warp(3600);
+    dao.finalise(proposal).unwrap();
+    dao.ctx.warp(RETENTION_PERIOD);
+    dao.send(close, &[]).unwrap();
+    assert!(!dao.ctx.exists(&record));
+    assert_eq!(dao.ctx.events::<NftVoteRecordClosed>()[0].governor, dao.governor);
+}
+
+#[test]
+fn nft_vote_after_the_deadline_fails() {
+    let collection = Pubkey::new_unique();
+    let mut dao = Dao::builder().weight_source(WeightSource::Nft).nft_collection(collection, 1).build();
+    let proposal = dao.active_proposal(3600);
+    let holder = Keypair::new();
+    let nft = mint_nft(&mut dao, &holder.pubkey(), collection, true);
+    dao.ctx.warp(4000);
+
+    assert_error(nft_vote(&mut dao, proposal, &holder, nft, VoteSide::For {}), ErrorCode::VotingPeriodEnded);
+}
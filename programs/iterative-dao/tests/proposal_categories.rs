@@ -0,0 +1,225 @@
+//! Proposal categories with their own threshold, quorum, timelock and programs.
+
+mod common;
+
+use anchor_lang::{prelude::Pubkey, solana_program::system_program};
+use common::{assert_error, ix, mock_instruction, pda, Dao, TxResult};
+use iterative_dao::{
+    enums::{OptionSelection, ProposalState, VoteSide},
+    errors::ErrorCode,
+    events::{ProposalCategoryConfigured, ProposalCreated},
+    state::{NewProposalOption, ProposalCategory, ProposalCategoryConfig, ProposalInstruction},
+};
+use solana_sdk::signature::Signer;
+
+const TREASURY: u8 = 1;
+
+/// 75% of at least 100 votes, voted on for one hour to one day, then a two
+/// hour timelock; system program calls only.
+fn treasury() -> ProposalCategoryConfig {
+    ProposalCategoryConfig {
+        name: "treasury".to_string(),
+        vote_threshold: 75,
+        quorum: 100,
+        min_voting_period: 3600,
+        max_voting_period: 86_400,
+        timelock_delay: 7200,
+        programs: vec![system_program::ID],
+        emergency: false,
+    }
+}
+
+fn configure(dao: &mut Dao, id: u8, config: ProposalCategoryConfig) -> TxResult {
+    dao.send(ix::configure_proposal_category(dao.wallet(), dao.payer(), id, config), &[])
+}
+
+fn treasury_proposal(dao: &mut Dao) -> Pubkey {
+    let proposer = dao.add_member(0);
+    dao.try_create_proposal_in(&proposer, Some(TREASURY), vec![mock_instruction()]).unwrap()
+}
+
+#[test]
+fn configuring_a_category_stores_its_terms() {
+    let mut dao = Dao::new();
+    configure(&mut dao, TREASURY, treasury()).unwrap();
+
+    let category: ProposalCategory = dao.ctx.account(&pda::find_proposal_category_address(&dao.governor, TREASURY).0);
+    assert_eq!((category.governor, category.id, category.name.as_str()), (dao.governor, TREASURY, "treasury"));
+    assert_eq!((category.vote_threshold, category.quorum, category.timelock_delay), (75, 100, 7200));
+    assert_eq!(category.programs, vec![system_program::ID]);
+    assert_eq!(dao.governor().category_count, 1);
+    let event = &dao.ctx.events::<ProposalCategoryConfigured>()[0];
+    assert_eq!((event.id, event.quorum), (TREASURY, 100));
+
+    configure(&mut dao, TREASURY, ProposalCategoryConfig { quorum: 500, ..treasury() }).unwrap();
+    let category: ProposalCategory = dao.ctx.account(&pda::find_proposal_category_address(&dao.governor, TREASURY).0);
+    assert_eq!(category.quorum, 500);
+    assert_eq!(dao.governor().category_count, 1);
+}
+
+#[test]
+fn invalid_categories_are_rejected() {
+    let mut dao = Dao::new();
+    let invalid = [
+        ProposalCategoryConfig { name: String::new(), ..treasury() },
+        ProposalCategoryConfig { name: "x".repeat(ProposalCategory::MAX_NAME_LEN + 1), ..treasury() },
+        ProposalCategoryConfig { vote_threshold: 101, ..treasury() },
+        ProposalCategoryConfig { min_voting_period: 0, ..treasury() },
+        ProposalCategoryConfig { min_voting_period: 7200, max_voting_period: 3600, ..treasury() },
+        ProposalCategoryConfig { timelock_delay: -1, ..treasury() },
+        ProposalCategoryConfig { programs: vec![], ..treasury() },
+        ProposalCategoryConfig { programs: vec![system_program::ID; ProposalCategory::MAX_PROGRAMS + 1], ..treasury() },
+    ];
+    for config in invalid {
+        assert_error(configure(&mut dao, TREASURY, config), ErrorCode::InvalidProposalCategory);
+    }
+    assert_eq!(dao.governor().category_count, 0);
+}
+
+#[test]
+fn proposals_must_name_a_category_once_one_exists() {
+    let mut dao = Dao::builder().category(TREASURY, treasury()).build();
+    let proposer = dao.add_member(0);
+
+    let result = dao.try_create_proposal_in(&proposer, None, vec![mock_instruction()]).map(|_| ());
+    assert_error(result, ErrorCode::ProposalCategoryRequired);
+    let proposal = dao.try_create_proposal_in(&proposer, Some(TREASURY), vec![mock_instruction()]).unwrap();
+    assert_eq!(dao.proposal(&proposal).category, Some(TREASURY));
+    assert_eq!(dao.ctx.events::<ProposalCreated>()[0].category, Some(TREASURY));
+}
+
+#[test]
+fn proposals_may_only_call_the_category_programs() {
+    let mut dao = Dao::builder().category(TREASURY, treasury()).build();
+    let proposer = dao.add_member(0);
+    let outside = ProposalInstruction { program_id: Pubkey::new_unique(), ..mock_instruction() };
+
+    let result = dao.try_create_proposal_in(&proposer, Some(TREASURY), vec![mock_instruction(), outside.clone()]);
+    assert_error(result.map(|_| ()), ErrorCode::InstructionOutsideCategory);
+
+    let options = vec![
+        NewProposalOption { label: "Pay".to_string(), instructions: vec![mock_instruction()] },
+        NewProposalOption { label: "Swap".to_string(), instructions: vec![outside] },
+    ];
+    let proposal_id = dao.governor().proposal_count;
+    let ix = ix::create_multiple_choice_proposal(
+        dao.governor,
+        proposal_id,
+        proposer.pubkey(),
+        dao.payer(),
+        Some(TREASURY),
+        options,
+        OptionSelection::Plurality,
+    );
+    assert_error(dao.send(ix, &[&proposer]), ErrorCode::InstructionOutsideCategory);
+}
+
+#[test]
+fn category_threshold_and_quorum_decide_the_outcome() {
+    let mut dao = Dao::builder().category(TREASURY, treasury()).build();
+    let outcome = |dao: &mut Dao, votes: &[(VoteSide, u64)]| {
+        let proposal = treasury_proposal(dao);
+        dao.activate(proposal, 3600).unwrap();
+        for (side, weight) in votes {
+            let voter = dao.add_member(0);
+            dao.vote(proposal, &voter, side.clone(), *weight).unwrap();
+        }
+        dao.ctx.warp(3600);
+        dao.finalise(proposal).unwrap();
+        dao.proposal(&proposal).state
+    };
+
+    // 70% clears the governor's 60% but not the category's 75%.
+    let short_of_threshold = outcome(&mut dao, &[(VoteSide::For {}, 70), (VoteSide::Against {}, 30)]);
+    assert!(short_of_threshold == ProposalState::Rejected);
+    let short_of_quorum = outcome(&mut dao, &[(VoteSide::For {}, 60), (VoteSide::Abstain {}, 30)]);
+    assert!(short_of_quorum == ProposalState::Rejected);
+    let passed = outcome(&mut dao, &[(VoteSide::For {}, 80), (VoteSide::Abstain {}, 20)]);
+    assert!(passed == ProposalState::Succeeded);
+}
+
+#[test]
+fn multiple_choice_needs_the_quorum_for_a_winner() {
+    let mut dao = Dao::builder().category(TREASURY, treasury()).build();
+    let proposer = dao.add_member(0);
+    let options = vec![
+        NewProposalOption { label: "A".to_string(), instructions: vec![mock_instruction()] },
+        NewProposalOption { label: "B".to_string(), instructions: vec![] },
+    ];
+    let proposal_id = dao.governor().proposal_count;
+    let ix = ix::create_multiple_choice_proposal(
+        dao.governor,
+        proposal_id,
+        proposer.pubkey(),
+        dao.payer(),
+        Some(TREASURY),
+        options,
+        OptionSelection::Plurality,
+    );
+    dao.send(ix, &[&proposer]).unwrap();
+    let proposal = pda::find_proposal_address(&dao.governor, proposal_id).0;
+    dao.activate(proposal, 3600).unwrap();
+    dao.vote(proposal, &proposer, VoteSide::Choice { index: 0 }, 99).unwrap();
+    dao.ctx.warp(3600);
+    dao.finalise(proposal).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert!(account.state == ProposalState::Rejected);
+    assert_eq!(account.winning_option, None);
+}
+
+#[test]
+fn voting_period_must_fit_the_category() {
+    let mut dao = Dao::builder().category(TREASURY, treasury()).build();
+    let proposal = treasury_proposal(&mut dao);
+
+    assert_error(dao.activate(proposal, 3599), ErrorCode::InvalidVotingPeriod);
+    assert_error(dao.activate(proposal, 86_401), ErrorCode::InvalidVotingPeriod);
+    dao.activate(proposal, 86_400).unwrap();
+}
+
+#[test]
+fn default_voting_period_is_brought_within_the_category() {
+    const DAY: i64 = 86_400;
+    let mut dao = Dao::builder().category(TREASURY, treasury()).build();
+    let activate_by_default = |dao: &mut Dao, proposal| {
+        dao.send(ix::activate_proposal(dao.wallet(), proposal, None), &[]).unwrap();
+        dao.proposal(&proposal).voting_period
+    };
+
+    // The governor's three-day default is longer than the treasury allows.
+    let proposal = treasury_proposal(&mut dao);
+    assert_eq!(activate_by_default(&mut dao, proposal), DAY);
+
+    let slow = ProposalCategoryConfig { min_voting_period: 7 * DAY, max_voting_period: 14 * DAY, ..treasury() };
+    configure(&mut dao, TREASURY, slow).unwrap();
+    let proposal = treasury_proposal(&mut dao);
+    assert_eq!(activate_by_default(&mut dao, proposal), 7 * DAY);
+}
+
+#[test]
+fn category_timelock_applies_when_queued() {
+    let mut dao = Dao::builder().category(TREASURY, treasury()).build();
+    let proposal = treasury_proposal(&mut dao);
+    dao.activate(proposal, 3600).unwrap();
+    let voter = dao.add_member(0);
+    dao.vote(proposal, &voter, VoteSide::For {}, 100).unwrap();
+    dao.ctx.warp(3600);
+    dao.finalise(proposal).unwrap();
+    dao.queue(proposal).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert_eq!(account.ready_to_execute_at - account.queued_at, 7200);
+}
+
+#[test]
+fn reconfiguring_leaves_existing_proposals_alone() {
+    let mut dao = Dao::builder().category(TREASURY, treasury()).build();
+    let proposal = treasury_proposal(&mut dao);
+    configure(&mut dao, TREASURY, ProposalCategoryConfig { vote_threshold: 90, quorum: 0, ..treasury() }).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.vote_threshold, account.quorum, account.timelock_delay), (75, 100, 7200));
+    let later = treasury_proposal(&mut dao);
+    assert_eq!((dao.proposal(&later).vote_threshold, dao.proposal(&later).quorum), (90, 0));
+}
@@ -0,0 +1,372 @@
+//! Ports of `tests/test-case-2.ts` through `tests/test-case-5.ts`.
+
+mod common;
+
+use anchor_lang::error::ErrorCode as AnchorError;
+use common::{assert_error, ix, mock_instruction, Dao, TxError};
+use iterative_dao::{
+    enums::{ProposalState, VoteSide},
+    errors::ErrorCode,
+    events::{
+        ProposalActivated, ProposalCanceled, ProposalCreated, ProposalExecuted, ProposalFinalised, ProposalQueued,
+        EVENT_VERSION,
+    },
+    state::{ProposalAccount, ProposalInstruction},
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+//  Create Proposal (test case 2)
+
+#[test]
+fn test_case_2_1_create_proposal_with_valid_instructions() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal_key = dao.create_proposal(&proposer);
+
+    let proposal = dao.proposal(&proposal_key);
+    assert_eq!(proposal.governor, dao.governor);
+    assert_eq!(proposal.proposer, proposer.pubkey());
+    assert_eq!(proposal.proposal_id, 0);
+    assert_eq!(proposal.instructions.len(), 1);
+    assert_eq!(proposal.timelock_delay, 3600);
+    assert_eq!((proposal.for_votes, proposal.against_votes, proposal.abstain_votes), (0, 0, 0));
+
+    let event = &dao.ctx.events::<ProposalCreated>()[0];
+    assert_eq!(event.version, EVENT_VERSION);
+    assert_eq!((event.governor, event.proposal, event.proposer), (dao.governor, proposal_key, proposer.pubkey()));
+    assert_eq!((event.proposal_id, event.instruction_count), (0, 1));
+}
+
+#[test]
+fn test_case_2_3_proposal_count_increments() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let first = dao.create_proposal(&proposer);
+    let second = dao.create_proposal(&proposer);
+
+    assert_eq!(dao.governor().proposal_count, 2);
+    assert_eq!(dao.proposal(&first).proposal_id, 0);
+    assert_eq!(dao.proposal(&second).proposal_id, 1);
+}
+
+#[test]
+fn test_case_2_4_create_without_proposer_signature_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let ix = ix::create_proposal(dao.governor, 0, proposer.pubkey(), dao.payer(), None, vec![mock_instruction()]);
+
+    assert_eq!(dao.send(ix, &[]), Err(TxError::MissingSignature(proposer.pubkey())));
+}
+
+#[test]
+fn test_case_2_5_create_with_empty_instructions_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+
+    let result = dao.try_create_proposal(&proposer, vec![]).map(|_| ());
+    assert_error(result, ErrorCode::InvalidInstructions);
+}
+
+#[test]
+fn test_case_2_6_proposal_starts_as_draft() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+
+    assert!(dao.proposal(&proposal).state == ProposalState::Draft);
+}
+
+#[test]
+fn test_case_2_7_smart_wallet_instructions_are_stored() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let target = Keypair::new().pubkey();
+    let instructions = vec![ProposalInstruction {
+        program_id: iterative_dao::ID,
+        accounts: vec![ProposalAccount { pubkey: target, is_signer: true, is_writable: false }],
+        data: vec![9, 8, 7, 6],
+    }];
+    let proposal = dao.try_create_proposal(&proposer, instructions).unwrap();
+
+    let stored = &dao.proposal(&proposal).instructions[0];
+    assert_eq!(stored.program_id, iterative_dao::ID);
+    assert_eq!(stored.accounts[0].pubkey, target);
+    assert!(stored.accounts[0].is_signer);
+    assert!(!stored.accounts[0].is_writable);
+    assert_eq!(stored.data, vec![9, 8, 7, 6]);
+}
+
+#[test]
+fn create_by_non_member_fails() {
+    let mut dao = Dao::new();
+    let outsider = Keypair::new();
+
+    let result = dao.try_create_proposal(&outsider, vec![mock_instruction()]).map(|_| ());
+    assert_error(result, ErrorCode::UnauthorisedVoter);
+}
+
+//  Activate Proposal (test case 3)
+
+#[test]
+fn test_case_3_1_activate_proposal() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+    dao.activate(proposal, 3600).unwrap();
+
+    let proposal_account = dao.proposal(&proposal);
+    assert!(proposal_account.state == ProposalState::Active);
+    assert_eq!(proposal_account.voting_period, 3600);
+    assert_eq!(proposal_account.activated_at, dao.ctx.now());
+
+    let events = dao.ctx.events::<ProposalActivated>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].proposal, proposal);
+    assert_eq!((events[0].governor, events[0].proposal_id), (dao.governor, 0));
+}
+
+#[test]
+fn test_case_3_3_activate_by_unauthorised_wallet_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+    let impostor = Keypair::new();
+    let mut ix = ix::activate_proposal(dao.wallet(), proposal, Some(3600));
+    ix.accounts[2].pubkey = impostor.pubkey();
+
+    assert_error(dao.ctx.send(ix, &[&impostor]), AnchorError::ConstraintHasOne);
+}
+
+#[test]
+fn test_case_3_4_activate_twice_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+    dao.activate(proposal, 3600).unwrap();
+
+    assert_error(dao.activate(proposal, 3600), AnchorError::ConstraintRaw);
+}
+
+#[test]
+fn test_case_3_5_timelock_copied_from_governor() {
+    let mut dao = Dao::builder().timelock_delay(7200).build();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+    dao.activate(proposal, 3600).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).timelock_delay, 7200);
+}
+
+#[test]
+fn test_case_3_6_activate_without_smart_wallet_signature_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+    let ix = ix::activate_proposal(dao.wallet(), proposal, Some(3600));
+
+    assert_eq!(dao.ctx.send(ix, &[]), Err(TxError::MissingSignature(dao.wallet())));
+}
+
+#[test]
+fn activate_with_non_positive_period_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+
+    assert_error(dao.activate(proposal, 0), ErrorCode::InvalidVotingPeriod);
+}
+
+//  Cancel Proposal (test case 4)
+
+#[test]
+fn test_case_4_1_cancel_draft_proposal() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+    dao.send(ix::cancel_proposal(dao.wallet(), proposal, proposer.pubkey()), &[])
+        .unwrap();
+
+    assert!(dao.proposal(&proposal).state == ProposalState::Canceled);
+    let events = dao.ctx.events::<ProposalCanceled>();
+    assert_eq!(events[0].canceled_by, proposer.pubkey());
+}
+
+#[test]
+fn test_case_4_2_cancel_by_non_proposer_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let other = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+
+    let result = dao.send(ix::cancel_proposal(dao.wallet(), proposal, other.pubkey()), &[]);
+    assert_error(result, AnchorError::ConstraintRaw);
+}
+
+#[test]
+fn test_case_4_3_cancel_active_proposal_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+    dao.activate(proposal, 3600).unwrap();
+
+    let result = dao.send(ix::cancel_proposal(dao.wallet(), proposal, proposer.pubkey()), &[]);
+    assert_error(result, AnchorError::ConstraintRaw);
+}
+
+#[test]
+fn test_case_4_4_canceled_proposal_cannot_be_activated() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+    dao.send(ix::cancel_proposal(dao.wallet(), proposal, proposer.pubkey()), &[])
+        .unwrap();
+
+    assert_error(dao.activate(proposal, 3600), AnchorError::ConstraintRaw);
+}
+
+#[test]
+fn test_case_4_6_canceled_proposal_cannot_be_recreated() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+    dao.send(ix::cancel_proposal(dao.wallet(), proposal, proposer.pubkey()), &[])
+        .unwrap();
+
+    let ix = ix::create_proposal(dao.governor, 0, proposer.pubkey(), dao.payer(), None, vec![mock_instruction()]);
+    assert_error(dao.send(ix, &[&proposer]), AnchorError::ConstraintSeeds);
+}
+
+#[test]
+fn test_case_4_7_second_cancellation_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+    let cancel = ix::cancel_proposal(dao.wallet(), proposal, proposer.pubkey());
+    dao.send(cancel.clone(), &[]).unwrap();
+
+    assert_error(dao.send(cancel, &[]), AnchorError::ConstraintRaw);
+    assert!(dao.proposal(&proposal).state == ProposalState::Canceled);
+}
+
+//  Queue and execute (test case 5)
+
+#[test]
+fn test_case_5_1_queue_succeeded_proposal() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.passed_proposal(&proposer, 3600);
+    dao.queue(proposal).unwrap();
+
+    let proposal_account = dao.proposal(&proposal);
+    assert!(proposal_account.state == ProposalState::Queued);
+    assert_eq!(proposal_account.queued_at, dao.ctx.now());
+    assert_eq!(proposal_account.ready_to_execute_at, dao.ctx.now() + 3600);
+    assert_eq!(dao.ctx.events::<ProposalQueued>().len(), 1);
+}
+
+#[test]
+fn test_case_5_2_queue_non_succeeded_proposal_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+
+    assert_error(dao.queue(proposal), AnchorError::ConstraintRaw);
+}
+
+#[test]
+fn test_case_5_4_queue_without_smart_wallet_signature_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.passed_proposal(&proposer, 3600);
+
+    let result = dao.ctx.send(ix::queue_proposal(dao.wallet(), proposal), &[]);
+    assert_eq!(result, Err(TxError::MissingSignature(dao.wallet())));
+}
+
+#[test]
+fn test_case_5_5_requeue_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.passed_proposal(&proposer, 3600);
+    dao.queue(proposal).unwrap();
+
+    assert_error(dao.queue(proposal), AnchorError::ConstraintRaw);
+}
+
+#[test]
+fn test_case_5_6_queue_multiple_proposals_independently() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let first = dao.passed_proposal(&proposer, 3600);
+    let second = dao.passed_proposal(&proposer, 3600);
+    dao.queue(first).unwrap();
+    dao.queue(second).unwrap();
+
+    assert!(dao.proposal(&first).state == ProposalState::Queued);
+    assert!(dao.proposal(&second).state == ProposalState::Queued);
+}
+
+#[test]
+fn finalise_before_voting_period_ends_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.create_proposal(&proposer);
+    dao.activate(proposal, 3600).unwrap();
+    dao.ctx.warp(3599);
+
+    assert_error(dao.finalise(proposal), ErrorCode::VotingPeriodActive);
+}
+
+#[test]
+fn finalise_rejects_proposal_below_threshold() {
+    let mut dao = Dao::new();
+    let yes = dao.add_member(100);
+    let no = dao.add_member(100);
+    let proposal = dao.create_proposal(&yes);
+    dao.activate(proposal, 3600).unwrap();
+    dao.vote(proposal, &yes, VoteSide::For {}, 50).unwrap();
+    dao.vote(proposal, &no, VoteSide::Against {}, 50).unwrap();
+    dao.ctx.warp(3600);
+    dao.finalise(proposal).unwrap();
+
+    let proposal_account = dao.proposal(&proposal);
+    assert!(proposal_account.state == ProposalState::Rejected);
+    assert_eq!(proposal_account.finalised_at, dao.ctx.now());
+
+    let event = &dao.ctx.events::<ProposalFinalised>()[0];
+    assert!(event.state == ProposalState::Rejected);
+    assert_eq!((event.for_votes, event.against_votes, event.abstain_votes), (50, 50, 0));
+    assert_eq!(event.finalised_at, dao.ctx.now());
+}
+
+#[test]
+fn execute_respects_timelock() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.passed_proposal(&proposer, 3600);
+    dao.queue(proposal).unwrap();
+
+    dao.ctx.warp(3599);
+    assert_error(dao.execute(proposal), ErrorCode::TimelockNotExpired);
+
+    dao.ctx.warp(1);
+    dao.execute(proposal).unwrap();
+    assert!(dao.proposal(&proposal).state == ProposalState::Executed);
+    let event = &dao.ctx.events::<ProposalExecuted>()[0];
+    assert_eq!((event.proposal, event.executed_at), (proposal, dao.ctx.now()));
+}
+
+#[test]
+fn execute_through_another_governor_fails() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(100);
+    let proposal = dao.passed_proposal(&proposer, 3600);
+    dao.queue(proposal).unwrap();
+    dao.ctx.warp(3600);
+
+    let other = Keypair::new();
+    let init = ix::init_governor(other.pubkey(), dao.payer(), 60, 0, other.pubkey(), dao.governance_mint);
+    dao.ctx.send(init, &[&other]).unwrap();
+    assert_error(dao.ctx.send(ix::execute_proposal(other.pubkey(), proposal), &[&other]), AnchorError::ConstraintRaw);
+    dao.execute(proposal).unwrap();
+}
@@ -0,0 +1,108 @@
+//! Quadratic vote counting selected per governor.
+
+mod common;
+
+use common::{ix, pda, Dao, TxError};
+use iterative_dao::{
+    enums::{ProposalState, VoteSide, VotingStrategy},
+    events::{VoteSetEvent, VotingStrategySet},
+    state::Vote,
+};
+use solana_sdk::signature::Signer;
+
+#[test]
+fn governor_defaults_to_linear() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(0);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &voter, VoteSide::For {}, 100).unwrap();
+
+    assert!(dao.governor().voting_strategy == VotingStrategy::Linear);
+    assert_eq!(dao.proposal(&proposal).for_votes, 100);
+}
+
+#[test]
+fn set_voting_strategy_emits_event() {
+    let dao = Dao::builder().strategy(VotingStrategy::Quadratic).build();
+
+    assert!(dao.governor().voting_strategy == VotingStrategy::Quadratic);
+    let event = &dao.ctx.events::<VotingStrategySet>()[0];
+    assert_eq!(event.governor, dao.governor);
+    assert!(event.voting_strategy == VotingStrategy::Quadratic);
+}
+
+#[test]
+fn set_voting_strategy_without_smart_wallet_signature_fails() {
+    let mut dao = Dao::new();
+    let result = dao.ctx.send(ix::set_voting_strategy(dao.wallet(), VotingStrategy::Quadratic), &[]);
+
+    assert_eq!(result, Err(TxError::MissingSignature(dao.wallet())));
+    assert!(dao.governor().voting_strategy == VotingStrategy::Linear);
+}
+
+#[test]
+fn cast_vote_counts_square_root_of_power() {
+    let mut dao = Dao::builder().strategy(VotingStrategy::Quadratic).build();
+    let voter = dao.add_member(0);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &voter, VoteSide::For {}, 99).unwrap();
+
+    // Rounded down: 9 * 9 <= 99 < 10 * 10.
+    assert_eq!(dao.proposal(&proposal).for_votes, 9);
+    let vote: Vote = dao.ctx.account(&pda::find_vote_address(&proposal, &voter.pubkey()).0);
+    assert_eq!(vote.weight, 9);
+    assert_eq!(dao.ctx.events::<VoteSetEvent>()[0].weight, 9);
+}
+
+#[test]
+fn largest_power_does_not_overflow() {
+    let mut dao = Dao::builder().strategy(VotingStrategy::Quadratic).build();
+    let voter = dao.add_member(0);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &voter, VoteSide::For {}, u64::MAX).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).for_votes, u32::MAX as u64);
+}
+
+#[test]
+fn set_vote_recounts_registered_weight() {
+    let mut dao = Dao::builder().strategy(VotingStrategy::Quadratic).build();
+    let voter = dao.add_member(400);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &voter, VoteSide::For {}, 400).unwrap();
+    dao.change(proposal, &voter, VoteSide::Against {}).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.for_votes, account.against_votes), (0, 20));
+}
+
+#[test]
+fn many_small_holders_outvote_a_whale() {
+    let mut dao = Dao::builder().strategy(VotingStrategy::Quadratic).build();
+    let whale = dao.add_member(0);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &whale, VoteSide::Against {}, 900).unwrap();
+    for _ in 0..5 {
+        let member = dao.add_member(0);
+        dao.vote(proposal, &member, VoteSide::For {}, 100).unwrap();
+    }
+    dao.ctx.warp(3600);
+    dao.finalise(proposal).unwrap();
+
+    // 50 of 80 counted votes clears the 60% threshold; 500 of 1400 would not.
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.for_votes, account.against_votes), (50, 30));
+    assert!(account.state == ProposalState::Succeeded);
+}
+
+#[test]
+fn existing_proposals_keep_their_strategy() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(0);
+    let proposal = dao.active_proposal(3600);
+    dao.send(ix::set_voting_strategy(dao.wallet(), VotingStrategy::Quadratic), &[]).unwrap();
+    dao.vote(proposal, &voter, VoteSide::For {}, 100).unwrap();
+
+    assert!(dao.proposal(&proposal).voting_strategy == VotingStrategy::Linear);
+    assert_eq!(dao.proposal(&proposal).for_votes, 100);
+}
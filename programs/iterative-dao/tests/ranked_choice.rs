@@ -0,0 +1,221 @@
+//! Ranked-choice ballots and instant-runoff tallying.
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::{assert_error, ix, mock_instruction, pda, Dao, TxResult};
+use iterative_dao::{
+    enums::{OptionSelection, ProposalState, VoteSide},
+    errors::ErrorCode,
+    events::{ProposalFinalised, RankedRoundTallied},
+    state::{NewProposalOption, Vote},
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+/// An active instant-runoff election between three candidates.
+fn election(dao: &mut Dao) -> Pubkey {
+    let proposer = dao.add_member(0);
+    let options = ["Alice", "Bob", "Carol"]
+        .iter()
+        .map(|label| NewProposalOption { label: label.to_string(), instructions: vec![mock_instruction()] })
+        .collect();
+    let proposal = dao
+        .try_create_multiple_choice_proposal(&proposer, options, OptionSelection::InstantRunoff)
+        .unwrap();
+    dao.activate(proposal, 3600).unwrap();
+    proposal
+}
+
+fn ranked_vote(dao: &mut Dao, proposal: Pubkey, voter: &Keypair, ranking: &[u8], weight: u64) -> TxResult {
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[voter])?;
+    let cast = ix::cast_ranked_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), ranking.to_vec(), weight);
+    dao.send(cast, &[voter])
+}
+
+/// Casts each `(ranking, weight)` ballot from a fresh member; returns the vote accounts.
+fn ballots(dao: &mut Dao, proposal: Pubkey, ballots: &[(&[u8], u64)]) -> Vec<Pubkey> {
+    ballots
+        .iter()
+        .map(|(ranking, weight)| {
+            let voter = dao.add_member(0);
+            ranked_vote(dao, proposal, &voter, ranking, *weight).unwrap();
+            pda::find_vote_address(&proposal, &voter.pubkey()).0
+        })
+        .collect()
+}
+
+fn option_votes(dao: &Dao, proposal: &Pubkey) -> Vec<u64> {
+    dao.proposal(proposal).options.iter().map(|o| o.votes).collect()
+}
+
+//  Casting
+
+#[test]
+fn first_preferences_are_tallied_when_cast() {
+    let mut dao = Dao::new();
+    let proposal = election(&mut dao);
+    let votes = ballots(&mut dao, proposal, &[(&[0, 1], 40), (&[1, 0], 35), (&[2, 1], 25)]);
+
+    assert_eq!(option_votes(&dao, &proposal), vec![40, 35, 25]);
+    assert_eq!(dao.proposal(&proposal).ranked_ballots, 3);
+    let vote: Vote = dao.ctx.account(&votes[2]);
+    assert_eq!(vote.ranking, vec![2, 1]);
+    assert_eq!(vote.side, VoteSide::Choice { index: 2 });
+}
+
+#[test]
+fn invalid_rankings_fail() {
+    let mut dao = Dao::new();
+    let proposal = election(&mut dao);
+    for ranking in [&[][..], &[0, 0], &[1, 3]] {
+        let voter = dao.add_member(0);
+        assert_error(ranked_vote(&mut dao, proposal, &voter, ranking, 10), ErrorCode::InvalidRanking);
+    }
+}
+
+#[test]
+fn ranked_vote_on_plurality_proposal_fails() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(0);
+    let options = vec![
+        NewProposalOption { label: "A".into(), instructions: vec![] },
+        NewProposalOption { label: "B".into(), instructions: vec![] },
+    ];
+    let proposal = dao
+        .try_create_multiple_choice_proposal(&voter, options, OptionSelection::Plurality)
+        .unwrap();
+    dao.activate(proposal, 3600).unwrap();
+
+    assert_error(ranked_vote(&mut dao, proposal, &voter, &[0, 1], 10), ErrorCode::InvalidRanking);
+}
+
+#[test]
+fn single_choice_vote_on_ranked_proposal_fails() {
+    let mut dao = Dao::new();
+    let proposal = election(&mut dao);
+    let voter = dao.add_member(10);
+
+    assert_error(dao.vote(proposal, &voter, VoteSide::Choice { index: 0 }, 10), ErrorCode::InvalidVoteOption);
+}
+
+#[test]
+fn ranked_ballots_cannot_be_withdrawn() {
+    let mut dao = Dao::new();
+    let proposal = election(&mut dao);
+    let voter = dao.add_member(0);
+    ranked_vote(&mut dao, proposal, &voter, &[1, 0], 30).unwrap();
+
+    let withdraw = ix::withdraw_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer());
+    assert_error(dao.send(withdraw, &[&voter]), ErrorCode::RankedVoteNotWithdrawable);
+    assert_eq!(option_votes(&dao, &proposal), vec![0, 30, 0]);
+}
+
+//  Tallying
+
+#[test]
+fn first_round_majority_decides_at_finalisation() {
+    let mut dao = Dao::new();
+    let proposal = election(&mut dao);
+    ballots(&mut dao, proposal, &[(&[0], 60), (&[1, 0], 40)]);
+    dao.ctx.warp(3600);
+    dao.finalise(proposal).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert!(account.state == ProposalState::Succeeded);
+    assert_eq!(account.winning_option, Some(0));
+}
+
+#[test]
+fn runoff_transfers_eliminated_ballots() {
+    let mut dao = Dao::new();
+    let proposal = election(&mut dao);
+    let votes = ballots(&mut dao, proposal, &[(&[0, 1], 40), (&[1, 0], 35), (&[2, 1], 25)]);
+    dao.ctx.warp(3600);
+
+    dao.finalise(proposal).unwrap();
+    let account = dao.proposal(&proposal);
+    assert!(account.state == ProposalState::Active);
+    assert_eq!((account.tally_round, account.eliminated_options), (1, 0b100));
+    let round = &dao.ctx.events::<RankedRoundTallied>()[0];
+    assert_eq!((round.option_votes.clone(), round.eliminated_option), (vec![40, 35, 25], 2));
+
+    dao.send(ix::tally_ranked_votes(proposal, &votes), &[]).unwrap();
+    let account = dao.proposal(&proposal);
+    assert!(account.state == ProposalState::Succeeded);
+    assert_eq!(account.winning_option, Some(1));
+    assert_eq!(option_votes(&dao, &proposal), vec![40, 60, 0]);
+    assert_eq!(dao.ctx.events::<ProposalFinalised>()[0].winning_option, Some(1));
+}
+
+#[test]
+fn tally_spans_multiple_transactions() {
+    let mut dao = Dao::new();
+    let proposal = election(&mut dao);
+    let votes = ballots(&mut dao, proposal, &[(&[0, 1], 40), (&[1, 0], 35), (&[2, 1], 25)]);
+    dao.ctx.warp(3600);
+
+    dao.send(ix::tally_ranked_votes(proposal, &votes[2..]), &[]).unwrap();
+    // Resending a counted ballot does not count it twice.
+    dao.send(ix::tally_ranked_votes(proposal, &votes[2..]), &[]).unwrap();
+    let account = dao.proposal(&proposal);
+    assert!(account.state == ProposalState::Active);
+    assert_eq!((account.tally_round, account.tallied_ballots), (1, 1));
+
+    dao.send(ix::tally_ranked_votes(proposal, &votes[..2]), &[]).unwrap();
+    assert_eq!(dao.proposal(&proposal).winning_option, Some(1));
+}
+
+#[test]
+fn exhausted_ballots_drop_out() {
+    let mut dao = Dao::new();
+    let proposal = election(&mut dao);
+    let votes = ballots(&mut dao, proposal, &[(&[0], 40), (&[1], 35), (&[2], 25)]);
+    dao.ctx.warp(3600);
+    dao.send(ix::tally_ranked_votes(proposal, &votes), &[]).unwrap();
+
+    // 40 of the 75 ballots still live after Carol is eliminated.
+    assert_eq!(dao.proposal(&proposal).winning_option, Some(0));
+}
+
+#[test]
+fn election_without_ballots_is_rejected() {
+    let mut dao = Dao::new();
+    let proposal = election(&mut dao);
+    dao.ctx.warp(3600);
+    dao.finalise(proposal).unwrap();
+
+    assert!(dao.proposal(&proposal).state == ProposalState::Rejected);
+}
+
+#[test]
+fn tally_before_voting_ends_fails() {
+    let mut dao = Dao::new();
+    let proposal = election(&mut dao);
+    let votes = ballots(&mut dao, proposal, &[(&[0], 40)]);
+
+    assert_error(dao.send(ix::tally_ranked_votes(proposal, &votes), &[]), ErrorCode::VotingPeriodActive);
+}
+
+#[test]
+fn tally_with_foreign_vote_fails() {
+    let mut dao = Dao::new();
+    let proposal = election(&mut dao);
+    let other = election(&mut dao);
+    ballots(&mut dao, proposal, &[(&[0, 1], 40), (&[1, 0], 35), (&[2, 1], 25)]);
+    let foreign = ballots(&mut dao, other, &[(&[0], 10)]);
+    dao.ctx.warp(3600);
+    dao.finalise(proposal).unwrap();
+
+    let result = dao.send(ix::tally_ranked_votes(proposal, &foreign), &[]);
+    assert_error(result, ErrorCode::InvalidVoteAccount);
+}
+
+#[test]
+fn ranked_vote_after_the_deadline_fails() {
+    let mut dao = Dao::new();
+    let proposal = election(&mut dao);
+    let voter = dao.add_member(0);
+    dao.ctx.warp(4000);
+
+    assert_error(ranked_vote(&mut dao, proposal, &voter, &[0, 1], 10), ErrorCode::VotingPeriodEnded);
+}
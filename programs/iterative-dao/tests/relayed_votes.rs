@@ -0,0 +1,220 @@
+//! Off-chain signed votes submitted by a relayer and checked via ed25519.
+
+mod common;
+
+use anchor_lang::{prelude::Pubkey, solana_program::system_instruction::SystemError};
+use common::{assert_error, ix, pda, Dao, TxError, TxResult};
+use iterative_dao::{
+    enums::{VoteSide, VoteState, WeightSource},
+    errors::ErrorCode,
+    events::VoteSetEvent,
+    relay::relayed_vote_message,
+    state::{SignedVote, Vote},
+};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+type Signature = (Pubkey, [u8; 64], Vec<u8>);
+
+/// A listed voter holding no SOL.
+fn unfunded_member(dao: &mut Dao, weight: u64) -> Keypair {
+    let member = Keypair::new();
+    dao.send(ix::add_voter(dao.wallet(), member.pubkey(), weight), &[]).unwrap();
+    member
+}
+
+fn sign(proposal: Pubkey, voter: &Keypair, side: VoteSide, weight: u64) -> (Signature, SignedVote) {
+    let message = relayed_vote_message(&proposal, &side, weight);
+    let signature = voter.sign_message(&message).into();
+    ((voter.pubkey(), signature, message), SignedVote { voter: voter.pubkey(), side, weight })
+}
+
+fn relay(dao: &mut Dao, proposal: Pubkey, signed: Vec<(Signature, SignedVote)>) -> TxResult {
+    let (signatures, votes): (Vec<_>, Vec<_>) = signed.into_iter().unzip();
+    let instructions = [
+        ix::verify_ed25519_signatures(&signatures),
+        ix::cast_relayed_votes(dao.wallet(), proposal, dao.payer(), votes),
+    ];
+    let wallet = dao.smart_wallet.insecure_clone();
+    dao.ctx.process(&instructions, &[&wallet])
+}
+
+#[test]
+fn relayed_votes_are_tallied_for_voters_without_sol() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let (alice, bob) = (unfunded_member(&mut dao, 0), unfunded_member(&mut dao, 0));
+    let votes = vec![sign(proposal, &alice, VoteSide::For {}, 70), sign(proposal, &bob, VoteSide::Against {}, 30)];
+    relay(&mut dao, proposal, votes).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.for_votes, account.against_votes), (70, 30));
+    let vote: Vote = dao.ctx.account(&pda::find_vote_address(&proposal, &alice.pubkey()).0);
+    assert!(vote.state == VoteState::Cast);
+    assert_eq!((vote.power, vote.weight, vote.payer), (70, 70, dao.payer()));
+    assert_eq!(dao.ctx.lamports(&alice.pubkey()), 0);
+    let events = dao.ctx.events::<VoteSetEvent>();
+    assert_eq!(events.len(), 2);
+    assert_eq!((events[1].voter, events[1].weight), (bob.pubkey(), 30));
+}
+
+#[test]
+fn registry_weighting_ignores_the_signed_weight() {
+    let mut dao = Dao::new();
+    dao.send(ix::set_weight_source(dao.wallet(), WeightSource::Registry), &[]).unwrap();
+    let proposal = dao.active_proposal(3600);
+    let voter = unfunded_member(&mut dao, 40);
+    relay(&mut dao, proposal, vec![sign(proposal, &voter, VoteSide::For {}, 1_000)]).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).for_votes, 40);
+}
+
+#[test]
+fn votes_need_a_verified_signature() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = unfunded_member(&mut dao, 0);
+    let (_, vote) = sign(proposal, &voter, VoteSide::For {}, 10);
+
+    let relay_only = ix::cast_relayed_votes(dao.wallet(), proposal, dao.payer(), vec![vote]);
+    assert_error(dao.send(relay_only, &[]), ErrorCode::MissingVoteSignature);
+}
+
+#[test]
+fn signed_vote_cannot_be_altered() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = unfunded_member(&mut dao, 0);
+
+    let (signature, vote) = sign(proposal, &voter, VoteSide::For {}, 10);
+    let flipped = SignedVote { side: VoteSide::Against {}, ..vote.clone() };
+    assert_error(relay(&mut dao, proposal, vec![(signature.clone(), flipped)]), ErrorCode::MissingVoteSignature);
+    let inflated = SignedVote { weight: 1_000, ..vote };
+    assert_error(relay(&mut dao, proposal, vec![(signature, inflated)]), ErrorCode::MissingVoteSignature);
+}
+
+#[test]
+fn signature_for_another_proposal_is_rejected() {
+    let mut dao = Dao::new();
+    let first = dao.active_proposal(3600);
+    let second = dao.active_proposal(3600);
+    let voter = unfunded_member(&mut dao, 0);
+    let (signature, vote) = sign(first, &voter, VoteSide::For {}, 10);
+
+    assert_error(relay(&mut dao, second, vec![(signature, vote)]), ErrorCode::MissingVoteSignature);
+}
+
+#[test]
+fn forged_signature_fails_verification() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = unfunded_member(&mut dao, 0);
+    let forger = Keypair::new();
+    let ((_, signature, message), vote) = sign(proposal, &forger, VoteSide::For {}, 10);
+    let forged = ((voter.pubkey(), signature, message), SignedVote { voter: voter.pubkey(), ..vote });
+
+    // The runtime reports any failed precompile this way, before the program runs.
+    let result = relay(&mut dao, proposal, vec![forged]);
+    assert_eq!(result, Err(TxError::Transaction(TransactionError::InvalidAccountIndex)));
+}
+
+#[test]
+fn each_voter_votes_once() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = dao.add_member(0);
+    relay(&mut dao, proposal, vec![sign(proposal, &voter, VoteSide::For {}, 10)]).unwrap();
+
+    let replayed = relay(&mut dao, proposal, vec![sign(proposal, &voter, VoteSide::For {}, 10)]);
+    assert_error(replayed, ErrorCode::AlreadyVoted);
+    let create = ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer());
+    assert_error(dao.send(create, &[&voter]), SystemError::AccountAlreadyInUse as u32);
+    let other = dao.active_proposal(3600);
+    let twice = vec![sign(other, &voter, VoteSide::For {}, 10), sign(other, &voter, VoteSide::For {}, 10)];
+    assert_error(relay(&mut dao, other, twice), ErrorCode::AlreadyVoted);
+    assert_eq!(dao.proposal(&proposal).for_votes, 10);
+}
+
+#[test]
+fn on_chain_voters_cannot_also_be_relayed() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = dao.add_member(0);
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[&voter]).unwrap();
+
+    let result = relay(&mut dao, proposal, vec![sign(proposal, &voter, VoteSide::For {}, 10)]);
+    assert_error(result, ErrorCode::AlreadyVoted);
+}
+
+#[test]
+fn relayed_vote_can_be_changed_on_chain() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = dao.add_member(25);
+    relay(&mut dao, proposal, vec![sign(proposal, &voter, VoteSide::For {}, 25)]).unwrap();
+    dao.change(proposal, &voter, VoteSide::Against {}).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.for_votes, account.against_votes), (0, 25));
+}
+
+#[test]
+fn prefunded_vote_address_is_still_created() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = unfunded_member(&mut dao, 0);
+    let vote = pda::find_vote_address(&proposal, &voter.pubkey()).0;
+    dao.ctx.set_account(vote, Account { lamports: 1, ..Account::default() });
+    relay(&mut dao, proposal, vec![sign(proposal, &voter, VoteSide::For {}, 10)]).unwrap();
+
+    let stored: Vote = dao.ctx.account(&vote);
+    assert_eq!(stored.weight, 10);
+}
+
+#[test]
+fn vote_accounts_must_match_the_voters() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = unfunded_member(&mut dao, 0);
+    let (signature, vote) = sign(proposal, &voter, VoteSide::For {}, 10);
+    let mut relay_ix = ix::cast_relayed_votes(dao.wallet(), proposal, dao.payer(), vec![vote]);
+    relay_ix.accounts.last_mut().unwrap().pubkey = Pubkey::new_unique();
+
+    let wallet = dao.smart_wallet.insecure_clone();
+    let result = dao.ctx.process(&[ix::verify_ed25519_signatures(&[signature]), relay_ix], &[&wallet]);
+    assert_error(result, ErrorCode::InvalidVoteAccount);
+}
+
+#[test]
+fn non_members_and_unsupported_proposals_are_rejected() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let outsider = Keypair::new();
+    let result = relay(&mut dao, proposal, vec![sign(proposal, &outsider, VoteSide::For {}, 10)]);
+    assert_error(result, ErrorCode::UnauthorisedVoter);
+
+    let voter = unfunded_member(&mut dao, 0);
+    let proposer = dao.add_member(0);
+    let secret = dao.create_proposal(&proposer);
+    dao.send(ix::activate_secret_proposal(dao.wallet(), secret, Some(3600), 1800), &[]).unwrap();
+    let result = relay(&mut dao, secret, vec![sign(secret, &voter, VoteSide::For {}, 10)]);
+    assert_error(result, ErrorCode::SecretBallot);
+
+    dao.send(ix::set_weight_source(dao.wallet(), WeightSource::Locker), &[]).unwrap();
+    let result = relay(&mut dao, proposal, vec![sign(proposal, &voter, VoteSide::For {}, 10)]);
+    assert_error(result, ErrorCode::RelayedVotesUnsupported);
+}
+
+#[test]
+fn relayed_votes_after_the_deadline_fail() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = unfunded_member(&mut dao, 0);
+    dao.ctx.warp(4000);
+
+    let votes = vec![sign(proposal, &voter, VoteSide::For {}, 70)];
+    assert_error(relay(&mut dao, proposal, votes), ErrorCode::VotingPeriodEnded);
+}
@@ -0,0 +1,125 @@
+//! Pins the harness behaviour the other tests rely on.
+
+mod common;
+
+use anchor_lang::{
+    prelude::{Pubkey, Rent},
+    solana_program::system_instruction::{self, SystemError},
+};
+use common::{assert_error, ix, Dao, TestContext, TxError};
+use iterative_dao::events::ProposalActivated;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use spl_token::error::TokenError;
+
+const SOL: u64 = 1_000_000_000;
+
+#[test]
+fn failed_transactions_roll_back_earlier_instructions() {
+    let mut ctx = TestContext::new();
+    let payer = ctx.payer.pubkey();
+    let (recipient, poor) = (Pubkey::new_unique(), Keypair::new());
+    ctx.airdrop(&poor.pubkey(), SOL);
+    let before = ctx.lamports(&payer);
+
+    let instructions = [
+        system_instruction::transfer(&payer, &recipient, SOL),
+        system_instruction::transfer(&poor.pubkey(), &recipient, 2 * SOL),
+    ];
+    let err = ctx.process(&instructions, &[&poor]).unwrap_err();
+    assert_eq!(err.custom_code(), Some(SystemError::ResultWithNegativeLamports as u32));
+    assert!(matches!(err, TxError::Program { index: 1, .. }));
+    assert_eq!(ctx.lamports(&payer), before);
+    assert!(!ctx.exists(&recipient));
+}
+
+#[test]
+fn required_signers_must_sign() {
+    let mut ctx = TestContext::new();
+    let from = Keypair::new();
+    ctx.airdrop(&from.pubkey(), SOL);
+    let ix = system_instruction::transfer(&from.pubkey(), &Pubkey::new_unique(), SOL);
+    assert_eq!(ctx.send(ix.clone(), &[]), Err(TxError::MissingSignature(from.pubkey())));
+    ctx.send(ix, &[&from]).unwrap();
+}
+
+#[test]
+fn accounts_in_use_cannot_be_created_again() {
+    let mut ctx = TestContext::new();
+    let (payer, account) = (ctx.payer.pubkey(), Keypair::new());
+    let owner = iterative_dao::ID;
+    let create = system_instruction::create_account(&payer, &account.pubkey(), SOL, 40, &owner);
+    ctx.send(create.clone(), &[&account]).unwrap();
+
+    let created = ctx.raw_account(&account.pubkey()).unwrap();
+    assert_eq!((created.lamports, created.data.len(), created.owner), (SOL, 40, owner));
+    assert_error(ctx.send(create, &[&account]), SystemError::AccountAlreadyInUse as u32);
+}
+
+#[test]
+fn drained_accounts_are_removed() {
+    let mut ctx = TestContext::new();
+    let from = Keypair::new();
+    ctx.airdrop(&from.pubkey(), SOL);
+    ctx.send(system_instruction::transfer(&from.pubkey(), &ctx.payer.pubkey(), SOL), &[&from]).unwrap();
+    assert!(!ctx.exists(&from.pubkey()));
+}
+
+#[test]
+fn token_instructions_run_the_token_program() {
+    let mut ctx = TestContext::new();
+    let (owner, stranger) = (Keypair::new(), Keypair::new());
+    let mint = ctx.create_mint(&owner.pubkey(), 0);
+    let source = ctx.create_token_account(&mint, &owner.pubkey());
+    let destination = ctx.create_token_account(&mint, &stranger.pubkey());
+    ctx.mint_to(&source, 100);
+
+    let transfer = |authority: &Pubkey| {
+        spl_token::instruction::transfer(&spl_token::ID, &source, &destination, authority, &[], 40).unwrap()
+    };
+    assert_error(ctx.send(transfer(&stranger.pubkey()), &[&stranger]), TokenError::OwnerMismatch as u32);
+    ctx.send(transfer(&owner.pubkey()), &[&owner]).unwrap();
+    assert_eq!((ctx.token_balance(&source), ctx.token_balance(&destination)), (60, 40));
+}
+
+#[test]
+fn accounts_cannot_be_left_below_the_rent_exempt_minimum() {
+    let mut ctx = TestContext::new();
+    let (payer, recipient) = (ctx.payer.pubkey(), Pubkey::new_unique());
+    let minimum = Rent::default().minimum_balance(0);
+
+    let short = system_instruction::transfer(&payer, &recipient, minimum - 1);
+    let result = ctx.send(short, &[]);
+    let rejected = matches!(result, Err(TxError::Transaction(TransactionError::InsufficientFundsForRent { .. })));
+    assert!(rejected, "{result:?}");
+    assert!(!ctx.exists(&recipient));
+    ctx.send(system_instruction::transfer(&payer, &recipient, minimum), &[]).unwrap();
+}
+
+#[test]
+fn ed25519_instructions_verify_their_signatures() {
+    let mut ctx = TestContext::new();
+    let signer = Keypair::new();
+    let message = b"iterative-dao".to_vec();
+    let signature: [u8; 64] = signer.sign_message(&message).into();
+
+    let valid = ix::verify_ed25519_signatures(&[(signer.pubkey(), signature, message)]);
+    ctx.send(valid, &[]).unwrap();
+    let tampered = ix::verify_ed25519_signatures(&[(signer.pubkey(), signature, b"tampered".to_vec())]);
+    assert!(ctx.send(tampered, &[]).is_err());
+}
+
+#[test]
+fn programs_see_the_warped_clock() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(0);
+    let proposal = dao.create_proposal(&proposer);
+    dao.ctx.warp(1234);
+
+    dao.activate(proposal, 3600).unwrap();
+    assert_eq!(dao.ctx.events::<ProposalActivated>()[0].activated_at, dao.ctx.now());
+    dao.ctx.send(system_instruction::transfer(&dao.payer(), &Pubkey::new_unique(), SOL), &[]).unwrap();
+    assert!(dao.ctx.events::<ProposalActivated>().is_empty());
+}
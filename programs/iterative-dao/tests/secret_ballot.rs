@@ -0,0 +1,192 @@
+//! Commit-reveal voting on secret-ballot proposals.
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::{assert_error, ix, mock_instruction, pda, Dao, TxResult};
+use iterative_dao::{
+    enums::{OptionSelection, ProposalState, VoteSide, VoteState},
+    errors::ErrorCode,
+    events::{ProposalActivated, VoteCommitted, VoteSetEvent},
+    secret::ballot_commitment,
+    state::{NewProposalOption, Vote},
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+const VOTING: i64 = 3600;
+const REVEAL: i64 = 1800;
+
+fn secret_proposal(dao: &mut Dao) -> Pubkey {
+    let proposer = dao.add_member(0);
+    let proposal = dao.create_proposal(&proposer);
+    dao.send(ix::activate_secret_proposal(dao.wallet(), proposal, Some(VOTING), REVEAL), &[]).unwrap();
+    proposal
+}
+
+fn salt(byte: u8) -> [u8; 32] {
+    [byte; 32]
+}
+
+fn commit(dao: &mut Dao, proposal: Pubkey, voter: &Keypair, side: VoteSide, weight: u64) -> TxResult {
+    let vote = pda::find_vote_address(&proposal, &voter.pubkey()).0;
+    if !dao.ctx.exists(&vote) {
+        dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[voter])?;
+    }
+    let commitment = ballot_commitment(&proposal, &voter.pubkey(), &side, &salt(7));
+    dao.send(ix::commit_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), commitment, weight), &[voter])
+}
+
+fn reveal(dao: &mut Dao, proposal: Pubkey, voter: &Keypair, side: VoteSide, salt: [u8; 32]) -> TxResult {
+    dao.send(ix::reveal_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), side, salt), &[voter])
+}
+
+#[test]
+fn activate_secret_proposal_sets_reveal_period() {
+    let mut dao = Dao::new();
+    let proposal = secret_proposal(&mut dao);
+
+    assert_eq!(dao.proposal(&proposal).reveal_period, REVEAL);
+    assert_eq!(dao.ctx.events::<ProposalActivated>()[0].reveal_period, REVEAL);
+}
+
+#[test]
+fn instant_runoff_proposals_cannot_be_secret() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(0);
+    let options = ["Alice", "Bob"]
+        .iter()
+        .map(|label| NewProposalOption { label: label.to_string(), instructions: vec![mock_instruction()] })
+        .collect();
+    let proposal = dao
+        .try_create_multiple_choice_proposal(&proposer, options, OptionSelection::InstantRunoff)
+        .unwrap();
+
+    let result = dao.send(ix::activate_secret_proposal(dao.wallet(), proposal, Some(VOTING), REVEAL), &[]);
+    assert_error(result, ErrorCode::SecretBallotUnsupported);
+    assert!(dao.proposal(&proposal).state == ProposalState::Draft);
+}
+
+#[test]
+fn commitments_hide_side_and_tally() {
+    let mut dao = Dao::new();
+    let proposal = secret_proposal(&mut dao);
+    let voter = dao.add_member(0);
+    commit(&mut dao, proposal, &voter, VoteSide::For {}, 100).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.for_votes, account.against_votes, account.abstain_votes), (0, 0, 0));
+    let vote: Vote = dao.ctx.account(&pda::find_vote_address(&proposal, &voter.pubkey()).0);
+    assert!(vote.state == VoteState::Committed);
+    assert_eq!(dao.ctx.events::<VoteCommitted>()[0].voter, voter.pubkey());
+    assert!(dao.ctx.events::<VoteSetEvent>().is_empty());
+}
+
+#[test]
+fn revealed_votes_count_after_voting() {
+    let mut dao = Dao::new();
+    let proposal = secret_proposal(&mut dao);
+    let voter = dao.add_member(0);
+    commit(&mut dao, proposal, &voter, VoteSide::For {}, 100).unwrap();
+
+    assert_error(reveal(&mut dao, proposal, &voter, VoteSide::For {}, salt(7)), ErrorCode::VotingPeriodActive);
+    dao.ctx.warp(VOTING);
+    reveal(&mut dao, proposal, &voter, VoteSide::For {}, salt(7)).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).for_votes, 100);
+    assert_eq!(dao.ctx.events::<VoteSetEvent>()[0].weight, 100);
+}
+
+#[test]
+fn reveal_must_match_commitment() {
+    let mut dao = Dao::new();
+    let proposal = secret_proposal(&mut dao);
+    let voter = dao.add_member(0);
+    commit(&mut dao, proposal, &voter, VoteSide::For {}, 100).unwrap();
+    dao.ctx.warp(VOTING);
+
+    assert_error(reveal(&mut dao, proposal, &voter, VoteSide::Against {}, salt(7)), ErrorCode::CommitmentMismatch);
+    assert_error(reveal(&mut dao, proposal, &voter, VoteSide::For {}, salt(8)), ErrorCode::CommitmentMismatch);
+}
+
+#[test]
+fn recommitting_replaces_the_commitment() {
+    let mut dao = Dao::new();
+    let proposal = secret_proposal(&mut dao);
+    let voter = dao.add_member(0);
+    commit(&mut dao, proposal, &voter, VoteSide::For {}, 100).unwrap();
+    commit(&mut dao, proposal, &voter, VoteSide::Against {}, 100).unwrap();
+    dao.ctx.warp(VOTING);
+
+    assert_error(reveal(&mut dao, proposal, &voter, VoteSide::For {}, salt(7)), ErrorCode::CommitmentMismatch);
+    reveal(&mut dao, proposal, &voter, VoteSide::Against {}, salt(7)).unwrap();
+    assert_eq!(dao.proposal(&proposal).against_votes, 100);
+}
+
+#[test]
+fn copied_commitment_cannot_be_revealed() {
+    let mut dao = Dao::new();
+    let proposal = secret_proposal(&mut dao);
+    let author = dao.add_member(0);
+    let copier = dao.add_member(0);
+    commit(&mut dao, proposal, &author, VoteSide::For {}, 100).unwrap();
+    let copied = ballot_commitment(&proposal, &author.pubkey(), &VoteSide::For {}, &salt(7));
+    dao.send(ix::create_vote(dao.wallet(), proposal, copier.pubkey(), dao.payer()), &[&copier]).unwrap();
+    let ix = ix::commit_vote(dao.wallet(), proposal, copier.pubkey(), dao.payer(), copied, 100);
+    dao.send(ix, &[&copier]).unwrap();
+    dao.ctx.warp(VOTING);
+    reveal(&mut dao, proposal, &author, VoteSide::For {}, salt(7)).unwrap();
+
+    assert_error(reveal(&mut dao, proposal, &copier, VoteSide::For {}, salt(7)), ErrorCode::CommitmentMismatch);
+}
+
+#[test]
+fn commit_after_voting_fails() {
+    let mut dao = Dao::new();
+    let proposal = secret_proposal(&mut dao);
+    let voter = dao.add_member(0);
+    dao.ctx.warp(VOTING);
+
+    assert_error(commit(&mut dao, proposal, &voter, VoteSide::For {}, 100), ErrorCode::VotingPeriodEnded);
+}
+
+#[test]
+fn reveal_after_window_fails() {
+    let mut dao = Dao::new();
+    let proposal = secret_proposal(&mut dao);
+    let voter = dao.add_member(0);
+    commit(&mut dao, proposal, &voter, VoteSide::For {}, 100).unwrap();
+    dao.ctx.warp(VOTING + REVEAL);
+
+    assert_error(reveal(&mut dao, proposal, &voter, VoteSide::For {}, salt(7)), ErrorCode::RevealPeriodEnded);
+}
+
+#[test]
+fn finalise_waits_for_reveals_and_counts_only_revealed_votes() {
+    let mut dao = Dao::new();
+    let proposal = secret_proposal(&mut dao);
+    let (revealer, silent) = (dao.add_member(0), dao.add_member(0));
+    commit(&mut dao, proposal, &revealer, VoteSide::For {}, 60).unwrap();
+    commit(&mut dao, proposal, &silent, VoteSide::Against {}, 100).unwrap();
+    dao.ctx.warp(VOTING);
+    reveal(&mut dao, proposal, &revealer, VoteSide::For {}, salt(7)).unwrap();
+
+    assert_error(dao.finalise(proposal), ErrorCode::VotingPeriodActive);
+    dao.ctx.warp(REVEAL);
+    dao.finalise(proposal).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert!(account.state == ProposalState::Succeeded);
+    assert_eq!((account.for_votes, account.against_votes), (60, 0));
+}
+
+#[test]
+fn open_and_secret_voting_do_not_mix() {
+    let mut dao = Dao::new();
+    let secret = secret_proposal(&mut dao);
+    let voter = dao.add_member(0);
+    assert_error(dao.vote(secret, &voter, VoteSide::For {}, 100), ErrorCode::SecretBallot);
+
+    let open = dao.create_proposal(&voter);
+    dao.activate(open, VOTING).unwrap();
+    assert_error(commit(&mut dao, open, &voter, VoteSide::For {}, 100), ErrorCode::NotSecretBallot);
+}
@@ -0,0 +1,339 @@
+//! Ports of `tests/test-case-9.ts` through `tests/test-case-11.ts`.
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorError, prelude::Pubkey};
+use common::{assert_error, ix, pda, Dao, TxResult};
+use iterative_dao::{
+    enums::{LockKind, VoteSide, WeightSource},
+    errors::ErrorCode,
+    events::{ClaimVestedEvent, LockEvent, VoteSetEvent, WithdrawEvent},
+    state::LockAccount,
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+/// A DAO with one member holding 1000 governance tokens.
+fn setup() -> (Dao, Keypair, ix::LockAccounts) {
+    let mut dao = Dao::new();
+    let user = dao.add_member(100);
+    let user_tokens = dao.fund_member(&user.pubkey(), 1000);
+    let accs = dao.lock_accounts(&user.pubkey(), user_tokens);
+    (dao, user, accs)
+}
+
+fn lock(dao: &mut Dao, user: &Keypair, accs: ix::LockAccounts, amount: u64, duration: i64, lock_id: u64) -> TxResult {
+    let ix = ix::lock_tokens(accs, dao.payer(), amount, duration, lock_id);
+    dao.send(ix, &[user])
+}
+
+//  Lock Tokens (test case 9)
+
+#[test]
+fn test_case_9_1_lock_tokens_into_escrow() {
+    let (mut dao, user, accs) = setup();
+    lock(&mut dao, &user, accs, 50, 1000, 1).unwrap();
+
+    assert_eq!(dao.ctx.token_balance(&accs.escrow_token_account), 50);
+    assert_eq!(dao.ctx.token_balance(&accs.user_token_account), 950);
+    let lock_account: LockAccount = dao.ctx.account(&pda::find_lock_address(&dao.governor, &user.pubkey(), 1).0);
+    assert_eq!(lock_account.amount, 50);
+    assert_eq!(lock_account.duration, 1000);
+    assert_eq!(lock_account.end_time, dao.ctx.now() + 1000);
+    assert_eq!(lock_account.user, user.pubkey());
+    assert_eq!(lock_account.kind, LockKind::Standard);
+    assert_eq!(dao.ctx.events::<LockEvent>().len(), 1);
+}
+
+#[test]
+fn test_case_9_2_lock_more_than_balance_fails() {
+    let (mut dao, user, accs) = setup();
+    assert_error(lock(&mut dao, &user, accs, 5000, 1000, 1), ErrorCode::InsufficientBalance);
+}
+
+#[test]
+fn test_case_9_5_lock_with_zero_duration_fails() {
+    let (mut dao, user, accs) = setup();
+    assert_error(lock(&mut dao, &user, accs, 10, 0, 1), ErrorCode::InvalidLockParameters);
+}
+
+#[test]
+fn test_case_9_7_lock_by_non_member_fails() {
+    let (mut dao, _, _) = setup();
+    let outsider = Keypair::new();
+    let tokens = dao.fund_member(&outsider.pubkey(), 100);
+    let accs = dao.lock_accounts(&outsider.pubkey(), tokens);
+
+    assert_error(lock(&mut dao, &outsider, accs, 10, 100, 1), ErrorCode::UnauthorisedVoter);
+}
+
+#[test]
+fn test_case_9_10_multiple_locks_accumulate_in_escrow() {
+    let (mut dao, user, accs) = setup();
+    lock(&mut dao, &user, accs, 10, 400, 1).unwrap();
+    lock(&mut dao, &user, accs, 20, 800, 2).unwrap();
+
+    assert_eq!(dao.ctx.token_balance(&accs.escrow_token_account), 30);
+    let second: LockAccount = dao.ctx.account(&pda::find_lock_address(&dao.governor, &user.pubkey(), 2).0);
+    assert_eq!((second.amount, second.duration), (20, 800));
+}
+
+#[test]
+fn test_case_9_11_lock_non_governance_mint_fails() {
+    let (mut dao, user, _) = setup();
+    let other_mint = dao.ctx.create_mint(&user.pubkey(), 0);
+    let user_tokens = dao.ctx.create_token_account(&other_mint, &user.pubkey());
+    dao.ctx.mint_to(&user_tokens, 1000);
+    let vault = dao.ctx.create_token_account(&other_mint, &dao.wallet());
+    let accs = ix::LockAccounts { escrow_token_account: vault, ..dao.lock_accounts(&user.pubkey(), user_tokens) };
+
+    assert_error(lock(&mut dao, &user, accs, 10, 100, 1), ErrorCode::InvalidGovernanceMint);
+}
+
+#[test]
+fn lock_into_self_owned_vault_fails() {
+    let (mut dao, user, accs) = setup();
+    let vault = dao.ctx.create_token_account(&dao.governance_mint, &user.pubkey());
+    let accs = ix::LockAccounts { escrow_token_account: vault, ..accs };
+
+    assert_error(lock(&mut dao, &user, accs, 10, 100, 1), ErrorCode::InvalidEscrowOwner);
+    assert_eq!(dao.ctx.token_balance(&vault), 0);
+}
+
+//  Withdraw Tokens (test case 10)
+
+#[test]
+fn test_case_10_1_withdraw_after_lock_period() {
+    let (mut dao, user, accs) = setup();
+    lock(&mut dao, &user, accs, 100, 5, 1).unwrap();
+    dao.ctx.warp(5);
+    dao.send(ix::withdraw_tokens(accs, 1), &[&user]).unwrap();
+
+    assert_eq!(dao.ctx.token_balance(&accs.user_token_account), 1000);
+    assert_eq!(dao.ctx.token_balance(&accs.escrow_token_account), 0);
+    let lock_account: LockAccount = dao.ctx.account(&pda::find_lock_address(&dao.governor, &user.pubkey(), 1).0);
+    assert!(lock_account.withdrawn);
+    assert_eq!(lock_account.withdrawn_at, dao.ctx.now());
+    let events = dao.ctx.events::<WithdrawEvent>();
+    assert_eq!((events[0].amount, events[0].lock_id), (100, 1));
+    assert_eq!(events[0].lock_account, pda::find_lock_address(&dao.governor, &user.pubkey(), 1).0);
+}
+
+#[test]
+fn test_case_10_2_withdraw_before_lock_period_fails() {
+    let (mut dao, user, accs) = setup();
+    lock(&mut dao, &user, accs, 100, 60, 1).unwrap();
+    dao.ctx.warp(59);
+
+    assert_error(dao.send(ix::withdraw_tokens(accs, 1), &[&user]), ErrorCode::LockNotExpired);
+}
+
+#[test]
+fn test_case_10_4_withdraw_by_other_member_fails() {
+    let (mut dao, user, accs) = setup();
+    lock(&mut dao, &user, accs, 100, 5, 1).unwrap();
+    dao.ctx.warp(5);
+    let other = dao.add_member(100);
+    let other_tokens = dao.fund_member(&other.pubkey(), 0);
+
+    let mut withdraw = ix::withdraw_tokens(dao.lock_accounts(&other.pubkey(), other_tokens), 1);
+    withdraw.accounts[0].pubkey = pda::find_lock_address(&dao.governor, &user.pubkey(), 1).0;
+    assert_error(dao.send(withdraw, &[&other]), AnchorError::ConstraintHasOne);
+}
+
+#[test]
+fn test_case_10_7_second_withdrawal_fails() {
+    let (mut dao, user, accs) = setup();
+    lock(&mut dao, &user, accs, 100, 5, 1).unwrap();
+    dao.ctx.warp(5);
+    dao.send(ix::withdraw_tokens(accs, 1), &[&user]).unwrap();
+
+    assert_error(dao.send(ix::withdraw_tokens(accs, 1), &[&user]), ErrorCode::AlreadyWithdrawn);
+}
+
+#[test]
+fn test_case_10_8_multiple_withdrawals_stay_consistent() {
+    let (mut dao, user, accs) = setup();
+    for (lock_id, amount) in [(1, 10), (2, 20), (3, 30)] {
+        lock(&mut dao, &user, accs, amount, 5, lock_id).unwrap();
+    }
+    dao.ctx.warp(5);
+    for lock_id in 1..=3 {
+        dao.send(ix::withdraw_tokens(accs, lock_id), &[&user]).unwrap();
+    }
+
+    assert_eq!(dao.ctx.token_balance(&accs.user_token_account), 1000);
+    assert_eq!(dao.ctx.token_balance(&accs.escrow_token_account), 0);
+}
+
+#[test]
+fn test_case_10_9_withdraw_unknown_lock_fails() {
+    let (mut dao, user, accs) = setup();
+    assert_error(dao.send(ix::withdraw_tokens(accs, 9999), &[&user]), AnchorError::AccountNotInitialized);
+}
+
+//  Vesting locks (test case 11)
+
+#[test]
+fn test_case_11_1_vesting_lock_schedule() {
+    let (mut dao, user, accs) = setup();
+    dao.send(ix::lock_vesting_tokens(accs, dao.payer(), 100, 10, 100, 1), &[&user])
+        .unwrap();
+
+    let lock_account: LockAccount = dao.ctx.account(&pda::find_lock_address(&dao.governor, &user.pubkey(), 1).0);
+    assert_eq!(lock_account.kind, LockKind::Vesting);
+    assert_eq!(lock_account.cliff_time - lock_account.start_time, 10);
+    assert_eq!(lock_account.end_time - lock_account.start_time, 100);
+    assert_eq!(lock_account.remaining_locked(dao.ctx.now()), 100);
+}
+
+#[test]
+fn test_case_11_2_claim_before_cliff_fails() {
+    let (mut dao, user, accs) = setup();
+    dao.send(ix::lock_vesting_tokens(accs, dao.payer(), 100, 60, 120, 1), &[&user])
+        .unwrap();
+    dao.ctx.warp(59);
+
+    assert_error(dao.send(ix::claim_vested(accs, 1), &[&user]), ErrorCode::NothingToClaim);
+}
+
+#[test]
+fn test_case_11_3_claim_linear_release() {
+    let (mut dao, user, accs) = setup();
+    dao.send(ix::lock_vesting_tokens(accs, dao.payer(), 100, 10, 100, 1), &[&user])
+        .unwrap();
+
+    dao.ctx.warp(25);
+    dao.send(ix::claim_vested(accs, 1), &[&user]).unwrap();
+    let event = &dao.ctx.events::<ClaimVestedEvent>()[0];
+    assert_eq!((event.amount, event.claimed_amount, event.remaining_locked), (25, 25, 75));
+    assert_eq!(dao.ctx.token_balance(&accs.user_token_account), 925);
+
+    dao.ctx.warp(25);
+    dao.send(ix::claim_vested(accs, 1), &[&user]).unwrap();
+    assert_eq!(dao.ctx.events::<ClaimVestedEvent>()[0].amount, 25);
+
+    dao.ctx.warp(100);
+    dao.send(ix::claim_vested(accs, 1), &[&user]).unwrap();
+    let lock_account: LockAccount = dao.ctx.account(&pda::find_lock_address(&dao.governor, &user.pubkey(), 1).0);
+    assert_eq!(lock_account.claimed_amount, 100);
+    assert!(lock_account.withdrawn);
+    assert_eq!(dao.ctx.token_balance(&accs.user_token_account), 1000);
+}
+
+#[test]
+fn test_case_11_4_claim_from_standard_lock_fails() {
+    let (mut dao, user, accs) = setup();
+    lock(&mut dao, &user, accs, 50, 100, 1).unwrap();
+
+    assert_error(dao.send(ix::claim_vested(accs, 1), &[&user]), ErrorCode::NotVestingLock);
+}
+
+#[test]
+fn withdraw_after_partial_claim_returns_remainder() {
+    let (mut dao, user, accs) = setup();
+    dao.send(ix::lock_vesting_tokens(accs, dao.payer(), 100, 0, 100, 1), &[&user])
+        .unwrap();
+    dao.ctx.warp(40);
+    dao.send(ix::claim_vested(accs, 1), &[&user]).unwrap();
+    dao.ctx.warp(60);
+    dao.send(ix::withdraw_tokens(accs, 1), &[&user]).unwrap();
+
+    assert_eq!(dao.ctx.token_balance(&accs.user_token_account), 1000);
+    assert_eq!(dao.ctx.events::<WithdrawEvent>()[0].amount, 60);
+}
+
+#[test]
+fn lock_is_released_only_through_its_own_governor() {
+    let (mut dao, user, accs) = setup();
+    dao.send(ix::lock_vesting_tokens(accs, dao.payer(), 100, 0, 100, 1), &[&user])
+        .unwrap();
+    dao.ctx.warp(100);
+
+    // The user runs a governor of their own and signs as its smart wallet.
+    let init = ix::init_governor(user.pubkey(), dao.payer(), 60, 0, user.pubkey(), dao.governance_mint);
+    dao.ctx.send(init, &[&user]).unwrap();
+    let foreign = ix::LockAccounts { smart_wallet: user.pubkey(), ..accs };
+    let lock = pda::find_lock_address(&dao.governor, &user.pubkey(), 1).0;
+    for mut release in [ix::withdraw_tokens(foreign, 1), ix::claim_vested(foreign, 1)] {
+        release.accounts[0].pubkey = lock;
+        assert_error(dao.ctx.send(release, &[&user]), AnchorError::ConstraintHasOne);
+    }
+    assert_eq!(dao.ctx.token_balance(&accs.escrow_token_account), 100);
+}
+
+#[test]
+fn vesting_cliff_longer_than_duration_fails() {
+    let (mut dao, user, accs) = setup();
+    let result = dao.send(ix::lock_vesting_tokens(accs, dao.payer(), 100, 200, 100, 1), &[&user]);
+    assert_error(result, ErrorCode::InvalidLockParameters);
+}
+
+#[test]
+fn vesting_lock_into_self_owned_vault_fails() {
+    let (mut dao, user, accs) = setup();
+    let vault = dao.ctx.create_token_account(&dao.governance_mint, &user.pubkey());
+    let accs = ix::LockAccounts { escrow_token_account: vault, ..accs };
+
+    let result = dao.send(ix::lock_vesting_tokens(accs, dao.payer(), 100, 0, 100, 1), &[&user]);
+    assert_error(result, ErrorCode::InvalidEscrowOwner);
+}
+
+//  Locked voting power
+
+/// Casts `user`'s vote on a fresh proposal, declaring `weight` and counting `locks`.
+fn vote_with_locks(dao: &mut Dao, user: &Keypair, locks: &[Pubkey], weight: u64) -> TxResult {
+    let proposal = dao.create_proposal(user);
+    dao.activate(proposal, 3600).unwrap();
+    dao.send(ix::create_vote(dao.wallet(), proposal, user.pubkey(), dao.payer()), &[user])?;
+    let cast = ix::cast_vote(dao.wallet(), proposal, user.pubkey(), dao.payer(), VoteSide::For {}, weight, None);
+    dao.send(ix::with_locks(cast, locks), &[user])
+}
+
+fn last_vote_weight(dao: &Dao) -> u64 {
+    dao.ctx.events::<VoteSetEvent>()[0].weight
+}
+
+#[test]
+fn locks_add_what_they_still_hold_to_declared_weight() {
+    let (mut dao, user, accs) = setup();
+    dao.send(ix::lock_vesting_tokens(accs, dao.payer(), 100, 0, 100, 1), &[&user])
+        .unwrap();
+    lock(&mut dao, &user, accs, 50, 1000, 2).unwrap();
+    let locks = [1, 2].map(|id| pda::find_lock_address(&dao.governor, &user.pubkey(), id).0);
+
+    dao.ctx.warp(25);
+    vote_with_locks(&mut dao, &user, &locks, 5).unwrap();
+    assert_eq!(last_vote_weight(&dao), 5 + 75 + 50);
+
+    // Claiming does not change the power; releasing does.
+    dao.send(ix::claim_vested(accs, 1), &[&user]).unwrap();
+    vote_with_locks(&mut dao, &user, &locks, 5).unwrap();
+    assert_eq!(last_vote_weight(&dao), 5 + 75 + 50);
+    dao.ctx.warp(75);
+    vote_with_locks(&mut dao, &user, &locks, 5).unwrap();
+    assert_eq!(last_vote_weight(&dao), 5 + 50);
+}
+
+#[test]
+fn locker_source_counts_vesting_locks() {
+    let (mut dao, user, accs) = setup();
+    dao.send(ix::set_weight_source(dao.wallet(), WeightSource::Locker), &[]).unwrap();
+    dao.send(ix::lock_vesting_tokens(accs, dao.payer(), 100, 50, 100, 1), &[&user])
+        .unwrap();
+    let lock = pda::find_lock_address(&dao.governor, &user.pubkey(), 1).0;
+
+    dao.ctx.warp(60);
+    vote_with_locks(&mut dao, &user, &[lock], 1_000).unwrap();
+    assert_eq!(last_vote_weight(&dao), 40);
+}
+
+#[test]
+fn other_members_lock_is_rejected() {
+    let (mut dao, user, accs) = setup();
+    lock(&mut dao, &user, accs, 50, 1000, 1).unwrap();
+    let lock = pda::find_lock_address(&dao.governor, &user.pubkey(), 1).0;
+    let other = dao.add_member(100);
+
+    assert_error(vote_with_locks(&mut dao, &other, &[lock], 5), ErrorCode::InvalidEscrowAccount);
+    assert_error(vote_with_locks(&mut dao, &user, &[lock, lock], 5), ErrorCode::InvalidEscrowAccount);
+}
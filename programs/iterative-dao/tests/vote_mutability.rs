@@ -0,0 +1,147 @@
+//! Changing and withdrawing votes under each governor vote mutability.
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorError, prelude::Pubkey};
+use common::{assert_error, ix, pda, Dao, TxResult};
+use iterative_dao::{
+    enums::{VoteMutability, VoteSide, VoteState},
+    errors::ErrorCode,
+    events::{VoteMutabilitySet, VoteWithdrawn},
+    secret::ballot_commitment,
+    state::{Governor, Vote},
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+const VOTING: i64 = 3600;
+
+fn set_mutability(dao: &mut Dao, mutability: VoteMutability) -> TxResult {
+    dao.send(ix::set_vote_mutability(dao.wallet(), mutability), &[])
+}
+
+fn withdraw(dao: &mut Dao, proposal: Pubkey, voter: &Keypair) -> TxResult {
+    dao.send(ix::withdraw_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[voter])
+}
+
+#[test]
+fn votes_are_freely_changeable_by_default() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(VOTING);
+    let voter = dao.add_member(0);
+    dao.vote(proposal, &voter, VoteSide::For {}, 40).unwrap();
+
+    let governor: Governor = dao.ctx.account(&dao.governor);
+    assert!(governor.vote_mutability == VoteMutability::Free);
+    withdraw(&mut dao, proposal, &voter).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).for_votes, 0);
+    let vote: Vote = dao.ctx.account(&pda::find_vote_address(&proposal, &voter.pubkey()).0);
+    assert!(vote.state == VoteState::Pending);
+    assert_eq!((vote.weight, vote.power), (0, 0));
+    let event = &dao.ctx.events::<VoteWithdrawn>()[0];
+    assert!(event.side == VoteSide::For {});
+    assert_eq!((event.voter, event.weight), (voter.pubkey(), 40));
+}
+
+#[test]
+fn withdrawn_voter_can_vote_again() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(VOTING);
+    let voter = dao.add_member(0);
+    dao.vote(proposal, &voter, VoteSide::For {}, 40).unwrap();
+    withdraw(&mut dao, proposal, &voter).unwrap();
+
+    let cast = ix::cast_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), VoteSide::Against {}, 25, None);
+    dao.send(cast, &[&voter]).unwrap();
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.for_votes, account.against_votes), (0, 25));
+}
+
+#[test]
+fn only_cast_votes_can_be_withdrawn() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(VOTING);
+    let voter = dao.add_member(0);
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[&voter]).unwrap();
+
+    assert_error(withdraw(&mut dao, proposal, &voter), ErrorCode::InvalidStateTransition);
+}
+
+#[test]
+fn immutable_votes_cannot_change() {
+    let mut dao = Dao::new();
+    set_mutability(&mut dao, VoteMutability::Immutable).unwrap();
+    assert!(dao.ctx.events::<VoteMutabilitySet>()[0].vote_mutability == VoteMutability::Immutable);
+    let proposal = dao.active_proposal(VOTING);
+    let voter = dao.add_member(0);
+    dao.vote(proposal, &voter, VoteSide::For {}, 40).unwrap();
+
+    assert_error(dao.change(proposal, &voter, VoteSide::Against {}), ErrorCode::VoteImmutable);
+    assert_error(withdraw(&mut dao, proposal, &voter), ErrorCode::VoteImmutable);
+    assert_eq!(dao.proposal(&proposal).for_votes, 40);
+}
+
+#[test]
+fn votes_freeze_at_the_cutoff() {
+    let mut dao = Dao::new();
+    set_mutability(&mut dao, VoteMutability::UntilCutoff { cutoff: 600 }).unwrap();
+    let proposal = dao.active_proposal(VOTING);
+    let voter = dao.add_member(40);
+    dao.vote(proposal, &voter, VoteSide::For {}, 40).unwrap();
+
+    dao.ctx.warp(VOTING - 601);
+    dao.change(proposal, &voter, VoteSide::Against {}).unwrap();
+    dao.ctx.warp(1);
+    assert_error(dao.change(proposal, &voter, VoteSide::For {}), ErrorCode::VoteChangeCutoffPassed);
+    assert_error(withdraw(&mut dao, proposal, &voter), ErrorCode::VoteChangeCutoffPassed);
+
+    // Late voters may still cast a first vote.
+    let late = dao.add_member(0);
+    dao.vote(proposal, &late, VoteSide::For {}, 10).unwrap();
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.for_votes, account.against_votes), (10, 40));
+}
+
+#[test]
+fn cutoff_cannot_be_negative() {
+    let mut dao = Dao::new();
+    let result = set_mutability(&mut dao, VoteMutability::UntilCutoff { cutoff: -1 });
+
+    assert_error(result, ErrorCode::InvalidVoteMutability);
+}
+
+#[test]
+fn only_the_smart_wallet_sets_mutability() {
+    let mut dao = Dao::new();
+    let mut ix = ix::set_vote_mutability(dao.wallet(), VoteMutability::Immutable);
+    let impostor = Keypair::new();
+    ix.accounts[1].pubkey = impostor.pubkey();
+
+    assert_error(dao.ctx.send(ix, &[&impostor]), AnchorError::ConstraintHasOne);
+}
+
+#[test]
+fn commitments_follow_mutability() {
+    let mut dao = Dao::new();
+    let proposer = dao.add_member(0);
+    let proposal = dao.create_proposal(&proposer);
+    dao.send(ix::activate_secret_proposal(dao.wallet(), proposal, Some(VOTING), 1800), &[]).unwrap();
+    let voter = dao.add_member(0);
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[&voter]).unwrap();
+    let (wallet, payer) = (dao.wallet(), dao.payer());
+    let commit = |side| {
+        let commitment = ballot_commitment(&proposal, &voter.pubkey(), &side, &[7; 32]);
+        ix::commit_vote(wallet, proposal, voter.pubkey(), payer, commitment, 50)
+    };
+    dao.send(commit(VoteSide::For {}), &[&voter]).unwrap();
+
+    // A commitment was never counted, so it withdraws at zero weight.
+    withdraw(&mut dao, proposal, &voter).unwrap();
+    assert_eq!(dao.ctx.events::<VoteWithdrawn>()[0].weight, 0);
+    let vote: Vote = dao.ctx.account(&pda::find_vote_address(&proposal, &voter.pubkey()).0);
+    assert!(vote.state == VoteState::Pending && vote.commitment == [0; 32]);
+
+    set_mutability(&mut dao, VoteMutability::Immutable).unwrap();
+    dao.send(commit(VoteSide::For {}), &[&voter]).unwrap();
+    assert_error(dao.send(commit(VoteSide::Against {}), &[&voter]), ErrorCode::VoteImmutable);
+}
@@ -0,0 +1,87 @@
+//! Reasons given with votes.
+
+mod common;
+
+use common::{assert_error, ix, Dao};
+use iterative_dao::{enums::VoteSide, errors::ErrorCode, events::VoteSetEvent, state::VoteRationale};
+use solana_sdk::signature::Signer;
+
+fn rationale(reason: &str, uri: &str) -> VoteRationale {
+    VoteRationale { reason: reason.to_string(), uri: uri.to_string(), uri_hash: [9; 32] }
+}
+
+#[test]
+fn rationale_is_published_with_the_vote() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = dao.add_member(0);
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[&voter]).unwrap();
+    let reasons = rationale("Funds the audit", "https://forum/1");
+    let cast = ix::cast_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), VoteSide::For {}, 40, Some(reasons));
+    dao.send(cast, &[&voter]).unwrap();
+
+    let event = &dao.ctx.events::<VoteSetEvent>()[0];
+    assert!(event.side == VoteSide::For {});
+    assert_eq!((event.voter, event.weight), (voter.pubkey(), 40));
+    let published = event.rationale.as_ref().unwrap();
+    assert_eq!((published.reason.as_str(), published.uri.as_str()), ("Funds the audit", "https://forum/1"));
+    assert_eq!(published.uri_hash, [9; 32]);
+}
+
+#[test]
+fn create_and_cast_vote_takes_a_rationale() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = dao.add_member(0);
+    let ix = ix::create_and_cast_vote(
+        dao.wallet(),
+        proposal,
+        voter.pubkey(),
+        dao.payer(),
+        VoteSide::Against {},
+        10,
+        Some(rationale("", "https://forum/2")),
+    );
+    dao.send(ix, &[&voter]).unwrap();
+
+    let event = &dao.ctx.events::<VoteSetEvent>()[0];
+    assert_eq!(event.rationale.as_ref().unwrap().uri, "https://forum/2");
+}
+
+#[test]
+fn changed_vote_carries_only_its_own_rationale() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = dao.add_member(25);
+    dao.vote(proposal, &voter, VoteSide::For {}, 25).unwrap();
+
+    let mut change = |side, reasons| {
+        let ix = ix::set_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), side, reasons);
+        dao.send(ix, &[&voter]).unwrap();
+        dao.ctx.events::<VoteSetEvent>()[0].rationale.clone()
+    };
+
+    let published = change(VoteSide::Against {}, Some(rationale("Changed my mind", "")));
+    assert_eq!(published.unwrap().reason, "Changed my mind");
+    assert!(change(VoteSide::For {}, None).is_none());
+}
+
+#[test]
+fn rationale_must_be_present_and_short() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let voter = dao.add_member(0);
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[&voter]).unwrap();
+    let mut cast = |reasons| {
+        let side = VoteSide::For {};
+        let ix = ix::cast_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), side, 40, Some(reasons));
+        dao.send(ix, &[&voter])
+    };
+
+    assert_error(cast(rationale("", "")), ErrorCode::InvalidVoteRationale);
+    let long_reason = "r".repeat(VoteRationale::MAX_REASON_LEN + 1);
+    assert_error(cast(rationale(&long_reason, "")), ErrorCode::InvalidVoteRationale);
+    let long_uri = "u".repeat(VoteRationale::MAX_URI_LEN + 1);
+    assert_error(cast(rationale("", &long_uri)), ErrorCode::InvalidVoteRationale);
+    cast(rationale(&"r".repeat(VoteRationale::MAX_REASON_LEN), "")).unwrap();
+}
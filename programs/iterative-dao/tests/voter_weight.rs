@@ -0,0 +1,222 @@
+//! Governor-selected sources of voting power.
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorError, prelude::Pubkey, AnchorSerialize};
+use common::{assert_error, ix, pda, Dao};
+use iterative_dao::{
+    enums::{VoteSide, VoterWeightAction, WeightSource},
+    errors::ErrorCode,
+    events::WeightSourceSet,
+    state::VoterWeightRecord,
+};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+};
+
+const DAY: i64 = 24 * 60 * 60;
+
+fn set_source(dao: &mut Dao, source: WeightSource) {
+    dao.send(ix::set_weight_source(dao.wallet(), source), &[]).expect("set_weight_source");
+}
+
+/// A record for `voter` on `dao`, valid for any action until `expiry`.
+fn weight_record(dao: &Dao, voter: &Pubkey, voter_weight: u64, expiry: Option<u64>) -> VoterWeightRecord {
+    VoterWeightRecord {
+        realm: dao.governor,
+        governing_token_mint: dao.governance_mint,
+        governing_token_owner: *voter,
+        voter_weight,
+        voter_weight_expiry: expiry,
+        weight_action: None,
+        weight_action_target: None,
+        reserved: [0; 8],
+    }
+}
+
+/// Stores `record` in a fresh account owned by `owner`.
+fn store(dao: &mut Dao, owner: Pubkey, record: &VoterWeightRecord) -> Pubkey {
+    let mut data = VoterWeightRecord::DISCRIMINATOR.to_vec();
+    data.extend(record.try_to_vec().unwrap());
+    let key = Pubkey::new_unique();
+    dao.ctx.set_account(key, Account { lamports: 1_000_000, data, owner, ..Account::default() });
+    key
+}
+
+/// Creates and casts `voter`'s vote with `record` as its source of power.
+fn vote_with_record(dao: &mut Dao, proposal: Pubkey, voter: &Keypair, record: Pubkey) -> common::TxResult {
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[voter])?;
+    let cast = ix::cast_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), VoteSide::For {}, 1_000, None);
+    dao.send(ix::with_voter_weight_record(cast, record), &[voter])
+}
+
+#[test]
+fn governor_defaults_to_declared_weight() {
+    let mut dao = Dao::new();
+    assert!(dao.governor().weight_source == WeightSource::Declared);
+
+    let program = Pubkey::new_unique();
+    set_source(&mut dao, WeightSource::AddIn { program });
+    assert!(dao.governor().weight_source == WeightSource::AddIn { program });
+    assert!(dao.ctx.events::<WeightSourceSet>()[0].weight_source == WeightSource::AddIn { program });
+}
+
+#[test]
+fn registry_source_ignores_declared_weight() {
+    let mut dao = Dao::new();
+    set_source(&mut dao, WeightSource::Registry);
+    let voter = dao.add_member(7);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &voter, VoteSide::For {}, 1_000).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).for_votes, 7);
+}
+
+#[test]
+fn votes_cannot_be_cast_through_another_governor() {
+    let mut dao = Dao::new();
+    set_source(&mut dao, WeightSource::Registry);
+    let voter = dao.add_member(1);
+    let proposal = dao.active_proposal(3600);
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[&voter]).unwrap();
+
+    // The voter runs a governor of their own that takes declared weights.
+    let init = ix::init_governor(voter.pubkey(), dao.payer(), 60, 0, voter.pubkey(), dao.governance_mint);
+    dao.ctx.send(init, &[&voter]).unwrap();
+    dao.ctx.send(ix::add_voter(voter.pubkey(), voter.pubkey(), 1), &[&voter]).unwrap();
+
+    let cast =
+        ix::cast_vote(voter.pubkey(), proposal, voter.pubkey(), dao.payer(), VoteSide::For {}, u64::MAX / 2, None);
+    assert_error(dao.ctx.send(cast, &[&voter]), AnchorError::ConstraintRaw);
+    assert_eq!(dao.proposal(&proposal).for_votes, 0);
+}
+
+#[test]
+fn locker_source_counts_only_escrowed_power() {
+    let mut dao = Dao::new();
+    let mint = dao.governance_mint;
+    dao.send(ix::create_locker(dao.wallet(), mint, dao.payer(), 2, DAY, 365 * DAY), &[]).unwrap();
+    let locker = pda::find_locker_address(&dao.governor, &mint).0;
+    let member = dao.add_member(50);
+    let tokens = dao.fund_member(&member.pubkey(), 40);
+    let accs = dao.lock_accounts(&member.pubkey(), tokens);
+    dao.send(ix::create_escrow(accs, locker, dao.payer(), 40, 30 * DAY, 1), &[&member]).unwrap();
+    let escrow = pda::find_escrow_address(&locker, &member.pubkey(), 1).0;
+    set_source(&mut dao, WeightSource::Locker);
+
+    let proposal = dao.active_proposal(3600);
+    dao.send(ix::create_vote(dao.wallet(), proposal, member.pubkey(), dao.payer()), &[&member]).unwrap();
+    let cast = ix::cast_vote(dao.wallet(), proposal, member.pubkey(), dao.payer(), VoteSide::For {}, 1_000, None);
+    dao.send(ix::with_escrows(cast, &[(locker, escrow)]), &[&member]).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).for_votes, 80);
+}
+
+//  Add-in records
+
+#[test]
+fn add_in_record_sets_weight() {
+    let mut dao = Dao::new();
+    let program = Pubkey::new_unique();
+    set_source(&mut dao, WeightSource::AddIn { program });
+    let voter = dao.add_member(0);
+    let proposal = dao.active_proposal(3600);
+    let mut fresh = weight_record(&dao, &voter.pubkey(), 250, Some(dao.ctx.slot()));
+    fresh.weight_action = Some(VoterWeightAction::CastVote);
+    fresh.weight_action_target = Some(proposal);
+    let key = store(&mut dao, program, &fresh);
+
+    vote_with_record(&mut dao, proposal, &voter, key).unwrap();
+    assert_eq!(dao.proposal(&proposal).for_votes, 250);
+}
+
+#[test]
+fn set_vote_rereads_add_in_record() {
+    let mut dao = Dao::new();
+    let program = Pubkey::new_unique();
+    set_source(&mut dao, WeightSource::AddIn { program });
+    let voter = dao.add_member(0);
+    let proposal = dao.active_proposal(3600);
+    let initial = weight_record(&dao, &voter.pubkey(), 250, None);
+    let key = store(&mut dao, program, &initial);
+    vote_with_record(&mut dao, proposal, &voter, key).unwrap();
+
+    let lowered = weight_record(&dao, &voter.pubkey(), 90, None);
+    let updated = store(&mut dao, program, &lowered);
+    let set = ix::set_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), VoteSide::Against {}, None);
+    dao.send(ix::with_voter_weight_record(set, updated), &[&voter]).unwrap();
+
+    let account = dao.proposal(&proposal);
+    assert_eq!((account.for_votes, account.against_votes), (0, 90));
+}
+
+#[test]
+fn record_from_other_program_is_rejected() {
+    let mut dao = Dao::new();
+    set_source(&mut dao, WeightSource::AddIn { program: Pubkey::new_unique() });
+    let voter = dao.add_member(0);
+    let proposal = dao.active_proposal(3600);
+    let record = weight_record(&dao, &voter.pubkey(), 250, None);
+    let key = store(&mut dao, Pubkey::new_unique(), &record);
+
+    assert_error(vote_with_record(&mut dao, proposal, &voter, key), ErrorCode::InvalidVoterWeightRecord);
+}
+
+#[test]
+fn record_for_other_voter_is_rejected() {
+    let mut dao = Dao::new();
+    let program = Pubkey::new_unique();
+    set_source(&mut dao, WeightSource::AddIn { program });
+    let voter = dao.add_member(0);
+    let proposal = dao.active_proposal(3600);
+    let record = weight_record(&dao, &Pubkey::new_unique(), 250, None);
+    let key = store(&mut dao, program, &record);
+
+    assert_error(vote_with_record(&mut dao, proposal, &voter, key), ErrorCode::InvalidVoterWeightRecord);
+}
+
+#[test]
+fn missing_record_is_rejected() {
+    let mut dao = Dao::new();
+    set_source(&mut dao, WeightSource::AddIn { program: Pubkey::new_unique() });
+    let voter = dao.add_member(10);
+    let proposal = dao.active_proposal(3600);
+
+    assert_error(dao.vote(proposal, &voter, VoteSide::For {}, 10), ErrorCode::InvalidVoterWeightRecord);
+}
+
+#[test]
+fn expired_record_is_rejected() {
+    let mut dao = Dao::new();
+    let program = Pubkey::new_unique();
+    set_source(&mut dao, WeightSource::AddIn { program });
+    let voter = dao.add_member(0);
+    let proposal = dao.active_proposal(3600);
+    let record = weight_record(&dao, &voter.pubkey(), 250, Some(dao.ctx.slot()));
+    let key = store(&mut dao, program, &record);
+    dao.ctx.warp(1);
+
+    assert_error(vote_with_record(&mut dao, proposal, &voter, key), ErrorCode::StaleVoterWeightRecord);
+}
+
+#[test]
+fn record_for_other_action_is_rejected() {
+    let mut dao = Dao::new();
+    let program = Pubkey::new_unique();
+    set_source(&mut dao, WeightSource::AddIn { program });
+    let voter = dao.add_member(0);
+    let proposal = dao.active_proposal(3600);
+
+    let mut other_action = weight_record(&dao, &voter.pubkey(), 250, None);
+    other_action.weight_action = Some(VoterWeightAction::CreateProposal);
+    let key = store(&mut dao, program, &other_action);
+    assert_error(vote_with_record(&mut dao, proposal, &voter, key), ErrorCode::StaleVoterWeightRecord);
+
+    let mut other_target = weight_record(&dao, &voter.pubkey(), 250, None);
+    other_target.weight_action_target = Some(Pubkey::new_unique());
+    let key = store(&mut dao, program, &other_target);
+    let cast = ix::cast_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), VoteSide::For {}, 0, None);
+    let result = dao.send(ix::with_voter_weight_record(cast, key), &[&voter]);
+    assert_error(result, ErrorCode::StaleVoterWeightRecord);
+}
@@ -0,0 +1,222 @@
+//! Ports of `tests/test-case-6.ts` through `tests/test-case-8.ts`.
+
+mod common;
+
+use anchor_lang::error::ErrorCode as AnchorError;
+use common::{assert_error, ix, pda, Dao, TxError};
+use iterative_dao::{
+    enums::{ProposalState, VoteSide, VoteState},
+    errors::ErrorCode,
+    events::{VoteCreateEvent, VoteSetEvent},
+    state::Vote,
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+fn tallies(dao: &Dao, proposal: &anchor_lang::prelude::Pubkey) -> (u64, u64, u64) {
+    let p = dao.proposal(proposal);
+    (p.for_votes, p.against_votes, p.abstain_votes)
+}
+
+//  Create Vote (test case 6)
+
+#[test]
+fn test_case_6_1_create_vote_record() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(100);
+    let proposal = dao.active_proposal(3600);
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[&voter])
+        .unwrap();
+
+    let vote: Vote = dao.ctx.account(&pda::find_vote_address(&proposal, &voter.pubkey()).0);
+    assert_eq!(vote.proposal, proposal);
+    assert_eq!(vote.voter, voter.pubkey());
+    assert!(vote.state == VoteState::Pending);
+    assert_eq!(vote.weight, 0);
+    assert_eq!(dao.ctx.events::<VoteCreateEvent>().len(), 1);
+}
+
+#[test]
+fn test_case_6_3_create_vote_by_non_member_fails() {
+    let mut dao = Dao::new();
+    let proposal = dao.active_proposal(3600);
+    let outsider = Keypair::new();
+
+    let result = dao.send(ix::create_vote(dao.wallet(), proposal, outsider.pubkey(), dao.payer()), &[&outsider]);
+    assert_error(result, ErrorCode::UnauthorisedVoter);
+}
+
+#[test]
+fn test_case_6_4_create_vote_on_draft_proposal_fails() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(100);
+    let proposal = dao.create_proposal(&voter);
+
+    let result = dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[&voter]);
+    assert_error(result, AnchorError::ConstraintRaw);
+}
+
+#[test]
+fn test_case_6_6_multiple_votes_tracked_separately() {
+    let mut dao = Dao::new();
+    let first = dao.add_member(100);
+    let second = dao.add_member(100);
+    let proposal = dao.active_proposal(3600);
+    for voter in [&first, &second] {
+        dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[voter])
+            .unwrap();
+    }
+
+    let vote1: Vote = dao.ctx.account(&pda::find_vote_address(&proposal, &first.pubkey()).0);
+    let vote2: Vote = dao.ctx.account(&pda::find_vote_address(&proposal, &second.pubkey()).0);
+    assert_eq!(vote1.voter, first.pubkey());
+    assert_eq!(vote2.voter, second.pubkey());
+}
+
+#[test]
+fn test_case_6_7_vote_account_space() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(100);
+    let proposal = dao.active_proposal(3600);
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[&voter])
+        .unwrap();
+
+    let raw = dao.ctx.raw_account(&pda::find_vote_address(&proposal, &voter.pubkey()).0).unwrap();
+    assert_eq!(raw.data.len(), Vote::LEN);
+}
+
+#[test]
+fn test_case_6_9_duplicate_vote_record_fails() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(100);
+    let proposal = dao.active_proposal(3600);
+    let create = ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer());
+    dao.send(create.clone(), &[&voter]).unwrap();
+
+    let result = dao.send(create, &[&voter]);
+    assert!(matches!(result, Err(TxError::Program { .. })), "{result:?}");
+}
+
+//  Cast Vote (test case 7)
+
+#[test]
+fn test_case_7_1_to_7_3_cast_each_side() {
+    let mut dao = Dao::new();
+    let a = dao.add_member(100);
+    let b = dao.add_member(100);
+    let c = dao.add_member(100);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &a, VoteSide::For {}, 10).unwrap();
+    dao.vote(proposal, &b, VoteSide::Against {}, 20).unwrap();
+    dao.vote(proposal, &c, VoteSide::Abstain {}, 30).unwrap();
+
+    assert_eq!(tallies(&dao, &proposal), (10, 20, 30));
+    let events = dao.ctx.events::<VoteSetEvent>();
+    assert_eq!(events[0].side, VoteSide::Abstain {});
+    assert_eq!(events[0].weight, 30);
+}
+
+#[test]
+fn test_case_7_5_double_voting_fails() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(100);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &voter, VoteSide::For {}, 10).unwrap();
+
+    let cast = ix::cast_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), VoteSide::For {}, 10, None);
+    assert_error(dao.send(cast, &[&voter]), AnchorError::ConstraintRaw);
+    assert_eq!(tallies(&dao, &proposal), (10, 0, 0));
+}
+
+#[test]
+fn test_case_7_7_cast_without_voter_signature_fails() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(100);
+    let proposal = dao.active_proposal(3600);
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[&voter])
+        .unwrap();
+
+    let cast = ix::cast_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), VoteSide::For {}, 10, None);
+    assert_eq!(dao.send(cast, &[]), Err(TxError::MissingSignature(voter.pubkey())));
+}
+
+#[test]
+fn test_case_7_8_votes_decide_proposal_state() {
+    let mut dao = Dao::new();
+    let yes = dao.add_member(100);
+    let no = dao.add_member(100);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &yes, VoteSide::For {}, 70).unwrap();
+    dao.vote(proposal, &no, VoteSide::Against {}, 30).unwrap();
+    dao.ctx.warp(3600);
+    dao.finalise(proposal).unwrap();
+
+    assert!(dao.proposal(&proposal).state == ProposalState::Succeeded);
+}
+
+#[test]
+fn test_case_7_9_maximum_weight_overflows() {
+    let mut dao = Dao::new();
+    let a = dao.add_member(100);
+    let b = dao.add_member(100);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &a, VoteSide::For {}, u64::MAX).unwrap();
+
+    assert_error(dao.vote(proposal, &b, VoteSide::For {}, 1), ErrorCode::NumericalOverflow);
+    assert_eq!(tallies(&dao, &proposal), (u64::MAX, 0, 0));
+}
+
+//  Set Vote (test case 8)
+
+#[test]
+fn test_case_8_2_set_vote_moves_weight_between_sides() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(100);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &voter, VoteSide::For {}, 100).unwrap();
+
+    dao.change(proposal, &voter, VoteSide::Against {}).unwrap();
+    assert_eq!(tallies(&dao, &proposal), (0, 100, 0));
+
+    dao.change(proposal, &voter, VoteSide::Abstain {}).unwrap();
+    assert_eq!(tallies(&dao, &proposal), (0, 0, 100));
+}
+
+#[test]
+fn test_case_8_4_set_vote_by_non_member_fails() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(100);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &voter, VoteSide::For {}, 100).unwrap();
+    let outsider = Keypair::new();
+
+    let mut set = ix::set_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), VoteSide::Against {}, None);
+    set.accounts[3].pubkey = outsider.pubkey();
+    assert_error(dao.send(set, &[&outsider]), AnchorError::ConstraintRaw);
+}
+
+#[test]
+fn test_case_8_9_set_vote_uses_registered_weight() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(250);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &voter, VoteSide::For {}, 1).unwrap();
+
+    dao.change(proposal, &voter, VoteSide::For {}).unwrap();
+
+    assert_eq!(tallies(&dao, &proposal), (250, 0, 0));
+    let vote: Vote = dao.ctx.account(&pda::find_vote_address(&proposal, &voter.pubkey()).0);
+    assert_eq!(vote.weight, 250);
+}
+
+#[test]
+fn test_case_8_10_repeated_set_vote_does_not_double_count() {
+    let mut dao = Dao::new();
+    let voter = dao.add_member(100);
+    let proposal = dao.active_proposal(3600);
+    dao.vote(proposal, &voter, VoteSide::For {}, 100).unwrap();
+
+    for _ in 0..3 {
+        dao.change(proposal, &voter, VoteSide::For {}).unwrap();
+    }
+    assert_eq!(tallies(&dao, &proposal), (100, 0, 0));
+}
@@ -0,0 +1,186 @@
+//! Governor-wide voting period bounds, default and review period.
+
+mod common;
+
+use anchor_lang::{prelude::Pubkey, solana_program::system_program};
+use common::{assert_error, ix, mock_instruction, Dao, TxError, TxResult};
+use iterative_dao::{
+    enums::{ProposalState, VoteSide},
+    errors::ErrorCode,
+    events::{ProposalActivated, VotingScheduleSet},
+    state::{ProposalCategoryConfig, VotingSchedule},
+};
+use solana_sdk::signature::Signer;
+
+const REVIEW: i64 = 600;
+
+/// One hour to one day, two hours unless named, after ten minutes' review.
+fn schedule() -> VotingSchedule {
+    VotingSchedule {
+        min_voting_period: 3600,
+        max_voting_period: 86_400,
+        default_voting_period: 7200,
+        review_period: REVIEW,
+    }
+}
+
+fn set_schedule(dao: &mut Dao, voting_schedule: VotingSchedule) -> TxResult {
+    dao.send(ix::set_voting_schedule(dao.wallet(), voting_schedule), &[])
+}
+
+fn draft(dao: &mut Dao) -> Pubkey {
+    let proposer = dao.add_member(0);
+    dao.create_proposal(&proposer)
+}
+
+fn activate(dao: &mut Dao, proposal: Pubkey, voting_period: Option<i64>) -> TxResult {
+    dao.send(ix::activate_proposal(dao.wallet(), proposal, voting_period), &[])
+}
+
+#[test]
+fn governors_start_with_an_open_schedule() {
+    let dao = Dao::new();
+    assert_eq!(dao.governor().voting_schedule, VotingSchedule::default());
+}
+
+#[test]
+fn setting_the_schedule_stores_and_announces_it() {
+    let dao = Dao::builder().schedule(schedule()).build();
+    assert_eq!(dao.governor().voting_schedule, schedule());
+    assert_eq!(dao.ctx.events::<VotingScheduleSet>()[0].voting_schedule, schedule());
+}
+
+#[test]
+fn invalid_schedules_are_rejected() {
+    let mut dao = Dao::new();
+    let invalid = [
+        VotingSchedule { min_voting_period: 0, ..schedule() },
+        VotingSchedule { default_voting_period: 3599, ..schedule() },
+        VotingSchedule { default_voting_period: 86_401, ..schedule() },
+        VotingSchedule { min_voting_period: 7200, max_voting_period: 3600, ..schedule() },
+        VotingSchedule { review_period: -1, ..schedule() },
+    ];
+    for voting_schedule in invalid {
+        assert_error(set_schedule(&mut dao, voting_schedule), ErrorCode::InvalidVotingSchedule);
+    }
+    assert_eq!(dao.governor().voting_schedule, VotingSchedule::default());
+}
+
+#[test]
+fn activation_defaults_to_the_governor_voting_period() {
+    let mut dao = Dao::builder().schedule(schedule()).build();
+    let proposal = draft(&mut dao);
+    activate(&mut dao, proposal, None).unwrap();
+
+    assert_eq!(dao.proposal(&proposal).voting_period, 7200);
+    let event = &dao.ctx.events::<ProposalActivated>()[0];
+    assert_eq!((event.voting_period, event.review_period), (7200, REVIEW));
+}
+
+#[test]
+fn voting_period_must_fit_the_governor_bounds() {
+    let mut dao = Dao::builder().schedule(schedule()).build();
+    let proposal = draft(&mut dao);
+
+    assert_error(activate(&mut dao, proposal, Some(3599)), ErrorCode::InvalidVotingPeriod);
+    assert_error(activate(&mut dao, proposal, Some(86_401)), ErrorCode::InvalidVotingPeriod);
+    activate(&mut dao, proposal, Some(86_400)).unwrap();
+}
+
+#[test]
+fn bounds_are_fixed_when_the_proposal_is_created() {
+    let mut dao = Dao::new();
+    let proposal = draft(&mut dao);
+    set_schedule(&mut dao, schedule()).unwrap();
+
+    activate(&mut dao, proposal, Some(60)).unwrap();
+    assert_eq!(dao.proposal(&proposal).voting_period, 60);
+}
+
+#[test]
+fn review_period_delays_voting_and_its_end() {
+    let mut dao = Dao::builder().schedule(schedule()).build();
+    let proposal = draft(&mut dao);
+    activate(&mut dao, proposal, Some(3600)).unwrap();
+    let voter = dao.add_member(0);
+    dao.send(ix::create_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer()), &[&voter]).unwrap();
+
+    let cast = |dao: &mut Dao| {
+        let ix = ix::cast_vote(dao.wallet(), proposal, voter.pubkey(), dao.payer(), VoteSide::For {}, 100, None);
+        dao.send(ix, &[&voter])
+    };
+    assert_error(cast(&mut dao), ErrorCode::VotingNotStarted);
+    dao.ctx.warp(REVIEW);
+    cast(&mut dao).unwrap();
+
+    // Voting closes a full hour after the review, not after activation.
+    dao.ctx.warp(3599);
+    assert_error(dao.finalise(proposal), ErrorCode::VotingPeriodActive);
+    dao.ctx.warp(1);
+    dao.finalise(proposal).unwrap();
+    assert!(dao.proposal(&proposal).state == ProposalState::Succeeded);
+}
+
+/// Voted on for one to two hours and passed at 80% of at least 100 votes.
+fn emergency() -> ProposalCategoryConfig {
+    ProposalCategoryConfig {
+        name: "emergency".to_string(),
+        vote_threshold: 80,
+        quorum: 100,
+        min_voting_period: 3600,
+        max_voting_period: 7200,
+        timelock_delay: 0,
+        programs: vec![system_program::ID],
+        emergency: true,
+    }
+}
+
+fn configure(dao: &mut Dao, config: ProposalCategoryConfig) -> TxResult {
+    dao.send(ix::configure_proposal_category(dao.wallet(), dao.payer(), 0, config), &[])
+}
+
+fn categorised_draft(dao: &mut Dao) -> Result<Pubkey, TxError> {
+    let proposer = dao.add_member(0);
+    dao.try_create_proposal_in(&proposer, Some(0), vec![mock_instruction()])
+}
+
+#[test]
+fn category_periods_must_lie_within_the_schedule() {
+    let mut dao = Dao::builder().schedule(schedule()).build();
+    for config in [
+        ProposalCategoryConfig { min_voting_period: 1, ..emergency() },
+        ProposalCategoryConfig { max_voting_period: 86_401, ..emergency() },
+    ] {
+        assert_error(configure(&mut dao, config), ErrorCode::InvalidProposalCategory);
+    }
+    configure(&mut dao, emergency()).unwrap();
+}
+
+#[test]
+fn a_narrowed_schedule_also_bounds_category_proposals() {
+    let mut dao = Dao::new();
+    configure(&mut dao, ProposalCategoryConfig { min_voting_period: 60, ..emergency() }).unwrap();
+    set_schedule(&mut dao, schedule()).unwrap();
+    let proposal = categorised_draft(&mut dao).unwrap();
+
+    assert_error(activate(&mut dao, proposal, Some(60)), ErrorCode::InvalidVotingPeriod);
+    activate(&mut dao, proposal, Some(3600)).unwrap();
+
+    // A category left with no period inside the schedule takes no proposals.
+    let narrow = VotingSchedule { min_voting_period: 10_800, default_voting_period: 10_800, ..schedule() };
+    set_schedule(&mut dao, narrow).unwrap();
+    assert_error(categorised_draft(&mut dao).map(drop), ErrorCode::InvalidProposalCategory);
+}
+
+#[test]
+fn emergency_proposals_skip_the_review() {
+    let mut dao = Dao::builder().schedule(schedule()).build();
+    configure(&mut dao, emergency()).unwrap();
+    let proposal = categorised_draft(&mut dao).unwrap();
+
+    activate(&mut dao, proposal, Some(3600)).unwrap();
+    assert_eq!(dao.proposal(&proposal).review_period, 0);
+    let voter = dao.add_member(0);
+    dao.vote(proposal, &voter, VoteSide::For {}, 100).unwrap();
+    dao.send(ix::fast_track_proposal(dao.wallet(), proposal), &[]).unwrap();
+}
@@ -0,0 +1,193 @@
+import BN from 'bn.js';
+import { assert } from 'chai';
+
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import { SystemProgram } from '@solana/web3.js';
+
+import { IterativeDao } from '../target/types/iterative_dao';
+
+describe("Vesting Lock Tests (Test Case 11)", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.IterativeDao as Program<IterativeDao>;
+  let smartWallet: anchor.web3.Keypair;
+  let user: anchor.web3.Keypair;
+  let governorPda: anchor.web3.PublicKey;
+  let governanceMint: anchor.web3.PublicKey;
+  let userTokenAccount: anchor.web3.PublicKey;
+  let escrowTokenAccount: anchor.web3.PublicKey;
+  let lockIdCounter: number;
+  const connection = provider.connection;
+
+  beforeEach(async () => {
+    lockIdCounter = 0;
+    smartWallet = anchor.web3.Keypair.generate();
+    user = anchor.web3.Keypair.generate();
+    [governorPda] = await anchor.web3.PublicKey.findProgramAddress(
+      [Buffer.from("governor"), smartWallet.publicKey.toBuffer()],
+      program.programId
+    );
+    governanceMint = await createMint(connection, provider.wallet.payer, user.publicKey, null, 0);
+    userTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(connection, provider.wallet.payer, governanceMint, user.publicKey)
+    ).address;
+    escrowTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(connection, provider.wallet.payer, governanceMint, smartWallet.publicKey)
+    ).address;
+    await mintTo(connection, provider.wallet.payer, governanceMint, userTokenAccount, user, 1000);
+
+    await program.methods
+      .initGovernor(60, new BN(3600), user.publicKey, governanceMint)
+      .accounts({
+        governor: governorPda,
+        smartWallet: smartWallet.publicKey,
+        payer: provider.wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([smartWallet])
+      .rpc();
+    await program.methods
+      .addVoter(user.publicKey, new BN(100))
+      .accounts({
+        governor: governorPda,
+        smartWallet: smartWallet.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([smartWallet])
+      .rpc();
+  });
+
+  async function deriveLockAccountPda(lockId: BN) {
+    const [pda] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("lock"),
+        governorPda.toBuffer(),
+        user.publicKey.toBuffer(),
+        lockId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    return pda;
+  }
+
+  async function lockVesting(lockId: BN, amount: BN, cliff: BN, duration: BN) {
+    const lockPda = await deriveLockAccountPda(lockId);
+    await program.methods
+      .lockVestingTokens(amount, cliff, duration, lockId)
+      .accounts({
+        governor: governorPda,
+        smartWallet: smartWallet.publicKey,
+        user: user.publicKey,
+        userTokenAccount,
+        escrowTokenAccount,
+        lockAccount: lockPda,
+        payer: provider.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([smartWallet, user])
+      .rpc();
+    return lockPda;
+  }
+
+  function claimVested(lockId: BN, lockPda: anchor.web3.PublicKey) {
+    return program.methods
+      .claimVested(lockId)
+      .accounts({
+        lockAccount: lockPda,
+        user: user.publicKey,
+        userTokenAccount,
+        escrowTokenAccount,
+        smartWallet: smartWallet.publicKey,
+        governor: governorPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([smartWallet, user])
+      .rpc();
+  }
+
+  it("Test Case 11.1: Create a vesting lock and verify the cliff and schedule", async () => {
+    lockIdCounter += 1;
+    const lockId = new BN(lockIdCounter);
+    const lockPda = await lockVesting(lockId, new BN(100), new BN(10), new BN(100));
+
+    const lock = await program.account.lockAccount.fetch(lockPda);
+    assert.deepEqual(lock.kind, { vesting: {} }, "Lock should be a vesting lock");
+    assert.equal(lock.cliffTime.sub(lock.startTime).toNumber(), 10, "Cliff should be 10s after start");
+    assert.equal(lock.endTime.sub(lock.startTime).toNumber(), 100, "End should be 100s after start");
+    assert.equal(lock.claimedAmount.toNumber(), 0, "Nothing should be claimed yet");
+    const escrowBal = await getAccount(connection, escrowTokenAccount);
+    assert.equal(escrowBal.amount.toString(), "100");
+  });
+
+  it("Test Case 11.2: Attempt to claim before the cliff and expect failure", async () => {
+    lockIdCounter += 1;
+    const lockId = new BN(lockIdCounter);
+    const lockPda = await lockVesting(lockId, new BN(100), new BN(60), new BN(120));
+    try {
+      await claimVested(lockId, lockPda);
+      assert.fail("Claiming before the cliff should fail");
+    } catch (err: any) {
+      if (err.error?.errorCode?.code) {
+        assert.equal(err.error.errorCode.code, "NothingToClaim");
+      } else {
+        assert.include(err.toString(), "NothingToClaim");
+      }
+    }
+  });
+
+  it("Test Case 11.3: Claim linearly vested tokens after the cliff", async () => {
+    lockIdCounter += 1;
+    const lockId = new BN(lockIdCounter);
+    const lockPda = await lockVesting(lockId, new BN(100), new BN(2), new BN(20));
+    await new Promise(res => setTimeout(res, 5000));
+    await claimVested(lockId, lockPda);
+
+    const lock = await program.account.lockAccount.fetch(lockPda);
+    const claimed = lock.claimedAmount.toNumber();
+    assert.isAbove(claimed, 0, "Some tokens should have vested");
+    assert.isBelow(claimed, 100, "Not all tokens should have vested yet");
+    assert.isFalse(lock.withdrawn, "Lock should still hold unvested tokens");
+    const userBal = await getAccount(connection, userTokenAccount);
+    assert.equal(userBal.amount.toString(), (900 + claimed).toString());
+  });
+
+  it("Test Case 11.4: Attempt to claim from a standard lock and expect failure", async () => {
+    lockIdCounter += 1;
+    const lockId = new BN(lockIdCounter);
+    const lockPda = await deriveLockAccountPda(lockId);
+    await program.methods
+      .lockTokens(new BN(50), new BN(100), lockId)
+      .accounts({
+        governor: governorPda,
+        smartWallet: smartWallet.publicKey,
+        user: user.publicKey,
+        userTokenAccount,
+        escrowTokenAccount,
+        lockAccount: lockPda,
+        payer: provider.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([smartWallet, user])
+      .rpc();
+    try {
+      await claimVested(lockId, lockPda);
+      assert.fail("Claiming from a standard lock should fail");
+    } catch (err: any) {
+      if (err.error?.errorCode?.code) {
+        assert.equal(err.error.errorCode.code, "NotVestingLock");
+      } else {
+        assert.include(err.toString(), "NotVestingLock");
+      }
+    }
+  });
+});
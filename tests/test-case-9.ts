@@ -627,4 +627,45 @@ it("Test Case 9.6: Ensure locked tokens cannot be withdrawn before lock period v
       assert.fail(`Test Case 9.10 failed: ${error.message}`);
     }
   });
+
+  it("Test Case 9.11: Attempt to lock tokens of a non-governance mint => fail", async () => {
+    console.log(">>> Starting Test Case 9.11");
+
+    lockIdCounter += 1;
+    const lockId = new BN(lockIdCounter);
+    const lockAccountPda = await deriveLockAccountPda(governorPda, user.publicKey, lockId);
+    const amount = new BN(10);
+    const duration = new BN(100);
+    console.log("Creating a second, non-governance mint...");
+    const otherMint = await createMint(connection, provider.wallet.payer, user.publicKey, null, 0);
+    const otherUserAcc = await getOrCreateAssociatedTokenAccount(connection, provider.wallet.payer, otherMint, user.publicKey);
+    const otherEscrowAcc = await getOrCreateAssociatedTokenAccount(connection, provider.wallet.payer, otherMint, smartWallet.publicKey);
+    await mintTo(connection, provider.wallet.payer, otherMint, otherUserAcc.address, user, 1000);
+    try {
+      await program.methods
+        .lockTokens(amount, duration, lockId)
+        .accounts({
+          governor: governorPda,
+          smartWallet: smartWallet.publicKey,
+          user: user.publicKey,
+          userTokenAccount: otherUserAcc.address,
+          escrowTokenAccount: otherEscrowAcc.address,
+          lockAccount: lockAccountPda,
+          payer: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([smartWallet, user])
+        .rpc();
+      assert.fail("Locking should have failed due to the wrong mint.");
+    } catch (err: any) {
+      console.error("Expected error in Test Case 9.11:", err);
+      if (err.error && err.error.errorCode && err.error.errorCode.code) {
+        assert.equal(err.error.errorCode.code, "InvalidGovernanceMint", "Expected InvalidGovernanceMint error.");
+      } else {
+        assert.include(err.toString(), "InvalidGovernanceMint", "Expected InvalidGovernanceMint error.");
+      }
+    }
+    console.log("<<< Test Case 9.11 completed successfully.\n");
+  });
 });